@@ -0,0 +1,152 @@
+use axum::{
+    body::{Body, to_bytes},
+    http::{HeaderMap, HeaderValue, Request, StatusCode, header},
+};
+use tower::ServiceExt;
+
+use crate::api::{request_api_key, router};
+use crate::config::{ApiKey, Config, parse_api_keys, scoped};
+use crate::testing::fake_pfm::FakePfmApi;
+
+async fn get(uri: &str) -> (StatusCode, serde_json::Value) {
+    let resp = router()
+        .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+
+    (status, serde_json::from_slice(&body).unwrap())
+}
+
+// status and Retry-After of a request sending `key`, passing the key check ends in a bad request
+async fn get_with_key(key: Option<&str>) -> (StatusCode, Option<String>) {
+    let mut request = Request::get("/api/v1/convert");
+    if let Some(key) = key {
+        request = request.header("x-api-key", key);
+    }
+    let resp = router()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    let retry_after = resp
+        .headers()
+        .get(header::RETRY_AFTER)
+        .map(|value| value.to_str().unwrap().to_string());
+
+    (resp.status(), retry_after)
+}
+
+fn with_keys(keys: &'static str) -> impl FnOnce(&mut Config) {
+    move |config| config.api_keys = parse_api_keys(keys).unwrap()
+}
+
+#[tokio::test]
+async fn invalid_arguments_are_bad_requests() {
+    let (status, body) = get("/api/v1/convert?q=USD%20100").await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Missing ; at the end.")
+    );
+    assert_eq!(8, body["ref"].as_str().unwrap().len());
+
+    let (status, _) = get("/api/v1/convert").await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+
+    let (status, body) = get("/api/v1/forex/USD/IDR?date=2022-13-01").await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+    assert!(
+        body["error"]
+            .as_str()
+            .unwrap()
+            .starts_with("Invalid date 2022-13-01, e.g. 2022-02-02")
+    );
+
+    let (status, _) = get("/api/v1/forex/USDX/IDR").await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+
+    // extra arguments smuggled through an encoded path segment
+    let (status, _) = get("/api/v1/rates/USD%202022-02-02").await;
+    assert_eq!(StatusCode::BAD_REQUEST, status);
+}
+
+#[tokio::test]
+async fn answers_name_their_source() {
+    let pfm = FakePfmApi::start().await;
+
+    let (status, body) = pfm.scope(get("/api/v1/forex/USD/IDR")).await;
+
+    assert_eq!(StatusCode::OK, status);
+    assert_eq!("IDR 15,730.00", body["data"]["code"]);
+    assert_eq!("pfm", body["source"]["source"]);
+    assert_eq!(false, body["source"]["stored"]);
+    assert_eq!(0, body["age_secs"]);
+}
+
+#[tokio::test]
+async fn requests_need_a_known_key() {
+    scoped(with_keys("router-key-1"), async {
+        assert_eq!(StatusCode::UNAUTHORIZED, get_with_key(None).await.0);
+        assert_eq!(StatusCode::UNAUTHORIZED, get_with_key(Some("nope")).await.0);
+        assert_eq!(
+            StatusCode::BAD_REQUEST,
+            get_with_key(Some("router-key-1")).await.0
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn keys_over_their_limit_wait_for_the_next_window() {
+    scoped(with_keys("router-key-2:2"), async {
+        for _ in 0..2 {
+            assert_eq!(
+                (StatusCode::BAD_REQUEST, None),
+                get_with_key(Some("router-key-2")).await
+            );
+        }
+
+        // the window of a minute has only just started
+        assert_eq!(
+            (StatusCode::TOO_MANY_REQUESTS, Some("60".to_string())),
+            get_with_key(Some("router-key-2")).await
+        );
+    })
+    .await;
+}
+
+#[test]
+fn api_key_from_headers() {
+    let mut headers = HeaderMap::new();
+    assert_eq!(None, request_api_key(&headers));
+
+    headers.insert(
+        header::AUTHORIZATION,
+        HeaderValue::from_static("Bearer abc"),
+    );
+    assert_eq!(Some("abc"), request_api_key(&headers));
+
+    headers.insert("x-api-key", HeaderValue::from_static("def"));
+    assert_eq!(Some("def"), request_api_key(&headers));
+}
+
+#[test]
+fn api_keys_with_optional_limits() {
+    assert_eq!(
+        vec![
+            ApiKey {
+                key: "abc".into(),
+                rate_limit: None
+            },
+            ApiKey {
+                key: "def".into(),
+                rate_limit: Some(120)
+            },
+        ],
+        parse_api_keys(" abc, def:120 ,").unwrap()
+    );
+    assert!(parse_api_keys("abc:lots").is_err());
+}
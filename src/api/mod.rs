@@ -0,0 +1,234 @@
+//! api is the public REST API served next to the bot.
+//!
+//! It parses arguments with the same parsers as the commands, and calls upstream through the same cached client.
+use std::time::{Duration, Instant};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, Request},
+    http::{HeaderMap, HeaderValue, StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use crate::api::rate_limit::rate_limiter;
+use crate::commands::Args;
+use crate::config::config;
+use crate::error::{CorrelationId, HandlerError};
+use crate::handlers::convert::{ConvertArg, fetch_convert};
+use crate::handlers::forex::{
+    BaseRatesArg, ForexResp, SinglePairArg, fetch_base_rates, fetch_single_pair,
+};
+use crate::handlers::rate_sources::{SourceInfo, Sourced};
+use crate::i18n::{Lang, Localized};
+
+pub(crate) mod rate_limit;
+
+#[cfg(test)]
+mod api_test;
+
+#[cfg(test)]
+mod rate_limit_test;
+
+static API_KEY_HEADER: &str = "x-api-key";
+
+pub(crate) fn router() -> Router {
+    Router::new()
+        .route("/api/v1/convert", get(convert))
+        .route("/api/v1/forex/:left/:right", get(forex_pair))
+        .route("/api/v1/rates/:base", get(rates))
+        .layer(middleware::from_fn(authorize))
+}
+
+#[derive(Debug, Deserialize)]
+struct ConvertQuery {
+    // same format as /convert, e.g. "USD 100;IDR;2022-02-02"
+    q: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct DateQuery {
+    date: Option<String>,
+}
+
+async fn convert(Query(query): Query<ConvertQuery>) -> Result<Response, ApiError> {
+    let arg = ConvertArg::try_from(Args(query.q.unwrap_or_default()))?;
+
+    Ok(upstream_response(fetch_convert(arg).await?))
+}
+
+async fn forex_pair(
+    Path((left, right)): Path<(String, String)>,
+    Query(query): Query<DateQuery>,
+) -> Result<Response, ApiError> {
+    if !is_code_segment(&left) || !is_code_segment(&right) {
+        return Err(HandlerError::from(Localized::new("forex.pair_format")).into());
+    }
+
+    let arg = SinglePairArg::try_from(args_with_date(format!("{}/{}", left, right), query.date))?;
+
+    Ok(upstream_response(fetch_single_pair(arg).await?))
+}
+
+async fn rates(
+    Path(base): Path<String>,
+    Query(query): Query<DateQuery>,
+) -> Result<Response, ApiError> {
+    if !is_code_segment(&base) {
+        return Err(HandlerError::from(Localized::new("forex.base_format")).into());
+    }
+
+    let arg = BaseRatesArg::try_from(args_with_date(base, query.date))?;
+
+    Ok(upstream_response(fetch_base_rates(arg).await?))
+}
+
+// path segments are decoded, keep them from smuggling extra arguments into the parser
+fn is_code_segment(segment: &str) -> bool {
+    !segment.is_empty() && segment.chars().all(|ch| ch.is_ascii_alphanumeric())
+}
+
+fn args_with_date(head: String, date: Option<String>) -> Args {
+    match date.as_deref().map(str::trim) {
+        Some(date) if !date.is_empty() => Args(format!("{} {}", head, date)),
+        _ => Args(head),
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct SourcedBody<T> {
+    #[serde(flatten)]
+    resp: ForexResp<T>,
+
+    source: SourceInfo,
+    age_secs: u64,
+}
+
+/// Upstream body as is, with the source that answered. Errors reported by upstream inside the body are passed on
+/// as bad gateway.
+fn upstream_response<T: Serialize>(sourced: Sourced<T>) -> Response {
+    let status = if sourced.resp.error.is_some() {
+        StatusCode::BAD_GATEWAY
+    } else {
+        StatusCode::OK
+    };
+    let body = SourcedBody {
+        age_secs: sourced.info.age(Utc::now()).as_secs(),
+        resp: sourced.resp,
+        source: sourced.info,
+    };
+
+    (status, Json(body)).into_response()
+}
+
+async fn authorize(headers: HeaderMap, request: Request, next: Next) -> Result<Response, ApiError> {
+    let config = config();
+    if config.api_keys.is_empty() {
+        return Ok(next.run(request).await);
+    }
+
+    let api_key = request_api_key(&headers)
+        .and_then(|key| config.api_key(key))
+        .ok_or(ApiError::Unauthorized)?;
+
+    rate_limiter()
+        .check(
+            &api_key.key,
+            api_key.rate_limit.unwrap_or(config.api_rate_limit),
+            Instant::now(),
+        )
+        .map_err(ApiError::RateLimited)?;
+
+    Ok(next.run(request).await)
+}
+
+/// Key from `X-API-Key`, or from `Authorization: Bearer <key>`.
+pub(crate) fn request_api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get(API_KEY_HEADER) {
+        return key.to_str().ok().map(str::trim);
+    }
+
+    headers
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+}
+
+#[derive(Debug)]
+pub(crate) enum ApiError {
+    Handler(HandlerError),
+    Unauthorized,
+    RateLimited(Duration),
+}
+
+impl From<HandlerError> for ApiError {
+    fn from(value: HandlerError) -> Self {
+        ApiError::Handler(value)
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ErrorBody {
+    error: String,
+
+    #[serde(rename = "ref", skip_serializing_if = "Option::is_none")]
+    reference: Option<String>,
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        match self {
+            ApiError::Handler(err) => {
+                let status = match err {
+                    HandlerError::InvalidArguments(_) => StatusCode::BAD_REQUEST,
+                    HandlerError::NetworkError(_) | HandlerError::ApiError(_) => {
+                        StatusCode::BAD_GATEWAY
+                    }
+                    HandlerError::UpstreamUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
+                    HandlerError::TelegramError(_) | HandlerError::StorageError(_) => {
+                        StatusCode::INTERNAL_SERVER_ERROR
+                    }
+                };
+
+                let id = CorrelationId::new();
+                eprintln!("[error][{}] api\n{}", id, err.chain());
+
+                let body = ErrorBody {
+                    error: err.user_message(Lang::En),
+                    reference: Some(id.to_string()),
+                };
+
+                (status, Json(body)).into_response()
+            }
+
+            ApiError::Unauthorized => {
+                let body = ErrorBody {
+                    error: "missing or invalid api key".into(),
+                    reference: None,
+                };
+
+                (StatusCode::UNAUTHORIZED, Json(body)).into_response()
+            }
+
+            ApiError::RateLimited(retry_after) => {
+                let body = ErrorBody {
+                    error: "rate limit exceeded".into(),
+                    reference: None,
+                };
+                let retry_after = HeaderValue::from(retry_after.as_secs_f64().ceil() as u64);
+
+                (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, retry_after)],
+                    Json(body),
+                )
+                    .into_response()
+            }
+        }
+    }
+}
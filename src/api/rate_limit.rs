@@ -0,0 +1,56 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+pub(crate) fn rate_limiter() -> &'static RateLimiter {
+    &RATE_LIMITER
+}
+
+static RATE_LIMITER: LazyLock<RateLimiter> =
+    LazyLock::new(|| RateLimiter::new(Duration::from_secs(60)));
+
+/// Fixed window request counter per key.
+pub(crate) struct RateLimiter {
+    window: Duration,
+    windows: Mutex<HashMap<String, Window>>,
+}
+
+struct Window {
+    started_at: Instant,
+    hits: u32,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(window: Duration) -> Self {
+        RateLimiter {
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Count a request of `key`. When it is over `limit` in the current window, returns how long until the next one.
+    /// Limit 0 means unlimited.
+    pub(crate) fn check(&self, key: &str, limit: u32, now: Instant) -> Result<(), Duration> {
+        if limit == 0 {
+            return Ok(());
+        }
+
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+        let window = windows.entry(key.to_string()).or_insert(Window {
+            started_at: now,
+            hits: 0,
+        });
+
+        let elapsed = now.saturating_duration_since(window.started_at);
+        if elapsed >= self.window {
+            window.started_at = now;
+            window.hits = 0;
+        } else if window.hits >= limit {
+            return Err(self.window - elapsed);
+        }
+
+        window.hits += 1;
+
+        Ok(())
+    }
+}
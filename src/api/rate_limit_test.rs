@@ -0,0 +1,32 @@
+use std::time::{Duration, Instant};
+
+use crate::api::rate_limit::RateLimiter;
+
+#[test]
+fn limit_per_key_and_window() {
+    let limiter = RateLimiter::new(Duration::from_secs(60));
+    let now = Instant::now();
+
+    assert_eq!(Ok(()), limiter.check("a", 2, now));
+    assert_eq!(Ok(()), limiter.check("a", 2, now + Duration::from_secs(1)));
+    assert_eq!(
+        Err(Duration::from_secs(50)),
+        limiter.check("a", 2, now + Duration::from_secs(10))
+    );
+
+    // other keys have their own window
+    assert_eq!(Ok(()), limiter.check("b", 2, now + Duration::from_secs(10)));
+
+    // next window
+    assert_eq!(Ok(()), limiter.check("a", 2, now + Duration::from_secs(60)));
+}
+
+#[test]
+fn zero_is_unlimited() {
+    let limiter = RateLimiter::new(Duration::from_secs(60));
+    let now = Instant::now();
+
+    for _ in 0..1000 {
+        assert_eq!(Ok(()), limiter.check("a", 0, now));
+    }
+}
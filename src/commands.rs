@@ -1,8 +1,21 @@
+use std::borrow::Cow;
 use std::fmt::Display;
 use std::str::FromStr;
 
+use teloxide::RequestError;
+use teloxide::prelude::*;
+use teloxide::types::{BotCommand, BotCommandScope, ChatId, Recipient};
 use teloxide::utils::command::BotCommands;
 
+use crate::config::{CommandAlias, config};
+use crate::i18n::{Lang, default_lang, t};
+
+// answered with coming soon for now, see `handlers` in main.rs
+static COMING_SOON: &[&str] = &["pm", "zakat", "stock", "remindme", "cpi"];
+
+// hidden from help, shown in menus of bot admins only
+static ADMIN_COMMANDS: &[&str] = &["admin"];
+
 #[derive(Clone, Debug)]
 pub(crate) struct Args(pub(crate) String);
 
@@ -21,67 +34,193 @@ impl FromStr for Args {
 }
 
 #[derive(BotCommands, Clone)]
-#[command(
-    rename_rule = "lowercase",
-    description = "This is Teknologi Umum Bot written in Rust supporting these commands:"
-)]
+// descriptions live in the i18n catalog under cmd.<name>.description, see `summary`
+#[command(rename_rule = "lowercase")]
 pub(crate) enum Command {
-    #[command(description = "Show this help message")]
-    Help,
-
-    #[command(description = r#"Fetch prices of some moneys.
-Arguments:
-- No arguments: Give list of author chosen rates.
-- Forex pair:
-  - USD/IDR (pair of currency, case insensitive)
-  - USD/IDR 2022-02-02 (YYYY-MM-DD, date is optional)
-- Base rates:
-  - USD (currency as a base, case insensitive)
-  - USD 2022-02-02 (YYYY-MM-DD, date is optional)
-    "#)]
+    Help(Args),
     Forex(Args),
-
-    #[command(description = r#"Convert between 2 currencies.
-Arguments:
-- <FROM_CODE> <FROM_AMOUNT>; <TO_CODE>
-- <FROM_CODE> <FROM_AMOUNT>; <TO_CODE>; <DATE> (optional date in YYYY-MM-DD format)
-- Example: USD 50,000; IDR
-- Example with date: USD 50,000; IDR; 2022-02-02
-- Semicolon separated.
-        "#)]
     Convert(Args),
-
-    #[command(
-        description = r#"Fetch Precious Metals(Gold & Silver) prices in multiple fiat currencies.
-Optional params:
-- Date of rates: YYYY-MM-DD
-    "#
-    )]
-    PM(Args),
-
-    #[command(
-        description = r#"Fetch Zakat information, such current nishab in Gold and Silver.
-Optional params:
-- Amount: your current holding in fiat, and bot will calculate if you reach nishab. E.g. IDR 1,000,000,000.02
-- Date of start: Date of you reach nishab, bot will calculate the end of the year in hijri calendar mapped into Gregory calendar.
-        "#
-    )]
+    Pm(Args),
     Zakat(Args),
-
-    #[command(
-        description = r#"Fetch information of a stock. Default to author picks.
-Optional params:
-- Stock ticker: e.g. BBCA
-        "#
-    )]
     Stock(Args),
-
-    #[command(description = r#"Remind me."#)]
     RemindMe(Args),
+    Cpi(Args),
+    SpongeBob(Args),
+    Uwu(Args),
+    Zalgo(Args),
+    Reverse(Args),
+    Leet(Args),
+    SmallCaps(Args),
+    Vaporwave(Args),
+    Clap(Args),
+    Settings(Args),
+    Stats(Args),
 
-    #[command(description = r#"Consumer Price Index data"#)]
-    CPI(Args),
+    // bot operators only, see config admin_ids
+    #[command(hide)]
+    Admin(Args),
+}
 
-    #[command(description = r#"sPoNgEbOb"#)]
-    SpongeBob(Args),
+impl Command {
+    /// Command name without slash, as recorded in usage stats.
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            Command::Help(_) => "help",
+            Command::Forex(_) => "forex",
+            Command::Convert(_) => "convert",
+            Command::Pm(_) => "pm",
+            Command::Zakat(_) => "zakat",
+            Command::Stock(_) => "stock",
+            Command::RemindMe(_) => "remindme",
+            Command::Cpi(_) => "cpi",
+            Command::SpongeBob(_) => "spongebob",
+            Command::Uwu(_) => "uwu",
+            Command::Zalgo(_) => "zalgo",
+            Command::Reverse(_) => "reverse",
+            Command::Leet(_) => "leet",
+            Command::SmallCaps(_) => "smallcaps",
+            Command::Vaporwave(_) => "vaporwave",
+            Command::Clap(_) => "clap",
+            Command::Settings(_) => "settings",
+            Command::Stats(_) => "stats",
+            Command::Admin(_) => "admin",
+        }
+    }
+
+    /// Whether an edited command runs again. Admin actions, like a broadcast, aren't repeated by fixing a typo.
+    pub(crate) fn reruns_on_edit(&self) -> bool {
+        !matches!(self, Command::Admin(_))
+    }
+}
+
+/// Commands shown in help, without the hidden ones.
+pub(crate) fn listed_commands() -> Vec<String> {
+    Command::bot_commands()
+        .into_iter()
+        .map(|cmd| cmd.command.trim_start_matches('/').to_string())
+        .collect()
+}
+
+/// Command of a message text, also when named by an alias, e.g. `/kurs@kartel_bot USD` for `/forex`.
+pub(crate) fn parse(text: &str, bot_name: &str) -> Option<Command> {
+    Command::parse(&resolve_alias(text, &config().command_aliases), bot_name).ok()
+}
+
+/// `text` with an aliased command name replaced by the command it stands for, bot mention and arguments kept.
+pub(crate) fn resolve_alias<'a>(text: &'a str, aliases: &[CommandAlias]) -> Cow<'a, str> {
+    let Some(rest) = text.strip_prefix('/') else {
+        return Cow::Borrowed(text);
+    };
+    let end = rest
+        .find(|c: char| c.is_whitespace() || c == '@')
+        .unwrap_or(rest.len());
+    let name = rest[..end].to_ascii_lowercase();
+
+    // commands can't be shadowed by an alias of the same name
+    if is_command(&name) {
+        return Cow::Borrowed(text);
+    }
+
+    match aliases.iter().find(|alias| alias.alias == name) {
+        Some(alias) => Cow::Owned(format!("/{}{}", alias.command, &rest[end..])),
+        None => Cow::Borrowed(text),
+    }
+}
+
+/// Configured aliases of a command, e.g. `kurs` and `fx` of `forex`.
+pub(crate) fn aliases_of(name: &str) -> Vec<String> {
+    config()
+        .command_aliases
+        .iter()
+        .filter(|alias| alias.command == name && !is_command(&alias.alias))
+        .map(|alias| alias.alias.clone())
+        .collect()
+}
+
+/// Command name without slash, when `name` is one, alias or not.
+pub(crate) fn resolve_name(name: &str) -> String {
+    let name = name.trim_start_matches('/').to_ascii_lowercase();
+    match resolve_alias(&format!("/{}", name), &config().command_aliases) {
+        Cow::Owned(resolved) => resolved.trim_start_matches('/').to_string(),
+        Cow::Borrowed(_) => name,
+    }
+}
+
+/// Whether `name` is a command of this bot, hidden ones included, not an alias.
+pub(crate) fn is_command(name: &str) -> bool {
+    ADMIN_COMMANDS.contains(&name) || listed_commands().iter().any(|listed| listed == name)
+}
+
+pub(crate) fn is_coming_soon(name: &str) -> bool {
+    COMING_SOON.contains(&name)
+}
+
+/// First line of a command description, e.g. `Convert between 2 currencies.`
+pub(crate) fn summary(lang: Lang, name: &str) -> String {
+    let key = format!("cmd.{}.description", name);
+    t(lang, &key).lines().next().unwrap_or_default().to_string()
+}
+
+/// Menu of implemented commands, with admin ones for bot admins.
+pub(crate) fn menu(lang: Lang, admin: bool) -> Vec<BotCommand> {
+    let admin_commands = ADMIN_COMMANDS
+        .iter()
+        .filter(|_| admin)
+        .map(|name| name.to_string());
+
+    listed_commands()
+        .into_iter()
+        .filter(|name| !is_coming_soon(name))
+        .chain(admin_commands)
+        .map(|name| {
+            let description = summary(lang, &name);
+            BotCommand::new(name, description)
+        })
+        .collect()
+}
+
+/// Publish command menus to Telegram: default, private and group chats, then private chats of each bot admin.
+/// Menus of admins in `removed_admins` are deleted, so they fall back to the private chats menu.
+pub(crate) async fn publish_menus(
+    bot: &Bot,
+    admins: &[u64],
+    removed_admins: &[u64],
+) -> Result<(), RequestError> {
+    let admin_scope = |id: u64| BotCommandScope::Chat {
+        chat_id: Recipient::Id(ChatId(id as i64)),
+    };
+    let scopes = [
+        BotCommandScope::Default,
+        BotCommandScope::AllPrivateChats,
+        BotCommandScope::AllGroupChats,
+    ];
+
+    for (scope, admin) in scopes
+        .into_iter()
+        .map(|scope| (scope, false))
+        .chain(admins.iter().map(|id| (admin_scope(*id), true)))
+    {
+        // without a language code for clients in other languages
+        bot.set_my_commands(menu(default_lang(), admin))
+            .scope(scope.clone())
+            .await?;
+        for lang in Lang::ALL {
+            bot.set_my_commands(menu(lang, admin))
+                .scope(scope.clone())
+                .language_code(lang.code())
+                .await?;
+        }
+    }
+
+    for id in removed_admins.iter().filter(|id| !admins.contains(id)) {
+        bot.delete_my_commands().scope(admin_scope(*id)).await?;
+        for lang in Lang::ALL {
+            bot.delete_my_commands()
+                .scope(admin_scope(*id))
+                .language_code(lang.code())
+                .await?;
+        }
+    }
+
+    Ok(())
 }
@@ -0,0 +1,126 @@
+use serde_json::Value;
+
+use crate::commands::{aliases_of, menu, publish_menus, resolve_alias, resolve_name};
+use crate::config::parse_command_aliases;
+use crate::i18n::Lang;
+use crate::testing::fake_bot::FakeBotApi;
+
+fn names(commands: &Value) -> Vec<&str> {
+    commands
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|command| command["command"].as_str().unwrap())
+        .collect()
+}
+
+#[test]
+fn menus_leave_out_unfinished_and_hidden_commands() {
+    let public: Vec<String> = menu(Lang::En, false)
+        .into_iter()
+        .map(|command| command.command)
+        .collect();
+    assert_eq!(
+        vec![
+            "help",
+            "forex",
+            "convert",
+            "spongebob",
+            "uwu",
+            "zalgo",
+            "reverse",
+            "leet",
+            "smallcaps",
+            "vaporwave",
+            "clap",
+            "settings",
+            "stats"
+        ],
+        public
+    );
+
+    let admin = menu(Lang::En, true);
+    assert_eq!("admin", admin.last().unwrap().command);
+
+    // descriptions are the first line only
+    let convert = &menu(Lang::Id, false)[2];
+    assert_eq!("Konversi antara 2 mata uang.", convert.description);
+}
+
+#[test]
+fn aliases_stand_for_commands() {
+    let aliases = parse_command_aliases(" kurs:forex, /C:Convert,help:forex ,").unwrap();
+    assert_eq!(3, aliases.len());
+    assert_eq!("c", aliases[1].alias);
+    assert_eq!("convert", aliases[1].command);
+    assert!(parse_command_aliases("kurs").is_err());
+    assert!(parse_command_aliases("ku rs:forex").is_err());
+    assert!(parse_command_aliases("kurs:forx").is_err());
+    assert!(parse_command_aliases("panel:admin").is_ok());
+
+    assert_eq!("/forex USD/IDR", resolve_alias("/kurs USD/IDR", &aliases));
+    assert_eq!(
+        "/forex@kartel_bot",
+        resolve_alias("/KURS@kartel_bot", &aliases)
+    );
+    assert_eq!(
+        "/convert\n100 usd; idr",
+        resolve_alias("/c\n100 usd; idr", &aliases)
+    );
+    // commands win over aliases of the same name
+    assert_eq!("/help forex", resolve_alias("/help forex", &aliases));
+    assert_eq!("/kursus", resolve_alias("/kursus", &aliases));
+    assert_eq!("kurs", resolve_alias("kurs", &aliases));
+}
+
+#[test]
+fn default_aliases() {
+    assert_eq!(vec!["kurs", "fx"], aliases_of("forex"));
+    assert_eq!(vec!["c"], aliases_of("convert"));
+    assert_eq!("pm", resolve_name("/Emas"));
+    assert_eq!("remindme", resolve_name("pengingat"));
+    assert_eq!("convert", resolve_name("convert"));
+}
+
+#[tokio::test]
+async fn menus_are_published_per_scope_and_language() {
+    let api = FakeBotApi::start().await;
+
+    publish_menus(&api.bot(), &[42], &[42, 7]).await.unwrap();
+
+    let calls = api.calls_to("setMyCommands");
+    // default, private, group and one admin scope, each without language, then in en and id
+    assert_eq!(12, calls.len());
+
+    let scopes: Vec<&str> = calls
+        .iter()
+        .step_by(3)
+        .map(|call| call.body["scope"]["type"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        vec!["default", "all_private_chats", "all_group_chats", "chat"],
+        scopes
+    );
+
+    assert_eq!(Value::Null, calls[0].body["language_code"]);
+    assert_eq!("en", calls[1].body["language_code"]);
+    assert_eq!("id", calls[2].body["language_code"]);
+    assert_eq!(
+        "Tampilkan pesan bantuan ini, atau rincian sebuah perintah",
+        calls[2].body["commands"][0]["description"]
+    );
+
+    assert!(!names(&calls[0].body["commands"]).contains(&"admin"));
+    assert!(!names(&calls[0].body["commands"]).contains(&"zakat"));
+    assert_eq!(42, calls[9].body["scope"]["chat_id"]);
+    assert!(names(&calls[9].body["commands"]).contains(&"admin"));
+
+    // admins no longer listed lose their menu
+    let deleted = api.calls_to("deleteMyCommands");
+    assert_eq!(3, deleted.len());
+    assert!(
+        deleted
+            .iter()
+            .all(|call| call.body["scope"]["chat_id"] == 7)
+    );
+}
@@ -1,21 +1,73 @@
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock, RwLock};
 
-use configrs::config as configrs;
-use serde::Deserialize;
+use ::configrs::config::{self as configrs, ConfigError};
+use serde::{Deserialize, Deserializer};
 
-pub fn config() -> &'static Config {
-    &CONFIG
+use crate::commands::is_command;
+
+pub fn config() -> Arc<Config> {
+    // tests running inside `scoped` see their own config
+    #[cfg(test)]
+    if let Ok(scoped) = SCOPED.try_with(Arc::clone) {
+        return scoped;
+    }
+
+    CONFIG.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+#[cfg(test)]
+tokio::task_local! {
+    static SCOPED: Arc<Config>;
+}
+
+/// Run `fut` with `edit` applied to the loaded config, without touching other tests.
+#[cfg(test)]
+pub(crate) async fn scoped<F: Future>(edit: impl FnOnce(&mut Config), fut: F) -> F::Output {
+    let mut scoped = (*config()).clone();
+    edit(&mut scoped);
+
+    SCOPED.scope(Arc::new(scoped), fut).await
 }
 
-static CONFIG: LazyLock<Config> = LazyLock::new(|| {
-    configrs::Config::new()
-        .with_env_prefix(ENV_PREFIX)
-        .build::<Config>()
-        .expect("failed initializing config")
-});
+/// Re-read configs from env vars and the optional config file.
+/// Bot token and ports are already bound, changing them only takes effect on restart.
+pub fn reload_config() -> Result<Arc<Config>, ConfigError> {
+    let new_config = Arc::new(load_config()?);
+    *CONFIG.write().unwrap_or_else(|e| e.into_inner()) = new_config.clone();
+
+    Ok(new_config)
+}
+
+static CONFIG: LazyLock<RwLock<Arc<Config>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(load_config().expect("failed initializing config"))));
 
 const ENV_PREFIX: &str = "KARTEL_";
 
+// path to optional .env formatted file, which can be edited then reloaded at runtime
+const CONFIG_FILE_ENV: &str = "KARTEL_CONFIG_FILE";
+
+fn load_config() -> Result<Config, ConfigError> {
+    let builder = configrs::Config::new().with_env_prefix(ENV_PREFIX);
+
+    // values from the file win over process env, otherwise reload would never see edits
+    let builder = match std::env::var(CONFIG_FILE_ENV) {
+        Ok(path) => builder.with_overwrite().with_env(path),
+        Err(_) => builder,
+    };
+
+    // tests never bind servers, ports only need to be present. Retries of fake upstreams needn't wait,
+    // and real upstreams are never called.
+    #[cfg(test)]
+    let builder = builder
+        .with_value("KARTEL_WEBHOOK_PORT", 0)
+        .with_value("KARTEL_API_PORT", 0)
+        .with_value("KARTEL_HTTP_BACKOFF_BASE_MS", 5)
+        .with_value("KARTEL_FOREX_FALLBACK_URL", "")
+        .with_value("KARTEL_CRYPTO_API_URL", "");
+
+    builder.build::<Config>()
+}
+
 #[derive(Debug, Clone, Deserialize)]
 pub(crate) struct Config {
     #[serde(alias = "KARTEL_BOT_TOKEN", default)]
@@ -26,4 +78,352 @@ pub(crate) struct Config {
 
     #[serde(alias = "KARTEL_API_PORT")]
     pub api_port: u16,
+
+    // chat receiving full error reports, e.g. a private group of maintainers
+    #[serde(alias = "KARTEL_ADMIN_CHAT_ID", default)]
+    pub admin_chat_id: Option<i64>,
+
+    // telegram user ids allowed to run /admin, comma separated
+    #[serde(
+        alias = "KARTEL_ADMIN_IDS",
+        default,
+        deserialize_with = "deserialize_id_list"
+    )]
+    pub admin_ids: Vec<u64>,
+
+    #[serde(alias = "KARTEL_DATABASE_PATH", default = "default_database_path")]
+    pub database_path: String,
+
+    // language used when neither chat setting nor user's telegram language is known
+    #[serde(alias = "KARTEL_DEFAULT_LANG", default = "default_lang")]
+    pub default_lang: String,
+
+    // IANA timezone of chats without one set, times are shown and dates typed in it
+    #[serde(alias = "KARTEL_DEFAULT_TIMEZONE", default = "default_timezone")]
+    pub default_timezone: String,
+
+    // base url of pfm forex API, endpoints like convert and rates are under it
+    #[serde(alias = "KARTEL_FOREX_API_URL", default = "default_forex_api_url")]
+    pub forex_api_url: String,
+
+    // frankfurter API asked when the pfm API fails, empty to go straight to stored rates
+    #[serde(
+        alias = "KARTEL_FOREX_FALLBACK_URL",
+        default = "default_forex_fallback_url"
+    )]
+    pub forex_fallback_url: String,
+
+    // currency "dollar", "buck" or "$" stand for
+    #[serde(alias = "KARTEL_DEFAULT_DOLLAR", default = "default_dollar")]
+    pub default_dollar: String,
+
+    // coingecko API asked for crypto pairs the pfm API can't answer, empty to disable
+    #[serde(alias = "KARTEL_CRYPTO_API_URL", default = "default_crypto_api_url")]
+    pub crypto_api_url: String,
+
+    // timeout of a single upstream request attempt
+    #[serde(
+        alias = "KARTEL_HTTP_TIMEOUT_SECS",
+        default = "default_http_timeout_secs"
+    )]
+    pub http_timeout_secs: u64,
+
+    // extra attempts of idempotent upstream requests failing transiently
+    #[serde(alias = "KARTEL_HTTP_RETRIES", default = "default_http_retries")]
+    pub http_retries: u32,
+
+    // first retry waits around this long, doubling on each next one up to http_backoff_max_ms
+    #[serde(
+        alias = "KARTEL_HTTP_BACKOFF_BASE_MS",
+        default = "default_http_backoff_base_ms"
+    )]
+    pub http_backoff_base_ms: u64,
+
+    // also the longest Retry-After of upstream that is honoured, longer ones are not retried
+    #[serde(
+        alias = "KARTEL_HTTP_BACKOFF_MAX_MS",
+        default = "default_http_backoff_max_ms"
+    )]
+    pub http_backoff_max_ms: u64,
+
+    // consecutive failures of an upstream host before requests to it fail fast
+    #[serde(
+        alias = "KARTEL_HTTP_BREAKER_THRESHOLD",
+        default = "default_http_breaker_threshold"
+    )]
+    pub http_breaker_threshold: u32,
+
+    // how long requests to a failing host fail fast before one is let through to probe it
+    #[serde(
+        alias = "KARTEL_HTTP_BREAKER_COOLDOWN_SECS",
+        default = "default_http_breaker_cooldown_secs"
+    )]
+    pub http_breaker_cooldown_secs: u64,
+
+    // how long latest rates are served from cache, historical rates are cached longer
+    #[serde(alias = "KARTEL_CACHE_TTL_SECS", default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+
+    // days covered by /stats
+    #[serde(
+        alias = "KARTEL_STATS_WINDOW_DAYS",
+        default = "default_stats_window_days"
+    )]
+    pub stats_window_days: u32,
+
+    // raw command events older than this are rolled up into daily counts
+    #[serde(
+        alias = "KARTEL_STATS_RETENTION_DAYS",
+        default = "default_stats_retention_days"
+    )]
+    pub stats_retention_days: u32,
+
+    // daily counts older than this are deleted
+    #[serde(
+        alias = "KARTEL_STATS_HISTORY_DAYS",
+        default = "default_stats_history_days"
+    )]
+    pub stats_history_days: u32,
+
+    // keys allowed to call the REST API, comma separated, each optionally with its own
+    // per minute limit as `key:limit`. The API is open when empty.
+    #[serde(
+        alias = "KARTEL_API_KEYS",
+        default,
+        deserialize_with = "deserialize_api_keys"
+    )]
+    pub api_keys: Vec<ApiKey>,
+
+    // REST API requests per minute per key, 0 for unlimited
+    #[serde(alias = "KARTEL_API_RATE_LIMIT", default = "default_api_rate_limit")]
+    pub api_rate_limit: u32,
+
+    // anyone in the chat may press buttons under forex and convert replies, not only who asked
+    #[serde(alias = "KARTEL_BUTTONS_ANYONE", default)]
+    pub buttons_anyone: bool,
+
+    // other names of commands as `alias:command`, comma separated, e.g. kurs:forex,c:convert.
+    // Replaces the default list when set.
+    #[serde(
+        alias = "KARTEL_COMMAND_ALIASES",
+        default = "default_command_aliases",
+        deserialize_with = "deserialize_command_aliases"
+    )]
+    pub command_aliases: Vec<CommandAlias>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct ApiKey {
+    pub key: String,
+
+    // overrides `api_rate_limit` for this key
+    pub rate_limit: Option<u32>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommandAlias {
+    pub alias: String,
+
+    // command name the alias stands for, without slash
+    pub command: String,
+}
+
+impl Config {
+    pub(crate) fn is_admin(&self, user_id: u64) -> bool {
+        self.admin_ids.contains(&user_id)
+    }
+
+    pub(crate) fn api_key(&self, key: &str) -> Option<&ApiKey> {
+        self.api_keys.iter().find(|api_key| api_key.key == key)
+    }
+}
+
+fn default_database_path() -> String {
+    "kartel.db".into()
+}
+
+fn default_lang() -> String {
+    "en".into()
+}
+
+fn default_timezone() -> String {
+    "Asia/Jakarta".into()
+}
+
+fn default_forex_api_url() -> String {
+    "https://api.mfirhas.com/pfm/v2/forex".into()
+}
+
+fn default_forex_fallback_url() -> String {
+    "https://api.frankfurter.app".into()
+}
+
+fn default_dollar() -> String {
+    "USD".into()
+}
+
+fn default_crypto_api_url() -> String {
+    "https://api.coingecko.com/api/v3".into()
+}
+
+fn default_http_timeout_secs() -> u64 {
+    10
+}
+
+fn default_http_retries() -> u32 {
+    2
+}
+
+fn default_http_backoff_base_ms() -> u64 {
+    200
+}
+
+fn default_http_backoff_max_ms() -> u64 {
+    5000
+}
+
+fn default_http_breaker_threshold() -> u32 {
+    5
+}
+
+fn default_http_breaker_cooldown_secs() -> u64 {
+    30
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    60
+}
+
+fn default_stats_window_days() -> u32 {
+    14
+}
+
+fn default_stats_retention_days() -> u32 {
+    30
+}
+
+fn default_stats_history_days() -> u32 {
+    365
+}
+
+fn default_api_rate_limit() -> u32 {
+    60
+}
+
+fn default_command_aliases() -> Vec<CommandAlias> {
+    parse_command_aliases("kurs:forex,fx:forex,c:convert,emas:pm,pengingat:remindme")
+        .expect("failed parsing default command aliases")
+}
+
+/// Env parsing turns a single id into a number and several ids into a string, accept both.
+fn deserialize_id_list<'de, D>(deserializer: D) -> Result<Vec<u64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Ids {
+        Single(u64),
+        List(String),
+    }
+
+    match Ids::deserialize(deserializer)? {
+        Ids::Single(id) => Ok(vec![id]),
+        Ids::List(list) => list
+            .split(',')
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .map(|id| {
+                id.parse::<u64>()
+                    .map_err(|e| serde::de::Error::custom(format!("invalid id {}: {}", id, e)))
+            })
+            .collect(),
+    }
+}
+
+/// Like ids, a single all-digit key is parsed as a number.
+fn deserialize_api_keys<'de, D>(deserializer: D) -> Result<Vec<ApiKey>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Keys {
+        Single(u64),
+        List(String),
+    }
+
+    let list = match Keys::deserialize(deserializer)? {
+        Keys::Single(key) => key.to_string(),
+        Keys::List(list) => list,
+    };
+
+    parse_api_keys(&list).map_err(serde::de::Error::custom)
+}
+
+pub(crate) fn parse_api_keys(list: &str) -> Result<Vec<ApiKey>, String> {
+    list.split(',')
+        .map(str::trim)
+        .filter(|key| !key.is_empty())
+        .map(|key| match key.split_once(':') {
+            Some((key, limit)) => limit
+                .trim()
+                .parse::<u32>()
+                .map(|limit| ApiKey {
+                    key: key.trim().to_string(),
+                    rate_limit: Some(limit),
+                })
+                .map_err(|e| format!("invalid api key rate limit {:?}: {}", limit, e)),
+            None => Ok(ApiKey {
+                key: key.to_string(),
+                rate_limit: None,
+            }),
+        })
+        .collect()
+}
+
+fn deserialize_command_aliases<'de, D>(deserializer: D) -> Result<Vec<CommandAlias>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let list = String::deserialize(deserializer)?;
+    parse_command_aliases(&list).map_err(serde::de::Error::custom)
+}
+
+/// Aliases like `kurs:forex,c:convert`. Names are lowercased, Telegram only takes `a-z`, digits and `_`.
+/// Aliases of commands the bot doesn't have are refused, so a typo fails at startup instead of going unnoticed.
+pub(crate) fn parse_command_aliases(list: &str) -> Result<Vec<CommandAlias>, String> {
+    let is_name = |name: &str| {
+        !name.is_empty()
+            && name.len() <= 32
+            && name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+    };
+
+    list.split(',')
+        .map(str::trim)
+        .filter(|alias| !alias.is_empty())
+        .map(|alias| {
+            let (name, command) = alias.split_once(':').ok_or_else(|| {
+                format!("invalid command alias {:?}, expected alias:command", alias)
+            })?;
+            let name = name.trim().trim_start_matches('/').to_ascii_lowercase();
+            let command = command.trim().trim_start_matches('/').to_ascii_lowercase();
+            if !is_name(&name) || !is_name(&command) {
+                return Err(format!("invalid command alias {:?}", alias));
+            }
+            if !is_command(&command) {
+                return Err(format!(
+                    "invalid command alias {:?}, no command named {:?}",
+                    alias, command
+                ));
+            }
+
+            Ok(CommandAlias {
+                alias: name,
+                command,
+            })
+        })
+        .collect()
 }
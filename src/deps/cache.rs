@@ -0,0 +1,100 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+pub(crate) fn response_cache() -> &'static Cache {
+    &RESPONSE_CACHE
+}
+
+static RESPONSE_CACHE: LazyLock<Cache> = LazyLock::new(|| Cache::new(MAX_RESPONSE_ENTRIES));
+
+const MAX_RESPONSE_ENTRIES: usize = 1024;
+
+/// Small in-memory cache with per entry expiry, used for upstream response bodies.
+pub(crate) struct Cache {
+    entries: Mutex<HashMap<String, Entry>>,
+    max_entries: usize,
+}
+
+struct Entry {
+    inserted_at: Instant,
+    expires_at: Instant,
+    value: String,
+}
+
+impl Cache {
+    pub(crate) fn new(max_entries: usize) -> Self {
+        Cache {
+            entries: Mutex::new(HashMap::new()),
+            max_entries,
+        }
+    }
+
+    fn entries(&self) -> MutexGuard<'_, HashMap<String, Entry>> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn get(&self, key: &str) -> Option<String> {
+        self.get_with_age(key).map(|(value, _)| value)
+    }
+
+    /// Fresh value with how long ago it was inserted.
+    pub(crate) fn get_with_age(&self, key: &str) -> Option<(String, Duration)> {
+        let mut entries = self.entries();
+        let now = Instant::now();
+
+        match entries.get(key) {
+            Some(entry) if entry.expires_at > now => Some((
+                entry.value.clone(),
+                now.saturating_duration_since(entry.inserted_at),
+            )),
+            Some(_) => {
+                entries.remove(key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    pub(crate) fn insert(&self, key: String, value: String, ttl: Duration) {
+        let mut entries = self.entries();
+        let now = Instant::now();
+
+        if entries.len() >= self.max_entries && !entries.contains_key(&key) {
+            entries.retain(|_, entry| entry.expires_at > now);
+        }
+
+        // still full of fresh entries, drop the one closest to expiring
+        if entries.len() >= self.max_entries
+            && !entries.contains_key(&key)
+            && let Some(oldest) = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.expires_at)
+                .map(|(k, _)| k.clone())
+        {
+            entries.remove(&oldest);
+        }
+
+        entries.insert(
+            key,
+            Entry {
+                inserted_at: now,
+                expires_at: now + ttl,
+                value,
+            },
+        );
+    }
+
+    /// Returns number of removed entries.
+    pub(crate) fn clear(&self) -> usize {
+        let mut entries = self.entries();
+        let len = entries.len();
+        entries.clear();
+
+        len
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries().len()
+    }
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+
+use crate::deps::cache::Cache;
+
+#[test]
+fn get_fresh_and_expired() {
+    let cache = Cache::new(8);
+
+    cache.insert("a".into(), "1".into(), Duration::from_secs(60));
+    cache.insert("b".into(), "2".into(), Duration::ZERO);
+
+    assert_eq!(Some("1".to_string()), cache.get("a"));
+    assert_eq!(None, cache.get("b"));
+    assert_eq!(None, cache.get("c"));
+    assert_eq!(1, cache.len());
+}
+
+#[test]
+fn evicts_closest_to_expiring_when_full() {
+    let cache = Cache::new(2);
+
+    cache.insert("short".into(), "1".into(), Duration::from_secs(10));
+    cache.insert("long".into(), "2".into(), Duration::from_secs(100));
+    cache.insert("new".into(), "3".into(), Duration::from_secs(50));
+
+    assert_eq!(2, cache.len());
+    assert_eq!(None, cache.get("short"));
+    assert_eq!(Some("2".to_string()), cache.get("long"));
+    assert_eq!(Some("3".to_string()), cache.get("new"));
+}
+
+#[test]
+fn clear_returns_removed_count() {
+    let cache = Cache::new(8);
+
+    cache.insert("a".into(), "1".into(), Duration::from_secs(60));
+    cache.insert("b".into(), "2".into(), Duration::from_secs(60));
+
+    assert_eq!(2, cache.clear());
+    assert_eq!(0, cache.len());
+}
@@ -0,0 +1,105 @@
+use std::collections::HashMap;
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+pub(crate) fn circuit_breakers() -> &'static CircuitBreakers {
+    &CIRCUIT_BREAKERS
+}
+
+static CIRCUIT_BREAKERS: LazyLock<CircuitBreakers> = LazyLock::new(CircuitBreakers::new);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum BreakerState {
+    Closed,
+
+    // failing fast since the instant
+    Open(Instant),
+
+    // one probe request is out since the instant
+    HalfOpen(Instant),
+}
+
+/// Consecutive failure counter per upstream host.
+///
+/// A host failing `threshold` times in a row opens its circuit, requests to it fail fast for `cooldown`.
+/// After that one request is let through, its success closes the circuit and its failure opens it again.
+pub(crate) struct CircuitBreakers {
+    hosts: Mutex<HashMap<String, Breaker>>,
+}
+
+struct Breaker {
+    state: BreakerState,
+    failures: u32,
+}
+
+impl CircuitBreakers {
+    pub(crate) fn new() -> Self {
+        CircuitBreakers {
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether a request to `host` may go out.
+    pub(crate) fn allow(&self, host: &str, cooldown: Duration, now: Instant) -> bool {
+        let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        let Some(breaker) = hosts.get_mut(host) else {
+            return true;
+        };
+
+        match breaker.state {
+            BreakerState::Closed => true,
+            // a probe that never reported back doesn't keep the circuit stuck
+            BreakerState::Open(since) | BreakerState::HalfOpen(since)
+                if now.saturating_duration_since(since) >= cooldown =>
+            {
+                eprintln!("[http] circuit of {} half-open, probing", host);
+                breaker.state = BreakerState::HalfOpen(now);
+                true
+            }
+            BreakerState::Open(_) | BreakerState::HalfOpen(_) => false,
+        }
+    }
+
+    pub(crate) fn record_success(&self, host: &str) {
+        let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(breaker) = hosts.remove(host)
+            && breaker.state != BreakerState::Closed
+        {
+            eprintln!("[http] circuit of {} closed", host);
+        }
+    }
+
+    pub(crate) fn record_failure(&self, host: &str, threshold: u32, now: Instant) {
+        let mut hosts = self.hosts.lock().unwrap_or_else(|e| e.into_inner());
+        let breaker = hosts.entry(host.to_string()).or_insert(Breaker {
+            state: BreakerState::Closed,
+            failures: 0,
+        });
+        breaker.failures += 1;
+
+        match breaker.state {
+            BreakerState::Closed if breaker.failures >= threshold.max(1) => {
+                eprintln!(
+                    "[http] circuit of {} opened after {} consecutive failures",
+                    host, breaker.failures
+                );
+                breaker.state = BreakerState::Open(now);
+            }
+            BreakerState::HalfOpen(_) => {
+                eprintln!("[http] circuit of {} opened again, probe failed", host);
+                breaker.state = BreakerState::Open(now);
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn state(&self, host: &str) -> BreakerState {
+        self.hosts
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(host)
+            .map(|breaker| breaker.state)
+            .unwrap_or(BreakerState::Closed)
+    }
+}
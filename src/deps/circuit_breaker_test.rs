@@ -0,0 +1,50 @@
+use std::time::{Duration, Instant};
+
+use crate::deps::circuit_breaker::{BreakerState, CircuitBreakers};
+
+const COOLDOWN: Duration = Duration::from_secs(30);
+
+#[test]
+fn opens_after_consecutive_failures() {
+    let breakers = CircuitBreakers::new();
+    let now = Instant::now();
+
+    breakers.record_failure("a:443", 3, now);
+    breakers.record_failure("a:443", 3, now);
+    breakers.record_success("a:443");
+    breakers.record_failure("a:443", 3, now);
+    breakers.record_failure("a:443", 3, now);
+    assert_eq!(BreakerState::Closed, breakers.state("a:443"));
+    assert!(breakers.allow("a:443", COOLDOWN, now));
+
+    breakers.record_failure("a:443", 3, now);
+    assert_eq!(BreakerState::Open(now), breakers.state("a:443"));
+    assert!(!breakers.allow("a:443", COOLDOWN, now + Duration::from_secs(29)));
+
+    // other hosts are not affected
+    assert!(breakers.allow("b:443", COOLDOWN, now));
+}
+
+#[test]
+fn half_open_probe() {
+    let breakers = CircuitBreakers::new();
+    let now = Instant::now();
+    breakers.record_failure("a:443", 1, now);
+
+    // one probe after cooldown, others keep failing fast
+    let later = now + COOLDOWN;
+    assert!(breakers.allow("a:443", COOLDOWN, later));
+    assert_eq!(BreakerState::HalfOpen(later), breakers.state("a:443"));
+    assert!(!breakers.allow("a:443", COOLDOWN, later));
+
+    // failed probe opens again
+    breakers.record_failure("a:443", 1, later);
+    assert_eq!(BreakerState::Open(later), breakers.state("a:443"));
+
+    // successful probe closes
+    let even_later = later + COOLDOWN;
+    assert!(breakers.allow("a:443", COOLDOWN, even_later));
+    breakers.record_success("a:443");
+    assert_eq!(BreakerState::Closed, breakers.state("a:443"));
+    assert!(breakers.allow("a:443", COOLDOWN, even_later));
+}
@@ -1,8 +1,18 @@
 use anyhow::Context;
 use anyhow::Result;
-use reqwest::Client;
+use chrono::{DateTime, Utc};
+use rand::Rng as _;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{Client, Method, Request, Response, StatusCode};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
 use std::sync::LazyLock;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+
+use crate::config::config;
+use crate::deps::cache::response_cache;
+use crate::deps::circuit_breaker::circuit_breakers;
+use crate::error::{HandlerError, IntoInternalError};
 
 pub(crate) fn http_client() -> Client {
     HTTP_CLIENT.clone()
@@ -11,13 +21,198 @@ pub(crate) fn http_client() -> Client {
 static HTTP_CLIENT: LazyLock<Client> =
     LazyLock::new(|| init_http_client().expect("global static init http client"));
 
+// timeouts are set per request in `send`, so they follow config reloads
 fn init_http_client() -> Result<reqwest::Client, anyhow::Error> {
-    let client = Client::builder()
-        .timeout(Duration::from_secs(10))
+    Client::builder()
         .pool_idle_timeout(Duration::from_secs(300))
         .pool_max_idle_per_host(32)
         .build()
-        .context("global: failed initializing http client");
+        .context("global: failed initializing http client")
+}
+
+/// Send `request` to upstream, guarded by the circuit breaker of its host.
+///
+/// Idempotent requests failing transiently are retried after a jittered exponential backoff, or after
+/// upstream's `Retry-After`. The last answer is returned as is when retries run out.
+pub(crate) async fn send(request: Request) -> Result<Response, HandlerError> {
+    let config = config();
+    let host = host_of(&request);
+    let cooldown = Duration::from_secs(config.http_breaker_cooldown_secs);
+    let backoff_base = Duration::from_millis(config.http_backoff_base_ms);
+    let backoff_max = Duration::from_millis(config.http_backoff_max_ms);
+
+    // requests with streamed bodies can't be cloned, those are sent once
+    let retries = match request.try_clone() {
+        Some(_) if is_idempotent(request.method()) => config.http_retries,
+        _ => 0,
+    };
+
+    let mut request = Some(request);
+    let mut attempt = 0;
+    loop {
+        if !circuit_breakers().allow(&host, cooldown, Instant::now()) {
+            return Err(HandlerError::UpstreamUnavailable(host));
+        }
+
+        let mut current = match attempt < retries {
+            true => request.as_ref().and_then(Request::try_clone),
+            false => request.take(),
+        }
+        .expect("request to send is kept until the last attempt");
+        *current.timeout_mut() = Some(Duration::from_secs(config.http_timeout_secs));
+
+        let jitter = rand::thread_rng().r#gen::<f64>();
+        let (reason, delay) = match http_client().execute(current).await {
+            Ok(resp) if !is_transient(resp.status()) => {
+                // not worth asking again, but a host answering 500 to everything is still down
+                if resp.status().is_server_error() {
+                    circuit_breakers().record_failure(
+                        &host,
+                        config.http_breaker_threshold,
+                        Instant::now(),
+                    );
+                } else {
+                    circuit_breakers().record_success(&host);
+                }
+                return Ok(resp);
+            }
+            Ok(resp) => {
+                circuit_breakers().record_failure(
+                    &host,
+                    config.http_breaker_threshold,
+                    Instant::now(),
+                );
+
+                let delay = match retry_after(resp.headers(), Utc::now()) {
+                    Some(delay) => delay,
+                    None => backoff_delay(attempt, backoff_base, backoff_max, jitter),
+                };
+                // upstream asking for a longer pause than we are willing to wait gets its answer back
+                if attempt >= retries || delay > backoff_max {
+                    return Ok(resp);
+                }
+
+                (resp.status().to_string(), delay)
+            }
+            Err(err) if err.is_timeout() || err.is_connect() => {
+                circuit_breakers().record_failure(
+                    &host,
+                    config.http_breaker_threshold,
+                    Instant::now(),
+                );
+                if attempt >= retries {
+                    return Err(err.into());
+                }
+
+                (
+                    err.to_string(),
+                    backoff_delay(attempt, backoff_base, backoff_max, jitter),
+                )
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        attempt += 1;
+        eprintln!(
+            "[http] {} failed with {}, retry {}/{} in {:?}",
+            host, reason, attempt, retries, delay
+        );
+        tokio::time::sleep(delay).await;
+    }
+}
+
+/// Breakers are kept per `host:port`.
+fn host_of(request: &Request) -> String {
+    let url = request.url();
+    format!(
+        "{}:{}",
+        url.host_str().unwrap_or_default(),
+        url.port_or_known_default().unwrap_or_default()
+    )
+}
+
+fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::OPTIONS | Method::PUT | Method::DELETE
+    )
+}
+
+// a plain 500 is usually a bug upstream, asking again gives the same answer
+pub(crate) fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::REQUEST_TIMEOUT
+            | StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Equal jitter backoff: half of `base * 2^attempt` capped at `max`, plus up to the other half by `jitter` in `[0, 1)`.
+pub(crate) fn backoff_delay(attempt: u32, base: Duration, max: Duration, jitter: f64) -> Duration {
+    let ceiling = base.saturating_mul(2u32.saturating_pow(attempt)).min(max);
+    let half = ceiling / 2;
+
+    half + half.mul_f64(jitter.clamp(0.0, 1.0))
+}
+
+fn retry_after(headers: &HeaderMap, now: DateTime<Utc>) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| parse_retry_after(value, now))
+}
+
+/// `Retry-After` value, either delay seconds or an HTTP date. Dates in the past mean no delay.
+pub(crate) fn parse_retry_after(value: &str, now: DateTime<Utc>) -> Option<Duration> {
+    let value = value.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+
+    let at = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((at.with_timezone(&Utc) - now).to_std().unwrap_or_default())
+}
+
+/// GET a json body with how long ago it was fetched from upstream, served from the response cache while fresh.
+/// Only successful responses are cached.
+pub(crate) async fn get_json_cached<T, Q>(
+    url: &str,
+    query: &Q,
+    ttl: Duration,
+) -> Result<(T, Duration), HandlerError>
+where
+    T: DeserializeOwned,
+    Q: Serialize + ?Sized,
+{
+    let request = http_client()
+        .get(url)
+        .query(query)
+        .build()
+        .with_context(|| format!("failed building request to {}", url))
+        .into_internal_err()?;
+    let key = request.url().to_string();
+
+    let (body, age) = match response_cache().get_with_age(&key) {
+        Some(cached) => cached,
+        None => {
+            let resp = send(request).await?;
+            let is_success = resp.status().is_success();
+            let body = resp.text().await?;
+
+            if is_success {
+                response_cache().insert(key, body.clone(), ttl);
+            }
+
+            (body, Duration::ZERO)
+        }
+    };
+
+    let body = serde_json::from_str(&body)
+        .with_context(|| format!("failed decoding response of {}", url))
+        .into_internal_err()?;
 
-    client
+    Ok((body, age))
 }
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use chrono::{TimeZone, Utc};
+use reqwest::StatusCode;
+use serde_json::Value;
+
+use crate::deps::http_client::{backoff_delay, get_json_cached, parse_retry_after};
+use crate::error::HandlerError;
+use crate::testing::fake_pfm::{FakePfmApi, Mode};
+
+const TTL: Duration = Duration::from_secs(60);
+
+async fn usd_idr(pfm: &FakePfmApi) -> Result<Value, HandlerError> {
+    get_json_cached(
+        &format!("{}/convert", pfm.base_url()),
+        &[("from", "USD 1"), ("to", "IDR")],
+        TTL,
+    )
+    .await
+    .map(|(body, _)| body)
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried() {
+    let pfm = FakePfmApi::start().await;
+    pfm.fail_next(2, StatusCode::BAD_GATEWAY);
+
+    let ret = usd_idr(&pfm).await.unwrap();
+
+    assert!(ret["data"].is_object(), "{}", ret);
+    assert_eq!(3, pfm.requests().len());
+}
+
+#[tokio::test]
+async fn retries_run_out() {
+    let pfm = FakePfmApi::start().await;
+    pfm.fail_next(3, StatusCode::SERVICE_UNAVAILABLE);
+
+    let ret = usd_idr(&pfm).await;
+
+    assert!(matches!(ret, Err(HandlerError::ApiError(_))), "{:?}", ret);
+    assert_eq!(3, pfm.requests().len());
+}
+
+#[tokio::test]
+async fn server_errors_are_not_retried() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ServerError);
+
+    usd_idr(&pfm).await.unwrap_err();
+
+    assert_eq!(1, pfm.requests().len());
+}
+
+#[tokio::test]
+async fn open_circuit_fails_fast() {
+    let pfm = FakePfmApi::start().await;
+    pfm.fail_next(10, StatusCode::GATEWAY_TIMEOUT);
+
+    // 3 attempts, then 2 more reaching the threshold of 5
+    usd_idr(&pfm).await.unwrap_err();
+    let ret = usd_idr(&pfm).await;
+    assert!(
+        matches!(ret, Err(HandlerError::UpstreamUnavailable(_))),
+        "{:?}",
+        ret
+    );
+    assert_eq!(5, pfm.requests().len());
+
+    let ret = usd_idr(&pfm).await;
+    assert!(
+        matches!(ret, Err(HandlerError::UpstreamUnavailable(_))),
+        "{:?}",
+        ret
+    );
+    assert_eq!(5, pfm.requests().len());
+}
+
+#[tokio::test]
+async fn server_errors_open_the_circuit() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ServerError);
+
+    for _ in 0..5 {
+        let ret = usd_idr(&pfm).await;
+        assert!(matches!(ret, Err(HandlerError::ApiError(_))), "{:?}", ret);
+    }
+    let ret = usd_idr(&pfm).await;
+    assert!(
+        matches!(ret, Err(HandlerError::UpstreamUnavailable(_))),
+        "{:?}",
+        ret
+    );
+    assert_eq!(5, pfm.requests().len());
+}
+
+#[test]
+fn backoff_grows_and_is_capped() {
+    let base = Duration::from_millis(200);
+    let max = Duration::from_millis(1000);
+
+    assert_eq!(Duration::from_millis(100), backoff_delay(0, base, max, 0.0));
+    assert_eq!(Duration::from_millis(200), backoff_delay(0, base, max, 1.0));
+    assert_eq!(Duration::from_millis(300), backoff_delay(1, base, max, 0.5));
+    assert_eq!(
+        Duration::from_millis(1000),
+        backoff_delay(10, base, max, 1.0)
+    );
+    assert_eq!(
+        Duration::from_millis(500),
+        backoff_delay(u32::MAX, base, max, 0.0)
+    );
+}
+
+#[test]
+fn retry_after_formats() {
+    let now = Utc.with_ymd_and_hms(2024, 3, 8, 12, 0, 0).unwrap();
+
+    assert_eq!(Some(Duration::from_secs(3)), parse_retry_after("3", now));
+    assert_eq!(
+        Some(Duration::from_secs(90)),
+        parse_retry_after("Fri, 08 Mar 2024 12:01:30 GMT", now)
+    );
+    assert_eq!(
+        Some(Duration::ZERO),
+        parse_retry_after("Fri, 08 Mar 2024 11:00:00 GMT", now)
+    );
+    assert_eq!(None, parse_retry_after("soon", now));
+    assert_eq!(None, parse_retry_after("-1", now));
+}
@@ -0,0 +1,58 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::error::HandlerError;
+
+pub(crate) fn metrics() -> &'static Metrics {
+    &METRICS
+}
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Process wide counters since start, reset on restart.
+pub(crate) struct Metrics {
+    started_at: Instant,
+    commands_served: AtomicU64,
+    errors: Mutex<BTreeMap<&'static str, u64>>,
+}
+
+impl Metrics {
+    fn new() -> Self {
+        Metrics {
+            started_at: Instant::now(),
+            commands_served: AtomicU64::new(0),
+            errors: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    pub(crate) fn record(&self, ret: &Result<(), HandlerError>) {
+        self.commands_served.fetch_add(1, Ordering::Relaxed);
+
+        if let Err(err) = ret {
+            *self
+                .errors
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(err.kind())
+                .or_default() += 1;
+        }
+    }
+
+    pub(crate) fn uptime(&self) -> Duration {
+        self.started_at.elapsed()
+    }
+
+    pub(crate) fn commands_served(&self) -> u64 {
+        self.commands_served.load(Ordering::Relaxed)
+    }
+
+    /// Error counts per `HandlerError` variant.
+    pub(crate) fn errors(&self) -> BTreeMap<&'static str, u64> {
+        self.errors
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
@@ -1 +1,20 @@
+pub(crate) mod cache;
+
+#[cfg(test)]
+mod cache_test;
+
+pub(crate) mod circuit_breaker;
+
+#[cfg(test)]
+mod circuit_breaker_test;
+
 pub(crate) mod http_client;
+
+#[cfg(test)]
+mod http_client_test;
+
+pub(crate) mod metrics;
+pub(crate) mod storage;
+
+#[cfg(test)]
+mod storage_test;
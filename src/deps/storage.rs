@@ -0,0 +1,450 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, NaiveDate, SecondsFormat, Utc};
+use rusqlite::{Connection, OptionalExtension, params};
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use std::time::Duration;
+
+use crate::config::config;
+
+pub(crate) fn storage() -> &'static Storage {
+    &STORAGE
+}
+
+static STORAGE: LazyLock<Storage> =
+    LazyLock::new(|| init_storage().expect("global static init storage"));
+
+fn init_storage() -> Result<Storage, anyhow::Error> {
+    // tests run in one process, keep them away from the real database file
+    if cfg!(test) {
+        return Storage::open_in_memory();
+    }
+
+    Storage::open(&config().database_path)
+}
+
+// Each entry migrates the schema one version up. Append only, never edit released entries.
+static MIGRATIONS: &[&str] = &[
+    "CREATE TABLE chat_settings (
+        chat_id INTEGER PRIMARY KEY,
+        lang TEXT
+    );",
+    "ALTER TABLE chat_settings ADD COLUMN broadcast INTEGER NOT NULL DEFAULT 0;
+    CREATE TABLE banned_users (
+        user_id INTEGER PRIMARY KEY,
+        banned_by INTEGER NOT NULL,
+        banned_at TEXT NOT NULL
+    );
+    CREATE TABLE audit_log (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        at TEXT NOT NULL,
+        user_id INTEGER NOT NULL,
+        chat_id INTEGER NOT NULL,
+        action TEXT NOT NULL,
+        allowed INTEGER NOT NULL
+    );",
+    "CREATE TABLE command_events (
+        id INTEGER PRIMARY KEY AUTOINCREMENT,
+        at TEXT NOT NULL,
+        chat_id INTEGER NOT NULL,
+        chat_type TEXT NOT NULL,
+        command TEXT NOT NULL,
+        outcome TEXT NOT NULL,
+        latency_ms INTEGER NOT NULL
+    );
+    CREATE INDEX command_events_at ON command_events (at);
+    CREATE TABLE command_daily (
+        day TEXT NOT NULL,
+        chat_id INTEGER NOT NULL,
+        chat_type TEXT NOT NULL,
+        command TEXT NOT NULL,
+        outcome TEXT NOT NULL,
+        count INTEGER NOT NULL,
+        latency_ms INTEGER NOT NULL,
+        PRIMARY KEY (day, chat_id, chat_type, command, outcome)
+    );",
+    "CREATE TABLE last_rates (
+        request TEXT PRIMARY KEY,
+        source TEXT NOT NULL,
+        body TEXT NOT NULL,
+        fetched_at TEXT NOT NULL
+    );",
+    "ALTER TABLE chat_settings ADD COLUMN timezone TEXT;",
+];
+
+/// Local sqlite database for state that has to survive restarts.
+pub(crate) struct Storage {
+    conn: Mutex<Connection>,
+}
+
+/// Per chat preferences. Unset fields fall back to user or global defaults.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct ChatSettings {
+    pub lang: Option<String>,
+
+    // opted in to receive /admin broadcast
+    pub broadcast: bool,
+
+    // IANA name, e.g. Asia/Makassar
+    pub timezone: Option<String>,
+}
+
+/// One handled command. Message text is never stored.
+#[derive(Debug, Clone)]
+pub(crate) struct CommandEvent {
+    pub at: DateTime<Utc>,
+    pub chat_id: i64,
+    pub chat_type: &'static str,
+    pub command: &'static str,
+
+    // "ok" or the `HandlerError` variant
+    pub outcome: &'static str,
+    pub latency: Duration,
+}
+
+/// Command usage summed per day, command and outcome.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UsageRow {
+    pub day: NaiveDate,
+    pub command: String,
+    pub outcome: String,
+    pub count: u64,
+    pub latency_ms: u64,
+}
+
+/// Last successful answer of an upstream rates request, served when every live source fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct LastRates {
+    pub source: String,
+
+    // json body in pfm shape, whichever source answered
+    pub body: String,
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl Storage {
+    pub(crate) fn open(path: &str) -> Result<Self, anyhow::Error> {
+        let conn = Connection::open(path)
+            .with_context(|| format!("storage: failed opening database {}", path))?;
+
+        Self::init(conn)
+    }
+
+    pub(crate) fn open_in_memory() -> Result<Self, anyhow::Error> {
+        let conn =
+            Connection::open_in_memory().context("storage: failed opening in-memory database")?;
+
+        Self::init(conn)
+    }
+
+    fn init(mut conn: Connection) -> Result<Self, anyhow::Error> {
+        migrate(&mut conn)?;
+
+        Ok(Storage {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        // a panic while holding the lock doesn't corrupt sqlite, keep serving
+        self.conn.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn chat_settings(&self, chat_id: i64) -> Result<ChatSettings, anyhow::Error> {
+        let ret = self
+            .conn()
+            .query_row(
+                "SELECT lang, broadcast, timezone FROM chat_settings WHERE chat_id = ?1",
+                params![chat_id],
+                |row| {
+                    Ok(ChatSettings {
+                        lang: row.get(0)?,
+                        broadcast: row.get(1)?,
+                        timezone: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+            .context("storage: failed reading chat settings")?;
+
+        Ok(ret.unwrap_or_default())
+    }
+
+    pub(crate) fn set_chat_lang(&self, chat_id: i64, lang: &str) -> Result<(), anyhow::Error> {
+        self.conn()
+            .execute(
+                "INSERT INTO chat_settings (chat_id, lang) VALUES (?1, ?2)
+                ON CONFLICT(chat_id) DO UPDATE SET lang = excluded.lang",
+                params![chat_id, lang],
+            )
+            .context("storage: failed saving chat language")?;
+
+        Ok(())
+    }
+
+    pub(crate) fn set_chat_broadcast(
+        &self,
+        chat_id: i64,
+        broadcast: bool,
+    ) -> Result<(), anyhow::Error> {
+        self.conn()
+            .execute(
+                "INSERT INTO chat_settings (chat_id, broadcast) VALUES (?1, ?2)
+                ON CONFLICT(chat_id) DO UPDATE SET broadcast = excluded.broadcast",
+                params![chat_id, broadcast],
+            )
+            .context("storage: failed saving chat broadcast setting")?;
+
+        Ok(())
+    }
+
+    pub(crate) fn set_chat_timezone(
+        &self,
+        chat_id: i64,
+        timezone: &str,
+    ) -> Result<(), anyhow::Error> {
+        self.conn()
+            .execute(
+                "INSERT INTO chat_settings (chat_id, timezone) VALUES (?1, ?2)
+                ON CONFLICT(chat_id) DO UPDATE SET timezone = excluded.timezone",
+                params![chat_id, timezone],
+            )
+            .context("storage: failed saving chat timezone")?;
+
+        Ok(())
+    }
+
+    pub(crate) fn broadcast_chats(&self) -> Result<Vec<i64>, anyhow::Error> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT chat_id FROM chat_settings WHERE broadcast = 1 ORDER BY chat_id")
+            .context("storage: failed preparing broadcast chats query")?;
+        let ret = stmt
+            .query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect::<Result<Vec<i64>, _>>())
+            .context("storage: failed reading broadcast chats")?;
+
+        Ok(ret)
+    }
+
+    /// Returns false if user was already banned.
+    pub(crate) fn ban_user(&self, user_id: u64, banned_by: u64) -> Result<bool, anyhow::Error> {
+        let inserted = self
+            .conn()
+            .execute(
+                "INSERT OR IGNORE INTO banned_users (user_id, banned_by, banned_at)
+                VALUES (?1, ?2, ?3)",
+                params![user_id, banned_by, Utc::now().to_rfc3339()],
+            )
+            .context("storage: failed banning user")?;
+
+        Ok(inserted > 0)
+    }
+
+    /// Returns false if user wasn't banned.
+    pub(crate) fn unban_user(&self, user_id: u64) -> Result<bool, anyhow::Error> {
+        let deleted = self
+            .conn()
+            .execute(
+                "DELETE FROM banned_users WHERE user_id = ?1",
+                params![user_id],
+            )
+            .context("storage: failed unbanning user")?;
+
+        Ok(deleted > 0)
+    }
+
+    pub(crate) fn is_banned(&self, user_id: u64) -> Result<bool, anyhow::Error> {
+        let ret = self
+            .conn()
+            .query_row(
+                "SELECT 1 FROM banned_users WHERE user_id = ?1",
+                params![user_id],
+                |_| Ok(()),
+            )
+            .optional()
+            .context("storage: failed reading banned users")?;
+
+        Ok(ret.is_some())
+    }
+
+    pub(crate) fn audit(
+        &self,
+        user_id: u64,
+        chat_id: i64,
+        action: &str,
+        allowed: bool,
+    ) -> Result<(), anyhow::Error> {
+        self.conn()
+            .execute(
+                "INSERT INTO audit_log (at, user_id, chat_id, action, allowed)
+                VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![Utc::now().to_rfc3339(), user_id, chat_id, action, allowed],
+            )
+            .context("storage: failed writing audit log")?;
+
+        Ok(())
+    }
+
+    /// Keep the answer of `request`, replacing the previous one.
+    pub(crate) fn save_last_rates(
+        &self,
+        request: &str,
+        rates: &LastRates,
+    ) -> Result<(), anyhow::Error> {
+        self.conn()
+            .execute(
+                "INSERT INTO last_rates (request, source, body, fetched_at) VALUES (?1, ?2, ?3, ?4)
+                ON CONFLICT (request) DO UPDATE SET
+                    source = excluded.source, body = excluded.body, fetched_at = excluded.fetched_at",
+                params![
+                    request,
+                    rates.source,
+                    rates.body,
+                    rates.fetched_at.to_rfc3339_opts(SecondsFormat::Millis, true),
+                ],
+            )
+            .context("storage: failed saving last rates")?;
+
+        Ok(())
+    }
+
+    pub(crate) fn last_rates(&self, request: &str) -> Result<Option<LastRates>, anyhow::Error> {
+        self.conn()
+            .query_row(
+                "SELECT source, body, fetched_at FROM last_rates WHERE request = ?1",
+                params![request],
+                |row| {
+                    Ok(LastRates {
+                        source: row.get(0)?,
+                        body: row.get(1)?,
+                        fetched_at: row.get(2)?,
+                    })
+                },
+            )
+            .optional()
+            .context("storage: failed reading last rates")
+    }
+
+    pub(crate) fn record_command(&self, event: &CommandEvent) -> Result<(), anyhow::Error> {
+        self.conn()
+            .execute(
+                "INSERT INTO command_events (at, chat_id, chat_type, command, outcome, latency_ms)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    event.at.to_rfc3339_opts(SecondsFormat::Millis, true),
+                    event.chat_id,
+                    event.chat_type,
+                    event.command,
+                    event.outcome,
+                    event.latency.as_millis() as u64,
+                ],
+            )
+            .context("storage: failed recording command")?;
+
+        Ok(())
+    }
+
+    /// Usage since the start of `since`, of one chat or of all chats when `chat_id` is empty.
+    /// Reads raw events and daily aggregates alike.
+    pub(crate) fn command_usage(
+        &self,
+        chat_id: Option<i64>,
+        since: NaiveDate,
+    ) -> Result<Vec<UsageRow>, anyhow::Error> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT day, command, outcome, SUM(count), SUM(latency_ms) FROM (
+                    SELECT substr(at, 1, 10) AS day, command, outcome,
+                        COUNT(*) AS count, SUM(latency_ms) AS latency_ms
+                    FROM command_events
+                    WHERE at >= ?1 AND (?2 IS NULL OR chat_id = ?2)
+                    GROUP BY 1, 2, 3
+                    UNION ALL
+                    SELECT day, command, outcome, count, latency_ms
+                    FROM command_daily
+                    WHERE day >= ?1 AND (?2 IS NULL OR chat_id = ?2)
+                )
+                GROUP BY day, command, outcome
+                ORDER BY day, command, outcome",
+            )
+            .context("storage: failed preparing command usage query")?;
+        let ret = stmt
+            .query_map(params![since.to_string(), chat_id], |row| {
+                Ok(UsageRow {
+                    day: row.get(0)?,
+                    command: row.get(1)?,
+                    outcome: row.get(2)?,
+                    count: row.get(3)?,
+                    latency_ms: row.get(4)?,
+                })
+            })
+            .and_then(|rows| rows.collect::<Result<Vec<_>, _>>())
+            .context("storage: failed reading command usage")?;
+
+        Ok(ret)
+    }
+
+    /// Roll events of days before `events_before` into daily aggregates, then drop aggregates of days before `daily_before`.
+    /// Returns number of rolled up events and dropped aggregates.
+    pub(crate) fn compact_command_usage(
+        &self,
+        events_before: NaiveDate,
+        daily_before: NaiveDate,
+    ) -> Result<(usize, usize), anyhow::Error> {
+        let mut conn = self.conn();
+        let tx = conn
+            .transaction()
+            .context("storage: failed starting usage compaction")?;
+
+        tx.execute(
+            "INSERT INTO command_daily (day, chat_id, chat_type, command, outcome, count, latency_ms)
+            SELECT substr(at, 1, 10), chat_id, chat_type, command, outcome, COUNT(*), SUM(latency_ms)
+            FROM command_events
+            WHERE at < ?1
+            GROUP BY 1, 2, 3, 4, 5
+            ON CONFLICT DO UPDATE SET
+                count = count + excluded.count,
+                latency_ms = latency_ms + excluded.latency_ms",
+            params![events_before.to_string()],
+        )
+        .context("storage: failed aggregating command events")?;
+        let rolled = tx
+            .execute(
+                "DELETE FROM command_events WHERE at < ?1",
+                params![events_before.to_string()],
+            )
+            .context("storage: failed deleting aggregated command events")?;
+        let dropped = tx
+            .execute(
+                "DELETE FROM command_daily WHERE day < ?1",
+                params![daily_before.to_string()],
+            )
+            .context("storage: failed deleting old command aggregates")?;
+
+        tx.commit()
+            .context("storage: failed committing usage compaction")?;
+
+        Ok((rolled, dropped))
+    }
+}
+
+fn migrate(conn: &mut Connection) -> Result<(), anyhow::Error> {
+    let version: usize = conn
+        .query_row("PRAGMA user_version", [], |row| row.get(0))
+        .context("storage: failed reading schema version")?;
+
+    for (idx, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+        let tx = conn
+            .transaction()
+            .context("storage: failed starting migration")?;
+        tx.execute_batch(migration)
+            .with_context(|| format!("storage: failed migrating to version {}", idx + 1))?;
+        tx.pragma_update(None, "user_version", idx + 1)
+            .context("storage: failed updating schema version")?;
+        tx.commit()
+            .context("storage: failed committing migration")?;
+    }
+
+    Ok(())
+}
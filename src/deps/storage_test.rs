@@ -0,0 +1,162 @@
+use std::time::Duration;
+
+use chrono::NaiveDate;
+
+use crate::deps::storage::{ChatSettings, CommandEvent, Storage, UsageRow};
+
+#[test]
+fn chat_settings_default_when_missing() {
+    let storage = Storage::open_in_memory().unwrap();
+
+    assert_eq!(ChatSettings::default(), storage.chat_settings(42).unwrap());
+}
+
+#[test]
+fn chat_lang_upsert() {
+    let storage = Storage::open_in_memory().unwrap();
+
+    storage.set_chat_lang(42, "id").unwrap();
+    assert_eq!(Some("id".into()), storage.chat_settings(42).unwrap().lang);
+
+    storage.set_chat_lang(42, "en").unwrap();
+    assert_eq!(Some("en".into()), storage.chat_settings(42).unwrap().lang);
+
+    assert_eq!(None, storage.chat_settings(43).unwrap().lang);
+}
+
+#[test]
+fn broadcast_opt_in_keeps_lang() {
+    let storage = Storage::open_in_memory().unwrap();
+
+    storage.set_chat_lang(1, "id").unwrap();
+    storage.set_chat_broadcast(1, true).unwrap();
+    storage.set_chat_broadcast(2, true).unwrap();
+    storage.set_chat_broadcast(3, false).unwrap();
+
+    assert_eq!(vec![1, 2], storage.broadcast_chats().unwrap());
+    assert_eq!(
+        ChatSettings {
+            lang: Some("id".into()),
+            broadcast: true,
+            timezone: None,
+        },
+        storage.chat_settings(1).unwrap()
+    );
+
+    storage.set_chat_broadcast(1, false).unwrap();
+    assert_eq!(vec![2], storage.broadcast_chats().unwrap());
+}
+
+#[test]
+fn chat_timezone_keeps_other_settings() {
+    let storage = Storage::open_in_memory().unwrap();
+
+    storage.set_chat_lang(1, "id").unwrap();
+    storage.set_chat_timezone(1, "Asia/Makassar").unwrap();
+    storage.set_chat_timezone(1, "Asia/Jayapura").unwrap();
+
+    let settings = storage.chat_settings(1).unwrap();
+    assert_eq!(Some("Asia/Jayapura".into()), settings.timezone);
+    assert_eq!(Some("id".into()), settings.lang);
+    assert_eq!(None, storage.chat_settings(2).unwrap().timezone);
+}
+
+#[test]
+fn ban_and_unban() {
+    let storage = Storage::open_in_memory().unwrap();
+
+    assert!(!storage.is_banned(7).unwrap());
+    assert!(storage.ban_user(7, 1).unwrap());
+    assert!(!storage.ban_user(7, 1).unwrap());
+    assert!(storage.is_banned(7).unwrap());
+
+    assert!(storage.unban_user(7).unwrap());
+    assert!(!storage.unban_user(7).unwrap());
+    assert!(!storage.is_banned(7).unwrap());
+}
+
+fn event(at: &str, chat_id: i64, command: &'static str, outcome: &'static str) -> CommandEvent {
+    CommandEvent {
+        at: at.parse().unwrap(),
+        chat_id,
+        chat_type: "group",
+        command,
+        outcome,
+        latency: Duration::from_millis(100),
+    }
+}
+
+fn day(day: &str) -> NaiveDate {
+    day.parse().unwrap()
+}
+
+#[test]
+fn command_usage_per_chat_and_global() {
+    let storage = Storage::open_in_memory().unwrap();
+
+    storage
+        .record_command(&event("2024-03-09T23:59:59Z", 1, "forex", "ok"))
+        .unwrap();
+    storage
+        .record_command(&event("2024-03-10T08:00:00Z", 1, "forex", "ok"))
+        .unwrap();
+    storage
+        .record_command(&event("2024-03-10T09:00:00Z", 2, "forex", "ApiError"))
+        .unwrap();
+
+    let rows = storage.command_usage(Some(1), day("2024-03-10")).unwrap();
+    assert_eq!(
+        vec![UsageRow {
+            day: day("2024-03-10"),
+            command: "forex".into(),
+            outcome: "ok".into(),
+            count: 1,
+            latency_ms: 100,
+        }],
+        rows
+    );
+
+    let rows = storage.command_usage(None, day("2024-03-01")).unwrap();
+    assert_eq!(3, rows.iter().map(|row| row.count).sum::<u64>());
+}
+
+#[test]
+fn compaction_keeps_usage() {
+    let storage = Storage::open_in_memory().unwrap();
+
+    for at in [
+        "2024-03-01T01:00:00Z",
+        "2024-03-01T02:00:00Z",
+        "2024-03-05T01:00:00Z",
+    ] {
+        storage
+            .record_command(&event(at, 1, "convert", "ok"))
+            .unwrap();
+    }
+    storage
+        .record_command(&event("2024-01-01T01:00:00Z", 1, "convert", "ok"))
+        .unwrap();
+
+    let before = storage.command_usage(Some(1), day("2024-02-01")).unwrap();
+    assert_eq!(
+        (3, 1),
+        storage
+            .compact_command_usage(day("2024-03-05"), day("2024-02-01"))
+            .unwrap()
+    );
+    assert_eq!(
+        before,
+        storage.command_usage(Some(1), day("2024-02-01")).unwrap()
+    );
+
+    // rolling up again adds to the existing daily counts
+    storage
+        .record_command(&event("2024-03-01T03:00:00Z", 1, "convert", "ok"))
+        .unwrap();
+    storage
+        .compact_command_usage(day("2024-03-05"), day("2024-02-01"))
+        .unwrap();
+    let rows = storage.command_usage(Some(1), day("2024-03-01")).unwrap();
+    assert_eq!(3, rows[0].count);
+    assert_eq!(300, rows[0].latency_ms);
+}
@@ -1,11 +1,21 @@
 use async_trait::async_trait;
-use std::{fmt::Debug, sync::Arc};
+use futures::future::BoxFuture;
+use rand::Rng as _;
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
 use teloxide::prelude::*;
-use teloxide::sugar::request::RequestReplyExt;
+use teloxide::utils::html;
 use teloxide::{ApiError, Bot, RequestError, prelude::Requester};
 
 use thiserror::Error;
 
+use crate::config::config;
+use crate::handlers::reply::Reply;
+use crate::i18n::{Lang, Localized, lang_for, lang_for_user};
+use crate::utils::syntax::SyntaxError;
+
 #[derive(Debug, Error)]
 pub enum HandlerError {
     #[error("Telegram Error: {0}")]
@@ -19,11 +29,137 @@ pub enum HandlerError {
 
     #[error("API call return error response: {0}")]
     ApiError(anyhow::Error),
+
+    #[error("Storage error: {0}")]
+    StorageError(anyhow::Error),
+
+    #[error("Upstream unavailable: circuit of {0} is open")]
+    UpstreamUnavailable(String),
+}
+
+impl From<Localized> for HandlerError {
+    fn from(value: Localized) -> Self {
+        HandlerError::InvalidArguments(value.into())
+    }
+}
+
+impl From<SyntaxError> for HandlerError {
+    fn from(value: SyntaxError) -> Self {
+        HandlerError::InvalidArguments(value.into())
+    }
+}
+
+impl HandlerError {
+    /// Friendly text shown to chat members. Upstream URLs and library error chains never end up here.
+    pub(crate) fn user_message(&self, lang: Lang) -> String {
+        match self {
+            // argument errors are written for users in the first place
+            Self::InvalidArguments(err) => {
+                if let Some(localized) = err.downcast_ref::<Localized>() {
+                    return localized.render(lang);
+                }
+                match err.downcast_ref::<SyntaxError>() {
+                    Some(syntax) => syntax.render(lang),
+                    None => err.to_string(),
+                }
+            }
+            Self::TelegramError(_) => Localized::new("error.telegram").render(lang),
+            Self::NetworkError(_) => Localized::new("error.network").render(lang),
+            Self::ApiError(_) => Localized::new("error.api").render(lang),
+            Self::StorageError(_) => Localized::new("error.storage").render(lang),
+            Self::UpstreamUnavailable(_) => {
+                Localized::new("error.upstream_unavailable").render(lang)
+            }
+        }
+    }
+
+    /// Same as `user_message`, as html for chat replies.
+    pub(crate) fn user_html(&self, lang: Lang) -> String {
+        match self {
+            Self::InvalidArguments(err) => match err.downcast_ref::<SyntaxError>() {
+                Some(syntax) => syntax.render_html(lang),
+                None => html::escape(&self.user_message(lang)),
+            },
+            _ => html::escape(&self.user_message(lang)),
+        }
+    }
+
+    /// Variant name, for counting errors without their content.
+    pub(crate) fn kind(&self) -> &'static str {
+        match self {
+            Self::TelegramError(_) => "TelegramError",
+            Self::InvalidArguments(_) => "InvalidArguments",
+            Self::NetworkError(_) => "NetworkError",
+            Self::ApiError(_) => "ApiError",
+            Self::StorageError(_) => "StorageError",
+            Self::UpstreamUnavailable(_) => "UpstreamUnavailable",
+        }
+    }
+
+    /// Whether operators should hear about this error, as opposed to the user simply mistyping.
+    pub(crate) fn is_operator_error(&self) -> bool {
+        // the breaker already logged the failures leading to it
+        !matches!(
+            self,
+            Self::InvalidArguments(_) | Self::UpstreamUnavailable(_)
+        )
+    }
+
+    /// Full error chain for logs, one cause per line.
+    pub(crate) fn chain(&self) -> String {
+        let head: Option<&(dyn std::error::Error + 'static)> = match self {
+            Self::TelegramError(err) => Some(err),
+            Self::NetworkError(err) => Some(err),
+            Self::InvalidArguments(err) | Self::ApiError(err) | Self::StorageError(err) => {
+                Some(err.as_ref())
+            }
+            Self::UpstreamUnavailable(_) => None,
+        };
+
+        let mut ret = self.to_string();
+        let mut source = head.and_then(|head| head.source());
+        while let Some(cause) = source {
+            ret.push_str(&format!("\n  caused by: {}", cause));
+            source = cause.source();
+        }
+
+        ret
+    }
+}
+
+// unambiguous lowercase alphabet, no i/l/o/u
+static CORRELATION_ID_ALPHABET: &[u8] = b"0123456789abcdefghjkmnpqrstvwxyz";
+
+const CORRELATION_ID_LEN: usize = 8;
+
+/// Short id attached to an error reply, so a member's screenshot can be matched with the logs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CorrelationId(String);
+
+impl CorrelationId {
+    pub(crate) fn new() -> Self {
+        let mut rng = rand::thread_rng();
+        let id = (0..CORRELATION_ID_LEN)
+            .map(|_| {
+                let idx = rng.gen_range(0..CORRELATION_ID_ALPHABET.len());
+                CORRELATION_ID_ALPHABET[idx] as char
+            })
+            .collect();
+
+        CorrelationId(id)
+    }
+}
+
+impl Display for CorrelationId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
 #[async_trait]
 pub(crate) trait SendIfError {
-    /// Send any error from call chains to telegram bot, otherwise only result sent.
+    /// Send a friendly version of any error from call chains to telegram bot, otherwise only result sent.
+    /// The full error is logged, and forwarded to the admin chat if configured, under a correlation id.
     async fn send_if_err(self, bot: Bot, msg: &Message) -> Self;
 }
 
@@ -31,11 +167,23 @@ pub(crate) trait SendIfError {
 impl SendIfError for Result<(), HandlerError> {
     async fn send_if_err(self, bot: Bot, msg: &Message) -> Self {
         if let Some(err) = self.as_ref().err() {
-            let err_msg = format!("{}", err);
-            let _ = bot
-                .send_message(msg.chat.id, err_msg)
-                .reply_to(msg.id)
-                .await;
+            let id = CorrelationId::new();
+
+            let context = format!(
+                "chat={} message={} text={:?}",
+                msg.chat.id,
+                msg.id,
+                msg.text().unwrap_or_default()
+            );
+            report(&bot, &context, err, &id).await;
+
+            let lang = lang_for(msg);
+            let err_msg = format!(
+                "{}\n\n{}",
+                err.user_html(lang),
+                html::escape(&Localized::new("error.ref").arg("id", &id).render(lang))
+            );
+            let _ = Reply::new(err_msg).html().quote().send(&bot, msg).await;
             return self;
         }
 
@@ -43,28 +191,85 @@ impl SendIfError for Result<(), HandlerError> {
     }
 }
 
-pub trait AsClientError<T> {
-    fn as_client_err(self) -> Result<T, HandlerError>;
-}
+/// Answer a button press with `press` run on the message it is under. Errors are reported like
+/// `send_if_err` does and shown in an alert with their correlation id.
+pub(crate) async fn answer_callback<F>(
+    bot: Bot,
+    query: CallbackQuery,
+    press: F,
+) -> Result<(), HandlerError>
+where
+    F: for<'a> FnOnce(
+        &'a Bot,
+        &'a CallbackQuery,
+        &'a Message,
+        Lang,
+    ) -> BoxFuture<'a, Result<(), HandlerError>>,
+{
+    // too old to be edited
+    let Some(msg) = query.regular_message() else {
+        bot.answer_callback_query(query.id.clone()).await?;
+        return Ok(());
+    };
+    let lang = lang_for_user(msg.chat.id.0, Some(&query.from));
+
+    let ret = press(&bot, &query, msg, lang).await;
+
+    let answer = bot.answer_callback_query(query.id.clone());
+    match &ret {
+        Ok(()) => answer.await?,
+        Err(err) => {
+            let id = CorrelationId::new();
+            let context = format!(
+                "chat={} message={} callback={:?}",
+                msg.chat.id,
+                msg.id,
+                query.data.as_deref().unwrap_or_default()
+            );
+            report(&bot, &context, err, &id).await;
 
-pub trait AsInternalError<T> {
-    fn as_internal_err(self) -> Result<T, HandlerError>;
+            let text = format!(
+                "{}\n\n{}",
+                err.user_message(lang),
+                Localized::new("error.ref").arg("id", &id).render(lang)
+            );
+            answer.text(text).show_alert(true).await?
+        }
+    };
+
+    ret
 }
 
-impl<T, E> AsClientError<T> for Result<T, E>
-where
-    E: Into<anyhow::Error>,
-{
-    fn as_client_err(self) -> Result<T, HandlerError> {
-        self.map_err(|e| HandlerError::InvalidArguments(e.into()))
+/// Log full error chain, and forward it to admin chat when it is something operators should look at.
+async fn report(bot: &Bot, context: &str, err: &HandlerError, id: &CorrelationId) {
+    let report = format!("[{}] {}\n{}", id, context, err.chain());
+
+    eprintln!("[error]{}", report);
+
+    if !err.is_operator_error() {
+        return;
     }
+
+    if let Some(admin_chat_id) = config().admin_chat_id {
+        let ret = bot.send_message(ChatId(admin_chat_id), report).await;
+        if let Err(err) = ret {
+            eprintln!(
+                "[error][{}] failed forwarding error to admin chat: {}",
+                id, err
+            );
+        }
+    }
+}
+
+pub trait IntoInternalError<T> {
+    fn into_internal_err(self) -> Result<T, HandlerError>;
 }
 
-impl<T, E> AsInternalError<T> for Result<T, E>
+impl<T, E> IntoInternalError<T> for Result<T, E>
 where
     E: Into<anyhow::Error>,
 {
-    fn as_internal_err(self) -> Result<T, HandlerError> {
+    fn into_internal_err(self) -> Result<T, HandlerError> {
         self.map_err(|e| HandlerError::ApiError(e.into()))
     }
 }
@@ -78,6 +283,12 @@ impl From<HandlerError> for RequestError {
                 RequestError::Api(ApiError::Unknown(err.to_string()))
             }
             HandlerError::ApiError(err) => RequestError::Api(ApiError::Unknown(err.to_string())),
+            HandlerError::StorageError(err) => {
+                RequestError::Api(ApiError::Unknown(err.to_string()))
+            }
+            HandlerError::UpstreamUnavailable(host) => {
+                RequestError::Api(ApiError::Unknown(format!("upstream {} unavailable", host)))
+            }
         }
     }
 }
@@ -0,0 +1,56 @@
+use anyhow::{Context, anyhow};
+
+use crate::error::{CorrelationId, HandlerError};
+use crate::i18n::{Lang, Localized};
+
+#[test]
+fn correlation_id_is_short_and_readable() {
+    let id = CorrelationId::new().to_string();
+
+    assert_eq!(8, id.len());
+    assert!(
+        id.chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_lowercase())
+    );
+    assert_ne!(id, CorrelationId::new().to_string());
+}
+
+#[test]
+fn invalid_arguments_are_shown_as_is() {
+    let err = HandlerError::InvalidArguments(anyhow!("Forex pair must be in format XXX/YYY"));
+
+    assert_eq!(
+        "Forex pair must be in format XXX/YYY",
+        err.user_message(Lang::En)
+    );
+    assert!(!err.is_operator_error());
+}
+
+#[test]
+fn api_error_hides_internals() {
+    let err: Result<(), _> = Err(anyhow!(
+        "error sending request for url (https://api.mfirhas.com/pfm/v2/forex/convert)"
+    ))
+    .context("failed calling forex convert api");
+    let err = HandlerError::ApiError(err.unwrap_err());
+
+    let user_message = err.user_message(Lang::En);
+    assert!(!user_message.contains("api.mfirhas.com"));
+    assert!(!user_message.contains("failed calling"));
+    assert!(err.is_operator_error());
+
+    let chain = err.chain();
+    assert!(chain.contains("failed calling forex convert api"));
+    assert!(chain.contains("caused by: error sending request for url"));
+}
+
+#[test]
+fn localized_invalid_arguments_follow_reader_language() {
+    let err: HandlerError = Localized::new("forex.pair_format").into();
+
+    assert_eq!(
+        Localized::new("forex.pair_format").render(Lang::Id),
+        err.user_message(Lang::Id)
+    );
+    assert_ne!(err.user_message(Lang::En), err.user_message(Lang::Id));
+}
@@ -0,0 +1,288 @@
+use std::time::Duration;
+
+use teloxide::prelude::*;
+use teloxide::sugar::request::RequestReplyExt;
+use teloxide::types::MessageId;
+
+use crate::commands::{Args, publish_menus};
+use crate::config::{config, reload_config};
+use crate::deps::cache::response_cache;
+use crate::deps::metrics::metrics;
+use crate::deps::storage::storage;
+use crate::error::HandlerError;
+use crate::handlers::stats::usage_report;
+use crate::i18n::{Lang, Localized, lang_for};
+
+// stay well below telegram's limit of ~30 messages per second
+const BROADCAST_INTERVAL: Duration = Duration::from_millis(50);
+
+// audit entries keep the command, but not a whole broadcast text
+const MAX_AUDIT_ACTION_LEN: usize = 256;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum AdminArgs {
+    Stats,
+    // command usage across all chats
+    GlobalStats,
+    Broadcast(String),
+    // user id, or sender of the replied message when empty
+    Ban(Option<u64>),
+    Unban(Option<u64>),
+    Reload,
+    CacheClear,
+}
+
+impl TryFrom<Args> for AdminArgs {
+    type Error = HandlerError;
+
+    fn try_from(value: Args) -> Result<Self, Self::Error> {
+        let trimmed = value.0.trim();
+        let (sub, rest) = trimmed
+            .split_once(char::is_whitespace)
+            .unwrap_or((trimmed, ""));
+        let rest = rest.trim();
+
+        match (sub.to_ascii_lowercase().as_str(), rest) {
+            ("stats", "") => Ok(AdminArgs::Stats),
+            ("stats", "global") => Ok(AdminArgs::GlobalStats),
+            ("broadcast", "") => Err(Localized::new("admin.broadcast_empty").into()),
+            ("broadcast", text) => Ok(AdminArgs::Broadcast(text.to_string())),
+            ("ban", user) => Ok(AdminArgs::Ban(parse_user_id(user)?)),
+            ("unban", user) => Ok(AdminArgs::Unban(parse_user_id(user)?)),
+            ("reload", "") => Ok(AdminArgs::Reload),
+            ("cache", "clear") => Ok(AdminArgs::CacheClear),
+            _ => Err(Localized::new("admin.usage").into()),
+        }
+    }
+}
+
+fn parse_user_id(user: &str) -> Result<Option<u64>, HandlerError> {
+    if user.is_empty() {
+        return Ok(None);
+    }
+
+    user.parse::<u64>().map(Some).map_err(|_| {
+        Localized::new("admin.invalid_user")
+            .arg("user", user)
+            .into()
+    })
+}
+
+/// Whether the sender was banned by an admin. Their commands are ignored silently.
+pub(crate) fn is_banned_sender(msg: &Message) -> bool {
+    let Some(ref user) = msg.from else {
+        return false;
+    };
+
+    if config().is_admin(user.id.0) {
+        return false;
+    }
+
+    storage().is_banned(user.id.0).unwrap_or_else(|err| {
+        eprintln!("[admin] {:#}", err);
+        false
+    })
+}
+
+pub(crate) async fn admin_handler(bot: Bot, msg: &Message, args: Args) -> Result<(), HandlerError> {
+    let user_id = msg.from.as_ref().map(|user| user.id.0);
+    let allowed = user_id.is_some_and(|id| config().is_admin(id));
+
+    audit(msg, user_id.unwrap_or_default(), &args, allowed);
+
+    if !allowed {
+        return Err(Localized::new("admin.forbidden").into());
+    }
+
+    let lang = lang_for(msg);
+    let text = match AdminArgs::try_from(args)? {
+        AdminArgs::Stats => stats(lang),
+        AdminArgs::GlobalStats => usage_report(None, lang)?,
+        AdminArgs::Broadcast(text) => return start_broadcast(bot, msg, lang, text).await,
+        AdminArgs::Ban(target) => ban(msg, lang, target)?,
+        AdminArgs::Unban(target) => unban(msg, lang, target)?,
+        AdminArgs::Reload => reload(&bot, lang).await,
+        AdminArgs::CacheClear => Localized::new("admin.cache_cleared")
+            .arg("count", response_cache().clear())
+            .render(lang),
+    };
+
+    bot.send_message(msg.chat.id, text).reply_to(msg.id).await?;
+
+    Ok(())
+}
+
+fn audit(msg: &Message, user_id: u64, args: &Args, allowed: bool) {
+    let action: String = format!("/admin {}", args.0.trim())
+        .chars()
+        .take(MAX_AUDIT_ACTION_LEN)
+        .collect();
+
+    eprintln!(
+        "[audit] user={} chat={} allowed={} action={:?}",
+        user_id, msg.chat.id, allowed, action
+    );
+
+    if let Err(err) = storage().audit(user_id, msg.chat.id.0, &action, allowed) {
+        eprintln!("[audit] {:#}", err);
+    }
+}
+
+fn stats(lang: Lang) -> String {
+    let errors = metrics().errors();
+    let errors = if errors.is_empty() {
+        Localized::new("admin.stats_no_errors").render(lang)
+    } else {
+        errors
+            .iter()
+            .map(|(kind, count)| format!("- {}: {}", kind, count))
+            .collect::<Vec<_>>()
+            .join("\n")
+    };
+
+    Localized::new("admin.stats")
+        .arg("uptime", format_uptime(metrics().uptime()))
+        .arg("commands", metrics().commands_served())
+        .arg("errors", errors)
+        .arg("cache", response_cache().len())
+        .render(lang)
+}
+
+pub(crate) fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let (days, hours, minutes) = (secs / 86400, secs % 86400 / 3600, secs % 3600 / 60);
+
+    if days > 0 {
+        format!("{}d {}h {}m", days, hours, minutes)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else {
+        format!("{}m {}s", minutes, secs % 60)
+    }
+}
+
+/// Reply right away and send in the background, many chats take a while at the broadcast pace.
+async fn start_broadcast(
+    bot: Bot,
+    msg: &Message,
+    lang: Lang,
+    text: String,
+) -> Result<(), HandlerError> {
+    let chats = storage()
+        .broadcast_chats()
+        .map_err(HandlerError::StorageError)?;
+
+    let started = Localized::new("admin.broadcast_started")
+        .arg("chats", chats.len())
+        .render(lang);
+    bot.send_message(msg.chat.id, started)
+        .reply_to(msg.id)
+        .await?;
+
+    tokio::spawn(broadcast(bot, msg.chat.id, msg.id, lang, text, chats));
+
+    Ok(())
+}
+
+async fn broadcast(
+    bot: Bot,
+    admin_chat: ChatId,
+    admin_msg: MessageId,
+    lang: Lang,
+    text: String,
+    chats: Vec<i64>,
+) {
+    let (mut sent, mut failed) = (0, 0);
+    for chat_id in chats {
+        match bot.send_message(ChatId(chat_id), &text).await {
+            Ok(_) => sent += 1,
+            Err(err) => {
+                eprintln!("[admin] failed broadcasting to {}: {}", chat_id, err);
+                failed += 1;
+            }
+        }
+
+        tokio::time::sleep(BROADCAST_INTERVAL).await;
+    }
+
+    let done = Localized::new("admin.broadcast_done")
+        .arg("sent", sent)
+        .arg("failed", failed)
+        .render(lang);
+    if let Err(err) = bot.send_message(admin_chat, done).reply_to(admin_msg).await {
+        eprintln!("[admin] failed reporting broadcast: {}", err);
+    }
+}
+
+fn ban_target(msg: &Message, target: Option<u64>) -> Result<u64, HandlerError> {
+    target
+        .or_else(|| {
+            msg.reply_to_message()
+                .and_then(|replied| replied.from.as_ref())
+                .map(|user| user.id.0)
+        })
+        .ok_or_else(|| Localized::new("admin.ban_target").into())
+}
+
+fn ban(msg: &Message, lang: Lang, target: Option<u64>) -> Result<String, HandlerError> {
+    let user_id = ban_target(msg, target)?;
+
+    if config().is_admin(user_id) {
+        return Err(Localized::new("admin.ban_admin").into());
+    }
+
+    let banned_by = msg.from.as_ref().map(|user| user.id.0).unwrap_or_default();
+    let key = if storage()
+        .ban_user(user_id, banned_by)
+        .map_err(HandlerError::StorageError)?
+    {
+        "admin.banned"
+    } else {
+        "admin.already_banned"
+    };
+
+    Ok(Localized::new(key).arg("user", user_id).render(lang))
+}
+
+fn unban(msg: &Message, lang: Lang, target: Option<u64>) -> Result<String, HandlerError> {
+    let user_id = ban_target(msg, target)?;
+
+    let key = if storage()
+        .unban_user(user_id)
+        .map_err(HandlerError::StorageError)?
+    {
+        "admin.unbanned"
+    } else {
+        "admin.not_banned"
+    };
+
+    Ok(Localized::new(key).arg("user", user_id).render(lang))
+}
+
+async fn reload(bot: &Bot, lang: Lang) -> String {
+    let old_admins = config().admin_ids.clone();
+
+    match reload_config() {
+        Ok(new_config) => {
+            let reloaded = Localized::new("admin.reloaded")
+                .arg("admins", new_config.admin_ids.len())
+                .render(lang);
+
+            // admins may have changed, and so their menus
+            match publish_menus(bot, &new_config.admin_ids, &old_admins).await {
+                Ok(()) => reloaded,
+                Err(err) => format!(
+                    "{}\n{}",
+                    reloaded,
+                    Localized::new("admin.menus_failed")
+                        .arg("error", err)
+                        .render(lang)
+                ),
+            }
+        }
+        // shown to admins only, details help fixing the config
+        Err(err) => Localized::new("admin.reload_failed")
+            .arg("error", err)
+            .render(lang),
+    }
+}
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use crate::config::scoped;
+use crate::deps::storage::storage;
+use crate::handler;
+use crate::testing::fake_bot::{FakeBotApi, message_update, private_message};
+use crate::{
+    commands::Args,
+    handlers::admin::{AdminArgs, format_uptime},
+};
+
+#[test]
+fn simple_subcommands() {
+    assert_eq!(AdminArgs::Stats, Args("stats".into()).try_into().unwrap());
+    assert_eq!(
+        AdminArgs::GlobalStats,
+        Args("stats global".into()).try_into().unwrap()
+    );
+    assert_eq!(
+        AdminArgs::Reload,
+        Args(" Reload ".into()).try_into().unwrap()
+    );
+    assert_eq!(
+        AdminArgs::CacheClear,
+        Args("cache clear".into()).try_into().unwrap()
+    );
+}
+
+#[test]
+fn broadcast_keeps_text_as_is() {
+    let ret: AdminArgs = Args("broadcast Hello  all,\nnew /forex is out!".into())
+        .try_into()
+        .unwrap();
+    assert_eq!(
+        AdminArgs::Broadcast("Hello  all,\nnew /forex is out!".into()),
+        ret
+    );
+
+    let ret: Result<AdminArgs, _> = Args("broadcast   ".into()).try_into();
+    assert!(ret.is_err());
+}
+
+#[test]
+fn ban_with_or_without_user_id() {
+    assert_eq!(
+        AdminArgs::Ban(Some(12345)),
+        Args("ban 12345".into()).try_into().unwrap()
+    );
+    assert_eq!(AdminArgs::Ban(None), Args("ban".into()).try_into().unwrap());
+    assert_eq!(
+        AdminArgs::Unban(Some(12345)),
+        Args("unban 12345".into()).try_into().unwrap()
+    );
+
+    let ret: Result<AdminArgs, _> = Args("ban @someone".into()).try_into();
+    assert!(ret.is_err());
+}
+
+#[test]
+fn unknown_subcommands() {
+    for args in [
+        "",
+        "cache",
+        "cache purge",
+        "stats now",
+        "reload please",
+        "shutdown",
+    ] {
+        let ret: Result<AdminArgs, _> = Args(args.into()).try_into();
+        assert!(ret.is_err(), "{} should be rejected", args);
+    }
+}
+
+#[test]
+fn uptime_formatting() {
+    assert_eq!("0m 42s", format_uptime(Duration::from_secs(42)));
+    assert_eq!(
+        "2h 5m",
+        format_uptime(Duration::from_secs(2 * 3600 + 5 * 60))
+    );
+    assert_eq!(
+        "3d 4h 0m",
+        format_uptime(Duration::from_secs(3 * 86400 + 4 * 3600 + 30))
+    );
+}
+
+#[tokio::test]
+async fn broadcast_replies_before_sending() {
+    let api = FakeBotApi::start().await;
+    for chat_id in [410_101, 410_102] {
+        storage().set_chat_broadcast(chat_id, true).unwrap();
+    }
+
+    scoped(
+        |config| config.admin_ids = vec![410_001],
+        api.dispatch(
+            handler(),
+            message_update(1, private_message(7, 410_001, "/admin broadcast hello")),
+        ),
+    )
+    .await
+    .unwrap();
+
+    let sent = api.sent_messages();
+    assert!(
+        sent[0].text().unwrap().starts_with("Broadcasting to"),
+        "{:?}",
+        sent[0]
+    );
+    assert_eq!(Some(7), sent[0].reply_to());
+
+    // the result follows once every opted in chat got the text
+    let done = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let sent = api.sent_messages();
+            if let Some(done) = sent.iter().skip(1).find(|call| call.reply_to() == Some(7)) {
+                return (done.clone(), sent);
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await;
+    let (done, sent) = done.expect("broadcast didn't report back");
+
+    assert_eq!(Some(410_001), done.chat_id());
+    assert!(done.text().unwrap().contains("0 failed"), "{:?}", done);
+    for chat_id in [410_101, 410_102] {
+        assert!(
+            sent.iter()
+                .any(|call| call.chat_id() == Some(chat_id) && call.text() == Some("hello")),
+            "{} got no broadcast",
+            chat_id
+        );
+    }
+}
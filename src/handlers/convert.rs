@@ -1,35 +1,81 @@
-use std::fmt::Display;
-
-use anyhow::{Context, anyhow};
 use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use regex::Regex;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use std::sync::LazyLock;
-use teloxide::sugar::request::RequestReplyExt;
-use teloxide::{prelude::*, types::ParseMode};
+use teloxide::prelude::*;
 
 use crate::commands::Args;
-use crate::deps::http_client::http_client;
-use crate::error::{AsInternalError, HandlerError};
+use crate::error::HandlerError;
 use crate::handlers::forex::{ConvertResponseData, ForexResp};
-use crate::utils::money::format_money_str;
-
-// format of a currency code: USD, IDR, BTC, XAU. Case insensitive.
-static CURRENCY_FORMAT: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?i)^[a-z]{3}$").expect("failed initializing currency regex"));
+use crate::handlers::rate_buttons::{self, RateQuery};
+use crate::handlers::rate_sources::{self, ConvertQuery, Sourced};
+use crate::handlers::reply::Reply;
+use crate::i18n::{Lang, Localized, lang_for};
+use crate::utils::currency::render_notes;
+use crate::utils::date::today;
+use crate::utils::money::{Money, MoneyStyle};
+use crate::utils::syntax::{CURRENCY, Context, DATE, Kind, Matched, Part, Syntax, Value};
+use crate::utils::time::{default_timezone, format_rates_time, timezone_for};
 
 // format for amount: optional commas for thousands, optional decimal point
 static AMOUNT_FORMAT: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^[\d,]+(?:\.\d+)?$").expect("failed initializing amount regex"));
 
-static CONVERT_ENDPOINT: &'static str = "https://api.mfirhas.com/pfm/v2/forex/convert";
-
-static EMPTY_ARGS_DEFAULT: &'static str = "USD 1";
-static EMPTY_ARGS_TO: &'static str = "IDR";
-
-// Fallback values for display
-static INVALID_CURRENCY: &'static str = "INVALID";
-static ZERO_AMOUNT: &'static str = "0";
+// amount converted, one word
+static AMOUNT: Kind = Kind {
+    max_words: 1,
+    spans_separators: false,
+    parse: |text, _, _| {
+        if AMOUNT_FORMAT.is_match(text) {
+            Ok(Value::Text(text.to_string()))
+        } else {
+            Err(Localized::new("convert.amount_format").arg("amount", text))
+        }
+    },
+    claims: |text| text.starts_with(|c: char| c.is_ascii_digit() || c == '-'),
+};
+
+/// Forms of /convert arguments, their usage heads /help convert.
+pub(crate) static SYNTAX: LazyLock<Syntax> = LazyLock::new(|| {
+    let rest = || {
+        vec![
+            Part::Sep(';'),
+            Part::Arg("to", &CURRENCY),
+            Part::Optional(vec![Part::Sep(';'), Part::Arg("date", &DATE)]),
+        ]
+    };
+
+    Syntax::new("convert")
+        .form("default", vec![])
+        // USD 100; IDR
+        .form(
+            "convert",
+            [
+                vec![Part::Arg("from", &CURRENCY), Part::Arg("amount", &AMOUNT)],
+                rest(),
+            ]
+            .concat(),
+        )
+        // 100 us dollar; IDR
+        .form(
+            "convert",
+            [
+                vec![Part::Arg("amount", &AMOUNT), Part::Arg("from", &CURRENCY)],
+                rest(),
+            ]
+            .concat(),
+        )
+});
+
+static EMPTY_ARGS_FROM: &str = "USD";
+static EMPTY_ARGS_AMOUNT: &str = "1";
+static EMPTY_ARGS_TO: &str = "IDR";
+
+// converted amounts from here are also shown compact
+const COMPACT_FROM: Decimal = dec!(1_000_000);
 
 #[derive(Debug, Clone)]
 pub(crate) struct ConvertArg {
@@ -37,6 +83,9 @@ pub(crate) struct ConvertArg {
     pub(super) from_amount: String,
     pub(super) to_currency: String,
     pub(super) date: Option<DateTime<Utc>>,
+
+    // how ambiguous currency names were read
+    pub(super) notes: Vec<Localized>,
 }
 
 #[derive(Debug, Clone)]
@@ -49,84 +98,27 @@ impl TryFrom<Args> for ConvertArg {
     type Error = HandlerError;
 
     fn try_from(value: Args) -> Result<Self, Self::Error> {
-        let trimmed = value.0.trim();
-
-        // Split by semicolon
-        let parts: Vec<&str> = trimmed.split(';').collect();
-
-        if parts.len() < 2 || parts.len() > 3 {
-            return Err(HandlerError::InvalidArguments(anyhow!(
-                "Arguments must be in format: <FROM_CODE> <AMOUNT> ; <TO_CODE> [; <DATE>]\nExample: USD 50,000 ; IDR\nWith date: USD 50,000 ; IDR ; 2022-02-02"
-            )));
-        }
-
-        let from_part = parts[0].trim();
-        let to_part = parts[1].trim();
-
-        // Parse FROM part (currency code and amount)
-        let from_tokens: Vec<&str> = from_part.split_whitespace().collect();
-
-        if from_tokens.len() != 2 {
-            return Err(HandlerError::InvalidArguments(anyhow!(
-                "FROM part must have format: <CURRENCY_CODE> <AMOUNT>\nExample: USD 1000"
-            )));
-        }
-
-        let from_currency = from_tokens[0];
-        let from_amount = from_tokens[1];
-
-        // Validate currency code format
-        if !CURRENCY_FORMAT.is_match(from_currency) {
-            return Err(HandlerError::InvalidArguments(anyhow!(
-                "Currency code must be 3 letters (case insensitive). Got: {}",
-                from_currency
-            )));
-        }
+        ConvertArg::parse(value, today(default_timezone()))
+    }
+}
 
-        // Validate amount format
-        if !AMOUNT_FORMAT.is_match(from_amount) {
-            return Err(HandlerError::InvalidArguments(anyhow!(
-                "Amount must be a number with optional commas and decimal point. Got: {}",
-                from_amount
-            )));
-        }
+impl ConvertArg {
+    /// Arguments of a command sent on `today`, the day relative dates count from.
+    pub(crate) fn parse(value: Args, today: NaiveDate) -> Result<Self, HandlerError> {
+        let matched = SYNTAX.parse_form("convert", &value.0, &Context { today })?;
+        Ok(matched.into())
+    }
+}
 
-        // Parse TO part (just currency code)
-        if !CURRENCY_FORMAT.is_match(to_part) {
-            return Err(HandlerError::InvalidArguments(anyhow!(
-                "TO currency code must be 3 letters (case insensitive). Got: {}",
-                to_part
-            )));
+impl From<Matched> for ConvertArg {
+    fn from(matched: Matched) -> Self {
+        ConvertArg {
+            from_currency: matched.text("from"),
+            from_amount: matched.text("amount"),
+            to_currency: matched.text("to"),
+            date: matched.date("date"),
+            notes: matched.notes,
         }
-
-        // Parse optional date (third part after second semicolon)
-        let date = if parts.len() == 3 {
-            let date_str = parts[2].trim();
-
-            let naive = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
-                HandlerError::InvalidArguments(anyhow!(
-                    "Invalid date format \"{}\": {}. Expected YYYY-MM-DD format.",
-                    date_str,
-                    e
-                ))
-            })?;
-
-            Some(DateTime::<Utc>::from_naive_utc_and_offset(
-                naive
-                    .and_hms_opt(0, 0, 0)
-                    .ok_or(HandlerError::InvalidArguments(anyhow!("invalid date")))?,
-                Utc,
-            ))
-        } else {
-            None
-        };
-
-        Ok(ConvertArg {
-            from_currency: from_currency.to_ascii_uppercase(),
-            from_amount: from_amount.to_string(),
-            to_currency: to_part.to_ascii_uppercase(),
-            date,
-        })
     }
 }
 
@@ -134,14 +126,19 @@ impl TryFrom<Args> for ConvertArgs {
     type Error = HandlerError;
 
     fn try_from(value: Args) -> Result<Self, Self::Error> {
-        let args = value.0.as_str().trim();
+        ConvertArgs::parse(value, today(default_timezone()))
+    }
+}
 
-        if args.is_empty() {
-            return Ok(ConvertArgs::Empty);
-        }
+impl ConvertArgs {
+    /// Arguments of a command sent on `today`, the day relative dates count from.
+    pub(crate) fn parse(value: Args, today: NaiveDate) -> Result<Self, HandlerError> {
+        let matched = SYNTAX.parse(&value.0, &Context { today })?;
 
-        let convert_arg = ConvertArg::try_from(value)?;
-        Ok(ConvertArgs::Convert(convert_arg))
+        Ok(match matched.form() {
+            "convert" => ConvertArgs::Convert(matched.into()),
+            _ => ConvertArgs::Empty,
+        })
     }
 }
 
@@ -150,50 +147,62 @@ pub enum ConvertResponse {
     Single(ForexResp<ConvertResponseData>),
 }
 
-impl Display for ConvertResponse {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ret = match self {
+impl ConvertResponse {
+    pub(crate) fn render(&self, lang: Lang, tz: Tz) -> String {
+        match self {
             Self::Single(resp) => {
                 if let Some(ref err) = resp.error {
-                    format!("forex api error: {}", err)
+                    Localized::new("forex.api_error")
+                        .arg("error", err)
+                        .render(lang)
                 } else {
                     match resp.data {
                         Some(ref data) if data.from.is_empty() || data.to.is_empty() => {
-                            format!("invalid response: empty data")
+                            Localized::new("forex.invalid_response").render(lang)
                         }
 
-                        Some(ref data) => {
-                            let from_currency = data
-                                .from
-                                .keys()
-                                .next()
-                                .cloned()
-                                .unwrap_or(INVALID_CURRENCY.into());
-                            let from_amount = data
-                                .from
-                                .values()
-                                .next()
-                                .cloned()
-                                .unwrap_or(ZERO_AMOUNT.into());
-
-                            let from_fmt = format_money_str(&from_currency, &from_amount);
-
-                            format!(
-                                "Conversion on {}:\n<b>{} = {}</b>",
-                                data.date.format("%Y-%m-%d %H:%M:%S %:z").to_string(),
-                                from_fmt,
-                                data.code,
-                            )
-                        }
+                        Some(ref data) => match amounts(data) {
+                            Ok((from, to)) => Localized::new("convert.result")
+                                .arg("date", format_rates_time(data.date, tz))
+                                .arg("from", from.format(lang, MoneyStyle::Symbol))
+                                .arg("to", render_to(&to, lang))
+                                .render(lang),
+                            Err(err) => err.render(lang),
+                        },
 
-                        None => String::from("no data returned"),
+                        None => Localized::new("forex.no_data_returned").render(lang),
                     }
                 }
             }
-        };
+        }
+    }
+}
 
-        write!(f, "{}", ret)
+/// Both sides of an answer, the converted one with upstream's symbol.
+pub(crate) fn amounts(data: &ConvertResponseData) -> Result<(Money, Money), Localized> {
+    let from = data
+        .from
+        .iter()
+        .next()
+        .map(|(code, amount)| Money::parse(code, amount))
+        .ok_or(Localized::new("forex.invalid_response"))??;
+    let to = data
+        .to
+        .iter()
+        .next()
+        .map(|(code, amount)| Money::parse(code, amount))
+        .ok_or(Localized::new("forex.invalid_response"))??;
+
+    Ok((from, to.with_symbol_of(&data.symbol)))
+}
+
+fn render_to(to: &Money, lang: Lang) -> String {
+    let full = to.format(lang, MoneyStyle::Symbol);
+    if to.amount.abs() < COMPACT_FROM {
+        return full;
     }
+
+    format!("{} ({})", full, to.format(lang, MoneyStyle::Compact))
 }
 
 pub(crate) async fn convert_handler(
@@ -201,7 +210,7 @@ pub(crate) async fn convert_handler(
     msg: &Message,
     args: Args,
 ) -> Result<(), HandlerError> {
-    let arg: ConvertArgs = args.try_into()?;
+    let arg = ConvertArgs::parse(args, today(timezone_for(msg.chat.id.0)))?;
 
     match arg {
         ConvertArgs::Empty => empty_arg(bot, msg).await,
@@ -210,54 +219,84 @@ pub(crate) async fn convert_handler(
 }
 
 async fn empty_arg(bot: Bot, msg: &Message) -> Result<(), HandlerError> {
-    let http_client = http_client().clone();
-
-    let query_params: Vec<(&str, &str)> = vec![("from", EMPTY_ARGS_DEFAULT), ("to", EMPTY_ARGS_TO)];
-
-    let resp: ForexResp<ConvertResponseData> = http_client
-        .get(CONVERT_ENDPOINT)
-        .query(&query_params)
-        .send()
-        .await
-        .context("failed calling forex convert api")
-        .as_internal_err()?
-        .json()
-        .await?;
-
-    bot.send_message(msg.chat.id, ConvertResponse::Single(resp).to_string())
-        .reply_to(msg.id)
-        .parse_mode(ParseMode::Html)
-        .await?;
-
-    Ok(())
+    let resp = rate_sources::convert(&ConvertQuery {
+        from: EMPTY_ARGS_FROM.into(),
+        amount: EMPTY_ARGS_AMOUNT.into(),
+        to: EMPTY_ARGS_TO.into(),
+        date: None,
+    })
+    .await?;
+
+    let query = RateQuery::new(
+        msg,
+        EMPTY_ARGS_FROM,
+        EMPTY_ARGS_TO,
+        Some(EMPTY_ARGS_AMOUNT),
+        None,
+    );
+    reply(bot, msg, &query, resp, &[]).await
 }
 
 async fn convert(bot: Bot, msg: &Message, convert_arg: ConvertArg) -> Result<(), HandlerError> {
-    let http_client = http_client().clone();
-
-    let from_param = format!("{} {}", convert_arg.from_currency, convert_arg.from_amount);
+    let notes = convert_arg.notes.clone();
+    let query = RateQuery::new(
+        msg,
+        &convert_arg.from_currency,
+        &convert_arg.to_currency,
+        Some(&convert_arg.from_amount),
+        convert_arg.date,
+    );
+    let resp = fetch_convert(convert_arg).await?;
+
+    reply(bot, msg, &query, resp, &notes).await
+}
 
-    let mut query_params: Vec<(&str, String)> =
-        vec![("from", from_param), ("to", convert_arg.to_currency)];
+async fn reply(
+    bot: Bot,
+    msg: &Message,
+    query: &RateQuery,
+    resp: Sourced<ConvertResponseData>,
+    notes: &[Localized],
+) -> Result<(), HandlerError> {
+    let lang = lang_for(msg);
+    let tz = timezone_for(msg.chat.id.0);
 
-    if let Some(date) = convert_arg.date {
-        query_params.push(("date", date.format("%Y-%m-%d").to_string()));
+    let mut reply = Reply::new(reply_text(resp, notes, lang, tz)).html().quote();
+    if let Some(keyboard) = rate_buttons::keyboard(query, lang) {
+        reply = reply.keyboard(keyboard);
     }
-
-    let resp: ForexResp<ConvertResponseData> = http_client
-        .get(CONVERT_ENDPOINT)
-        .query(&query_params)
-        .send()
-        .await
-        .context("failed calling forex convert api")
-        .as_internal_err()?
-        .json()
-        .await?;
-
-    bot.send_message(msg.chat.id, ConvertResponse::Single(resp).to_string())
-        .reply_to(msg.id)
-        .parse_mode(ParseMode::Html)
-        .await?;
+    reply.send(&bot, msg).await?;
 
     Ok(())
 }
+
+/// Reply of a conversion, with notes on how currencies were read and its source.
+pub(crate) fn reply_text(
+    resp: Sourced<ConvertResponseData>,
+    notes: &[Localized],
+    lang: Lang,
+    tz: Tz,
+) -> String {
+    let footer = resp.footer(lang);
+    format!(
+        "{}\n\n{}{}",
+        ConvertResponse::Single(resp.resp).render(lang, tz),
+        render_notes(notes, lang),
+        footer
+    )
+    .trim_end()
+    .to_string()
+}
+
+/// Conversion from the first source answering, shared by the bot and the REST API.
+pub(crate) async fn fetch_convert(
+    convert_arg: ConvertArg,
+) -> Result<Sourced<ConvertResponseData>, HandlerError> {
+    rate_sources::convert(&ConvertQuery {
+        from: convert_arg.from_currency,
+        amount: convert_arg.from_amount,
+        to: convert_arg.to_currency,
+        date: convert_arg.date,
+    })
+    .await
+}
@@ -1,7 +1,12 @@
 use chrono::{TimeZone, Utc};
 
+use crate::testing::fake_bot::{
+    Call, FakeBotApi, HandlerTreeError, message_update, private_message,
+};
+use crate::testing::fake_pfm::{FakePfmApi, Mode};
 use crate::{
     commands::Args,
+    handler,
     handlers::convert::{ConvertArg, ConvertArgs},
 };
 
@@ -87,6 +92,25 @@ fn extra_whitespace_handling() {
     assert_eq!("IDR", ret.to_currency);
 }
 
+#[test]
+fn currency_names_with_amount_first() {
+    let ret: ConvertArg = Args("100 us dollar ; rupiah".into()).try_into().unwrap();
+
+    assert_eq!("USD", ret.from_currency);
+    assert_eq!("100", ret.from_amount);
+    assert_eq!("IDR", ret.to_currency);
+    assert!(ret.notes.is_empty());
+
+    let ret: ConvertArg = Args("50 bucks ; ringgit".into()).try_into().unwrap();
+    assert_eq!("USD", ret.from_currency);
+    assert_eq!("MYR", ret.to_currency);
+    assert_eq!(1, ret.notes.len());
+
+    let ret: ConvertArg = Args("perak 2 ; idr".into()).try_into().unwrap();
+    assert_eq!("XAG", ret.from_currency);
+    assert_eq!("2", ret.from_amount);
+}
+
 #[test]
 fn invalid_missing_semicolon() {
     let args = Args("USD 1000 IDR".into());
@@ -229,7 +253,7 @@ fn invalid_date_format_wrong_separator() {
 
 #[test]
 fn invalid_date_text() {
-    let args = Args("USD 1000 ; IDR ; yesterday".into());
+    let args = Args("USD 1000 ; IDR ; someday".into());
     let ret: Result<ConvertArg, _> = args.try_into();
     assert!(ret.is_err());
 }
@@ -240,3 +264,97 @@ fn invalid_too_many_parts() {
     let ret: Result<ConvertArg, _> = args.try_into();
     assert!(ret.is_err());
 }
+
+async fn convert(
+    pfm: &FakePfmApi,
+    user_id: i64,
+    text: &str,
+) -> (Result<(), HandlerTreeError>, Vec<Call>) {
+    let api = FakeBotApi::start().await;
+    let ret = pfm
+        .scope(api.dispatch(
+            handler(),
+            message_update(1, private_message(1, user_id, text)),
+        ))
+        .await;
+
+    (ret, api.sent_messages())
+}
+
+#[tokio::test]
+async fn convert_from_upstream() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, sent) = convert(&pfm, 330_001, "/convert usd 100; idr").await;
+
+    ret.unwrap();
+    assert_eq!(vec!["convert_from-usd-100_to-idr.json"], pfm.requests());
+    assert_eq!(Some(1), sent[0].reply_to());
+    assert_eq!(Some("HTML"), sent[0].parse_mode());
+    assert_eq!(
+        Some(
+            "Conversion on 2024-03-08:\n<b>US$100.00 = Rp1,573,000.00 (Rp1.6M)</b>\n\n<i>Source: pfm API, updated just now</i>"
+        ),
+        sent[0].text()
+    );
+}
+
+#[tokio::test]
+async fn historical_convert_from_upstream() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, sent) = convert(&pfm, 330_002, "/convert EUR 50,000; JPY; 2022-02-02").await;
+
+    ret.unwrap();
+    let text = sent[0].text().unwrap();
+    assert!(
+        text.starts_with("Conversion on 2022-02-02:"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("\n<b>€50,000.00 = ¥6,472,500 (¥6.5M)</b>\n"),
+        "{}",
+        text
+    );
+}
+
+#[tokio::test]
+async fn empty_convert_defaults_to_usd_idr() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, _) = convert(&pfm, 330_003, "/convert").await;
+
+    ret.unwrap();
+    assert_eq!(vec!["convert_from-usd-1_to-idr.json"], pfm.requests());
+}
+
+#[tokio::test]
+async fn convert_upstream_outcomes() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ErrorField("amount is too large".into()));
+
+    let (ret, sent) = convert(&pfm, 330_004, "/convert USD 1; EUR").await;
+    ret.unwrap();
+    assert_eq!(
+        Some("forex api error: amount is too large"),
+        sent[0].text()
+    );
+
+    for mode in [Mode::ServerError, Mode::MalformedJson] {
+        let pfm = FakePfmApi::start().await;
+        pfm.set_mode(mode.clone());
+
+        let (ret, sent) = convert(&pfm, 330_004, "/convert USD 1; EUR").await;
+
+        assert!(ret.is_err(), "{:?}", mode);
+        assert!(
+            sent[0]
+                .text()
+                .unwrap()
+                .starts_with("The rates service couldn't answer this request"),
+            "{:?}",
+            mode
+        );
+    }
+}
@@ -1,37 +1,65 @@
-use std::{collections::HashMap, fmt::Display};
+use std::collections::HashMap;
+use std::time::Duration;
 
 use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 
-use anyhow::{Context, anyhow};
-use teloxide::sugar::request::RequestReplyExt;
-use teloxide::{prelude::*, types::ParseMode};
+use teloxide::prelude::*;
 
+use crate::commands::Args;
+use crate::config::config;
 use crate::error::HandlerError;
-use crate::{commands::Args, deps::http_client::http_client, error::AsInternalError};
-use regex::Regex;
+use crate::handlers::rate_buttons::{self, RateQuery};
+use crate::handlers::rate_pages::{self, PageQuery};
+use crate::handlers::rate_sources::{self, ConvertQuery, RatesQuery, Sourced, source_footer};
+use crate::handlers::rate_stats::{self, RANGE, STATS_WORDS, StatsArg};
+use crate::handlers::reply::Reply;
+use crate::i18n::{Lang, Localized, lang_for, t};
+use crate::utils::currency::{looks_like_code, read_currency, render_notes};
+use crate::utils::date::today;
+use crate::utils::syntax::{CURRENCY, Context, DATE, Kind, Matched, Part, Syntax, Value};
+use crate::utils::time::{default_timezone, format_rates_time, format_time, timezone_for};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
+use std::str::FromStr;
 use std::sync::LazyLock;
 
-// format pair of currencies: USD/IDR, BTC/USD, XAU/USD, etc. Case insensitive.
-static FOREX_PAIR_FORMAT: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"(?i)^[A-Z]{3}/[A-Z]{3}$").expect("failed initializing forex regex")
-});
+// pairs shown by /forex without arguments
+static WATCHLIST: &[(&str, &str)] = &[
+    ("USD", "IDR"),
+    ("BTC", "USD"),
+    ("XAU", "USD"),
+    ("XAU", "IDR"),
+    ("XAG", "USD"),
+    ("XAG", "IDR"),
+];
+
+// first page of /forex <base>, the rest is paged through alphabetically or by strength
+static TOP_QUOTES: &[&str] = &[
+    "USD", "EUR", "JPY", "GBP", "CNY", "AUD", "CAD", "CHF", "HKD", "SGD", "IDR", "MYR", "THB",
+    "KRW", "INR", "SAR", "XAU", "BTC",
+];
+
+// rates on each page after the top list
+const RATES_PER_PAGE: usize = 20;
 
-// format of a currency code: USD, IDR, BTC, XAU. Case insensitive.
-static FOREX_FORMAT: LazyLock<Regex> =
-    LazyLock::new(|| Regex::new(r"(?i)^[a-z]{3}$").expect("failed initializing forex regex"));
+// quotes asked for by name, more are better served by the pages
+const MAX_QUOTES: usize = 20;
 
-static FOREX_CONVERT_ENDPOINT: &'static str = "https://api.mfirhas.com/pfm/v2/forex/convert";
+// most words of quotes, a few names may have more than one
+const MAX_QUOTE_WORDS: usize = 2 * MAX_QUOTES;
 
-static FOREX_RATES_ENDPOINT: &'static str = "https://api.mfirhas.com/pfm/v2/forex/rates";
+// words ordering base rates, see RatesSort::from_word
+static SORT_WORDS: &[&[&str]] = &[&["az"], &["strength"]];
 
-static EMPTY_ARGS_ERROR: &'static str = "Arguments must be provided.\nArguments are: \n1. Pair of forex: e.g. \"USD/IDR\", \n2. (Optional) Date of rate, e.g.\"USD/IDR 2022-02-02\" ";
+// rates of a past date don't change anymore
+pub(crate) const HISTORICAL_CACHE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
 
 #[derive(Debug, Serialize, Deserialize)]
 pub enum ForexResponse {
-    EmptyArgResponse(Vec<ForexResp<ConvertResponseData>>),
-    SinglePairArgResponse(ForexResp<ConvertResponseData>),
-    BaseRatesResponse(ForexResp<RatesResponseData>),
+    EmptyArg(Vec<ForexResp<ConvertResponseData>>),
+    SinglePair(ForexResp<ConvertResponseData>),
+    BaseRates(ForexResp<RatesResponseData>, RatesView),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -69,6 +97,9 @@ pub(crate) struct SinglePairArg {
 
     // date of historical rates
     pub(super) date: Option<DateTime<Utc>>,
+
+    // how ambiguous currency names were read
+    pub(super) notes: Vec<Localized>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,55 +107,143 @@ pub(crate) struct BaseRatesArg {
     pub(super) base: String,
 
     pub(super) date: Option<DateTime<Utc>>,
+
+    pub(super) notes: Vec<Localized>,
+
+    // only these quotes, e.g. /forex USD EUR,JPY. All of them when empty
+    pub(super) quotes: Vec<String>,
+
+    pub(super) sort: RatesSort,
+}
+
+/// Order of base rates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) enum RatesSort {
+    #[default]
+    Alphabetical,
+
+    /// Strongest currency against the base first, the one with the fewest units per base.
+    Strength,
 }
 
+impl RatesSort {
+    /// Sort option as typed after the base, e.g. /forex USD strength
+    fn from_word(word: &str) -> Option<Self> {
+        match word.to_ascii_lowercase().as_str() {
+            "az" => Some(RatesSort::Alphabetical),
+            "strength" => Some(RatesSort::Strength),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            RatesSort::Alphabetical => "a",
+            RatesSort::Strength => "s",
+        }
+    }
+
+    pub(crate) fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "a" => Some(RatesSort::Alphabetical),
+            "s" => Some(RatesSort::Strength),
+            _ => None,
+        }
+    }
+}
+
+/// Part of base rates shown in one reply.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RatesView {
+    // requested quotes, shown at once. Empty for the top list and pages of the rest
+    pub quotes: Vec<String>,
+
+    pub sort: RatesSort,
+
+    // 0 is the top list
+    pub page: usize,
+}
+
+/// Forms of /forex arguments, their usage heads /help forex.
+pub(crate) static SYNTAX: LazyLock<Syntax> = LazyLock::new(|| {
+    let pair = || {
+        vec![
+            Part::Arg("from", &CURRENCY),
+            Part::Sep('/'),
+            Part::Arg("to", &CURRENCY),
+        ]
+    };
+
+    Syntax::new("forex")
+        .form("watchlist", vec![])
+        .form(
+            "pair",
+            [pair(), vec![Part::Optional(vec![Part::Arg("date", &DATE)])]].concat(),
+        )
+        .form(
+            "rates",
+            vec![
+                Part::Arg("base", &CURRENCY),
+                Part::AnyOrder(vec![
+                    Part::Optional(vec![Part::Arg("quotes", &QUOTES)]),
+                    Part::Optional(vec![Part::Arg("date", &DATE)]),
+                    Part::Optional(vec![Part::Word("sort", SORT_WORDS)]),
+                ]),
+            ],
+        )
+        .form(
+            "stats",
+            [
+                pair(),
+                vec![
+                    Part::Word("stats", &[STATS_WORDS]),
+                    Part::Optional(vec![Part::Arg("range", &RANGE)]),
+                ],
+            ]
+            .concat(),
+        )
+});
+
+// quotes of base rates, e.g. EUR,JPY,IDR
+static QUOTES: Kind = Kind {
+    max_words: MAX_QUOTE_WORDS,
+    spans_separators: false,
+    parse: |text, _, notes| parse_quotes(text, notes),
+    claims: |text| text.split(',').any(looks_like_code),
+};
+
 #[derive(Debug, Clone)]
 pub(crate) enum ForexArgs {
     Empty,
     SinglePair(SinglePairArg),
     BaseRates(BaseRatesArg),
+    Stats(StatsArg),
 }
 
 impl TryFrom<Args> for SinglePairArg {
     type Error = HandlerError;
 
     fn try_from(value: Args) -> Result<Self, Self::Error> {
-        let parts: Vec<&str> = value.0.trim().split_whitespace().collect();
-
-        if parts.is_empty() {
-            return Err(HandlerError::InvalidArguments(anyhow!(EMPTY_ARGS_ERROR)));
-        }
+        SinglePairArg::parse(value, today(default_timezone()))
+    }
+}
 
-        let pair = parts[0];
-        let pair_parts: Vec<&str> = pair.split('/').collect();
+impl SinglePairArg {
+    /// Arguments of a command sent on `today`, the day relative dates count from.
+    pub(crate) fn parse(value: Args, today: NaiveDate) -> Result<Self, HandlerError> {
+        let matched = SYNTAX.parse_form("pair", &value.0, &Context { today })?;
+        Ok(matched.into())
+    }
+}
 
-        if pair_parts.len() != 2 || !FOREX_PAIR_FORMAT.is_match(pair) {
-            return Err(HandlerError::InvalidArguments(anyhow!(
-                "Forex pair must be in format XXX/YYY"
-            )));
+impl From<Matched> for SinglePairArg {
+    fn from(matched: Matched) -> Self {
+        SinglePairArg {
+            left: matched.text("from"),
+            right: matched.text("to"),
+            date: matched.date("date"),
+            notes: matched.notes,
         }
-
-        let left = pair_parts[0].to_ascii_uppercase();
-        let right = pair_parts[1].to_ascii_uppercase();
-
-        let date = if parts.len() >= 2 {
-            let date_str = parts[1];
-
-            let naive = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
-                HandlerError::InvalidArguments(anyhow!("invalid date {}, {}", date_str, e))
-            })?;
-
-            Some(DateTime::<Utc>::from_naive_utc_and_offset(
-                naive
-                    .and_hms_opt(0, 0, 0)
-                    .ok_or(HandlerError::InvalidArguments(anyhow!("invalid date")))?,
-                Utc,
-            ))
-        } else {
-            None
-        };
-
-        Ok(SinglePairArg { left, right, date })
     }
 }
 
@@ -132,82 +251,92 @@ impl TryFrom<Args> for BaseRatesArg {
     type Error = HandlerError;
 
     fn try_from(value: Args) -> Result<Self, Self::Error> {
-        let parts: Vec<&str> = value.0.trim().split_whitespace().collect();
-
-        if parts.is_empty() {
-            return Err(HandlerError::InvalidArguments(anyhow!(EMPTY_ARGS_ERROR)));
-        }
+        BaseRatesArg::parse(value, today(default_timezone()))
+    }
+}
 
-        let curr = parts[0];
+impl BaseRatesArg {
+    /// Arguments of a command sent on `today`, the day relative dates count from.
+    pub(crate) fn parse(value: Args, today: NaiveDate) -> Result<Self, HandlerError> {
+        let matched = SYNTAX.parse_form("rates", &value.0, &Context { today })?;
+        Ok(matched.into())
+    }
+}
 
-        if !FOREX_FORMAT.is_match(curr) {
-            return Err(HandlerError::InvalidArguments(anyhow!(
-                "Forex pair must be in format XXX, case insensitive"
-            )));
+impl From<Matched> for BaseRatesArg {
+    fn from(matched: Matched) -> Self {
+        BaseRatesArg {
+            base: matched.text("base"),
+            date: matched.date("date"),
+            quotes: matched.list("quotes"),
+            sort: matched
+                .word("sort")
+                .and_then(RatesSort::from_word)
+                .unwrap_or_default(),
+            notes: matched.notes,
         }
+    }
+}
 
-        let date = if parts.len() >= 2 {
-            let date_str = parts[1];
-
-            let naive = NaiveDate::parse_from_str(date_str, "%Y-%m-%d").map_err(|e| {
-                HandlerError::InvalidArguments(anyhow!("invalid date {}, {}", date_str, e))
-            })?;
-
-            Some(DateTime::<Utc>::from_naive_utc_and_offset(
-                naive
-                    .and_hms_opt(0, 0, 0)
-                    .ok_or(HandlerError::InvalidArguments(anyhow!("invalid date")))?,
-                Utc,
-            ))
-        } else {
-            None
-        };
-
-        Ok(BaseRatesArg {
-            base: curr.to_ascii_uppercase(),
-            date: date,
-        })
+// comma separated, names may have spaces: EUR, us dollar,yen
+fn parse_quotes(text: &str, notes: &mut Vec<Localized>) -> Result<Value, Localized> {
+    let mut quotes = vec![];
+    for name in text.split(',').map(str::trim) {
+        if name.is_empty() {
+            continue;
+        }
+        let quote = read_currency(name, notes, || {
+            Localized::new("forex.quote_format").arg("quote", name)
+        })?;
+        if !quotes.contains(&quote) {
+            quotes.push(quote);
+        }
+    }
+    if quotes.is_empty() {
+        return Err(Localized::new("forex.quote_format").arg("quote", text));
     }
+    if quotes.len() > MAX_QUOTES {
+        return Err(Localized::new("forex.too_many_quotes").arg("max", MAX_QUOTES));
+    }
+
+    Ok(Value::List(quotes))
 }
 
 impl TryFrom<Args> for ForexArgs {
     type Error = HandlerError;
 
     fn try_from(value: Args) -> Result<Self, Self::Error> {
-        let args = value.0.as_str().trim();
-
-        if args.is_empty() {
-            return Ok(ForexArgs::Empty);
-        }
-
-        if let Ok(ret) = SinglePairArg::try_from(value.clone()) {
-            return Ok(ForexArgs::SinglePair(ret));
-        }
+        ForexArgs::parse(value, today(default_timezone()))
+    }
+}
 
-        if let Ok(ret) = BaseRatesArg::try_from(value.clone()) {
-            return Ok(ForexArgs::BaseRates(ret));
-        }
+impl ForexArgs {
+    /// Arguments of a command sent on `today`, the day relative dates count from.
+    pub(crate) fn parse(value: Args, today: NaiveDate) -> Result<Self, HandlerError> {
+        let matched = SYNTAX.parse(&value.0, &Context { today })?;
 
-        Err(HandlerError::InvalidArguments(anyhow!(
-            "Invalid arguments: {}",
-            value.0
-        )))
+        Ok(match matched.form() {
+            "pair" => ForexArgs::SinglePair(matched.into()),
+            "rates" => ForexArgs::BaseRates(matched.into()),
+            "stats" => ForexArgs::Stats(StatsArg::from_matched(matched, today)?),
+            _ => ForexArgs::Empty,
+        })
     }
 }
 
-impl Display for ForexResponse {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let ret = match self {
-            Self::EmptyArgResponse(resp) => {
+impl ForexResponse {
+    pub(crate) fn render(&self, lang: Lang, tz: Tz) -> String {
+        match self {
+            Self::EmptyArg(resp) => {
                 if resp.is_empty() {
-                    "Empty forex data".to_owned()
+                    Localized::new("forex.empty_data").render(lang)
                 } else {
-                    let date = if let Some(ref data) = resp.get(0)
+                    let date = if let Some(data) = resp.first()
                         && let Some(ref inner_data) = data.data
                     {
-                        inner_data.date.format("%Y-%m-%d %H:%M:%S %:z").to_string()
+                        format_rates_time(inner_data.date, tz)
                     } else {
-                        Utc::now().format("%Y-%m-%d %H:%M:%S %:z").to_string()
+                        format_time(Utc::now(), tz)
                     };
 
                     let mut content: String = "".to_string();
@@ -221,23 +350,34 @@ impl Display for ForexResponse {
                             content
                                 .push_str(format!("\n- <b>{}= {}</b>", pair, data.code).as_str());
                         } else if let Some(ref err) = r.error {
-                            content.push_str(format!("\nerror: {}", err).as_str());
+                            content.push('\n');
+                            content.push_str(
+                                &Localized::new("forex.item_error")
+                                    .arg("error", err)
+                                    .render(lang),
+                            );
                         } else {
-                            content.push_str("\nno data");
+                            content.push('\n');
+                            content.push_str(&Localized::new("forex.no_data").render(lang));
                         }
                     }
 
-                    format!("Forex data on {}:{}", date, content)
+                    Localized::new("forex.watchlist")
+                        .arg("date", date)
+                        .arg("content", content)
+                        .render(lang)
                 }
             }
 
-            Self::SinglePairArgResponse(resp) => {
+            Self::SinglePair(resp) => {
                 if let Some(ref err) = resp.error {
-                    format!("forex api error: {}", err)
+                    Localized::new("forex.api_error")
+                        .arg("error", err)
+                        .render(lang)
                 } else {
-                    let single_pair_ret = match resp.data {
+                    match resp.data {
                         Some(ref data) if data.from.is_empty() || data.to.is_empty() => {
-                            format!("invalid response empty data")
+                            Localized::new("forex.invalid_response").render(lang)
                         }
 
                         Some(ref data) => {
@@ -245,76 +385,150 @@ impl Display for ForexResponse {
                             let to = data.to.keys().next().cloned().unwrap_or("INVALID".into());
                             let pair = format!("{}/{}", from, to);
 
-                            format!(
-                                "{} on {} is:\n<b>{}</b>",
-                                pair,
-                                data.date.format("%Y-%m-%d %H:%M:%S %:z").to_string(),
-                                data.code,
-                            )
+                            Localized::new("forex.single_pair")
+                                .arg("pair", pair)
+                                .arg("date", format_rates_time(data.date, tz))
+                                .arg("rate", &data.code)
+                                .render(lang)
                         }
 
-                        None => String::from("no data returned"),
-                    };
-                    single_pair_ret
+                        None => Localized::new("forex.no_data_returned").render(lang),
+                    }
                 }
             }
 
-            Self::BaseRatesResponse(resp) => {
+            Self::BaseRates(resp, view) => {
                 if let Some(ref err) = resp.error {
-                    format!("forex api error: {}", err)
+                    Localized::new("forex.api_error")
+                        .arg("error", err)
+                        .render(lang)
                 } else {
-                    let base_rates_ret = match resp.data {
+                    match resp.data {
                         Some(ref data) if data.rates.is_empty() => {
-                            format!("invalid response empty data")
+                            Localized::new("forex.invalid_response").render(lang)
                         }
 
-                        Some(ref data) => {
-                            let mut content: String = format!(
-                                "Rates with base {} on {}:\n",
-                                &data.base,
-                                data.rates_date.format("%Y-%m-%d %H:%M:%S %:z").to_string()
-                            );
-
-                            let mut first: Vec<(String, String)> = Vec::new();
-                            let mut second: Vec<(String, String)> = Vec::new();
-
-                            for (k, v) in &data.rates {
-                                if k == &data.base.to_ascii_lowercase() {
-                                    first.push((k.clone(), v.clone()));
-                                } else {
-                                    second.push((k.clone(), v.clone()));
-                                }
-                            }
+                        Some(ref data) => view.render(data, lang, tz),
 
-                            second.sort_by(|a, b| a.0.cmp(&b.0));
+                        None => Localized::new("forex.no_data_returned").render(lang),
+                    }
+                }
+            }
+        }
+    }
+}
 
-                            first.append(&mut second);
+impl RatesView {
+    /// Number of pages of `data`: the top list, then the rest. Requested quotes fit one page.
+    pub(crate) fn pages(&self, data: &RatesResponseData) -> usize {
+        if !self.quotes.is_empty() {
+            return 1;
+        }
 
-                            for (k, v) in &first {
-                                let key = if k == &data.base.to_ascii_lowercase() {
-                                    &format!("<b>{}</b>", k.to_ascii_uppercase())
-                                } else {
-                                    k
-                                };
-                                content.push_str(format!("\n{}: {}", key, v).as_str());
-                            }
+        1 + self.rest(data).len().div_ceil(RATES_PER_PAGE)
+    }
 
-                            content
-                        }
+    pub(crate) fn render(&self, data: &RatesResponseData, lang: Lang, tz: Tz) -> String {
+        let mut content = Localized::plural("forex.base_rates", data.rates.len() as i64)
+            .arg("base", &data.base)
+            .arg("date", format_rates_time(data.rates_date, tz))
+            .render(lang);
+        content.push('\n');
+
+        let base = data.base.to_ascii_lowercase();
+        let rate = |code: &str| {
+            data.rates
+                .iter()
+                .find(|(k, _)| k.eq_ignore_ascii_case(code))
+                .map(|(_, v)| v.clone())
+        };
 
-                        None => String::from("no data returned"),
-                    };
-                    base_rates_ret
-                }
+        // pages past the end, e.g. of a forged button or after rates went missing, show the last one
+        let page = self.page.min(self.pages(data) - 1);
+        let entries: Vec<(String, Option<String>)> = if !self.quotes.is_empty() {
+            self.quotes
+                .iter()
+                .map(|quote| (quote.to_ascii_lowercase(), rate(quote)))
+                .collect()
+        } else if page == 0 {
+            if let Some(value) = rate(&base) {
+                content.push_str(&format!(
+                    "\n<b>{}</b>: {}",
+                    data.base.to_ascii_uppercase(),
+                    value
+                ));
             }
+            TOP_QUOTES
+                .iter()
+                .map(|quote| quote.to_ascii_lowercase())
+                .filter(|quote| *quote != base)
+                .filter_map(|quote| rate(&quote).map(|value| (quote, Some(value))))
+                .collect()
+        } else {
+            self.rest(data)
+                .into_iter()
+                .skip((page - 1) * RATES_PER_PAGE)
+                .take(RATES_PER_PAGE)
+                .map(|(code, value)| (code, Some(value)))
+                .collect()
         };
 
-        write!(f, "{}", ret)
+        for (code, value) in self.sorted(entries) {
+            match value {
+                Some(value) => content.push_str(&format!("\n{}: {}", code, value)),
+                None => content.push_str(&format!("\n{}: {}", code, t(lang, "forex.no_data"))),
+            }
+        }
+
+        content
+    }
+
+    // rates after the top list, sorted
+    fn rest(&self, data: &RatesResponseData) -> Vec<(String, String)> {
+        let base = data.base.to_ascii_lowercase();
+        let rest = data
+            .rates
+            .iter()
+            .map(|(code, value)| (code.to_ascii_lowercase(), value.clone()))
+            .filter(|(code, _)| {
+                *code != base && !TOP_QUOTES.iter().any(|top| top.eq_ignore_ascii_case(code))
+            })
+            .map(|(code, value)| (code, Some(value)))
+            .collect();
+
+        self.sorted(rest)
+            .into_iter()
+            .filter_map(|(code, value)| value.map(|value| (code, value)))
+            .collect()
+    }
+
+    fn sorted(&self, mut entries: Vec<(String, Option<String>)>) -> Vec<(String, Option<String>)> {
+        match self.sort {
+            RatesSort::Alphabetical => entries.sort_by(|a, b| a.0.cmp(&b.0)),
+            // fewest units per base first, rates without a value last
+            RatesSort::Strength => entries.sort_by_key(|(code, value)| {
+                let value = value
+                    .as_deref()
+                    .and_then(|value| Decimal::from_str(&value.replace(',', "")).ok());
+                (value.is_none(), value, code.clone())
+            }),
+        }
+
+        entries
+    }
+}
+
+/// How long an upstream response may be served from cache.
+pub(crate) fn cache_ttl(historical: bool) -> Duration {
+    if historical {
+        HISTORICAL_CACHE_TTL
+    } else {
+        Duration::from_secs(config().cache_ttl_secs)
     }
 }
 
 pub(crate) async fn forex_handler(bot: Bot, msg: &Message, args: Args) -> Result<(), HandlerError> {
-    let arg: ForexArgs = args.try_into()?;
+    let arg = ForexArgs::parse(args, today(timezone_for(msg.chat.id.0)))?;
 
     match arg {
         ForexArgs::Empty => empty_arg(bot.clone(), msg).await,
@@ -322,42 +536,63 @@ pub(crate) async fn forex_handler(bot: Bot, msg: &Message, args: Args) -> Result
             single_pair(bot.clone(), msg, single_pair_arg).await
         }
         ForexArgs::BaseRates(base_rates_arg) => base_rates(bot, msg, base_rates_arg).await,
+        ForexArgs::Stats(stats_arg) => rate_stats::stats(bot, msg, stats_arg).await,
     }
 }
 
 async fn empty_arg(bot: Bot, msg: &Message) -> Result<(), HandlerError> {
-    let http_client = http_client().clone();
+    let mut resp: Vec<ForexResp<ConvertResponseData>> = vec![];
+    let mut infos = vec![];
+    let mut first_err = None;
+    let lang = lang_for(msg);
+    let tz = timezone_for(msg.chat.id.0);
+
+    // a pair no source can answer doesn't hide the others
+    for (from, to) in WATCHLIST {
+        let query = ConvertQuery {
+            from: from.to_string(),
+            amount: "1".into(),
+            to: to.to_string(),
+            date: None,
+        };
 
-    let query_params: Vec<Vec<(&str, &str)>> = vec![
-        vec![("from", "USD 1"), ("to", "IDR")],
-        vec![("from", "BTC 1"), ("to", "USD")],
-        vec![("from", "XAU 1"), ("to", "USD")],
-        vec![("from", "XAU 1"), ("to", "IDR")],
-        vec![("from", "XAG 1"), ("to", "USD")],
-        vec![("from", "XAG 1"), ("to", "IDR")],
-    ];
+        match rate_sources::convert(&query).await {
+            Ok(ret) => {
+                if ret.resp.error.is_none() {
+                    infos.push(ret.info);
+                }
+                resp.push(ret.resp);
+            }
+            Err(err) => {
+                resp.push(ForexResp {
+                    data: None,
+                    error: Some(format!("{}/{}: {}", from, to, err.user_message(lang))),
+                });
+                first_err.get_or_insert(err);
+            }
+        }
+    }
 
-    let mut resp: Vec<ForexResp<ConvertResponseData>> = vec![];
-    for query in &query_params {
-        let ret: ForexResp<ConvertResponseData> = http_client
-            .get(FOREX_CONVERT_ENDPOINT)
-            .query(query)
-            .send()
-            .await
-            .context("failed calling forex convert api")
-            .as_internal_err()?
-            .json()
-            .await?;
-
-        resp.push(ret);
+    if infos.is_empty()
+        && let Some(err) = first_err
+    {
+        return Err(err);
     }
 
-    bot.send_message(
-        msg.chat.id,
-        ForexResponse::EmptyArgResponse(resp).to_string(),
-    )
-    .reply_to(msg.id)
-    .parse_mode(ParseMode::Html)
+    // pairs answered with an error field have no source to credit
+    let footer = if infos.is_empty() {
+        String::new()
+    } else {
+        source_footer(&infos, lang)
+    };
+    Reply::new(format!(
+        "{}\n\n{}",
+        ForexResponse::EmptyArg(resp).render(lang, tz),
+        footer
+    ))
+    .html()
+    .quote()
+    .send(&bot, msg)
     .await?;
 
     Ok(())
@@ -368,72 +603,112 @@ async fn single_pair(
     msg: &Message,
     single_pair_args: SinglePairArg,
 ) -> Result<(), HandlerError> {
-    let http_client = http_client().clone();
+    let notes = single_pair_args.notes.clone();
+    let query = RateQuery::new(
+        msg,
+        &single_pair_args.left,
+        &single_pair_args.right,
+        None,
+        single_pair_args.date,
+    );
+    let ret = fetch_single_pair(single_pair_args).await?;
+    let lang = lang_for(msg);
+    let tz = timezone_for(msg.chat.id.0);
+
+    let mut reply = Reply::new(single_pair_text(ret, &notes, lang, tz))
+        .html()
+        .quote();
+    if let Some(keyboard) = rate_buttons::keyboard(&query, lang) {
+        reply = reply.keyboard(keyboard);
+    }
+    reply.send(&bot, msg).await?;
 
-    let query_params: Vec<(&str, String)> = if let Some(date) = single_pair_args.date {
-        vec![
-            ("from", format!("{} 1", &single_pair_args.left)),
-            ("to", single_pair_args.right),
-            ("date", date.format("%Y-%m-%d").to_string()),
-        ]
-    } else {
-        vec![
-            ("from", format!("{} 1", &single_pair_args.left)),
-            ("to", single_pair_args.right),
-        ]
-    };
+    Ok(())
+}
 
-    let ret: ForexResp<ConvertResponseData> = http_client
-        .get(FOREX_CONVERT_ENDPOINT)
-        .query(&query_params)
-        .send()
-        .await
-        .context("failed calling forex convert api")
-        .as_internal_err()?
-        .json()
-        .await?;
-
-    bot.send_message(
-        msg.chat.id,
-        ForexResponse::SinglePairArgResponse(ret).to_string(),
+/// Reply of a pair's rate, with notes on how the pair was read and its source.
+pub(crate) fn single_pair_text(
+    ret: Sourced<ConvertResponseData>,
+    notes: &[Localized],
+    lang: Lang,
+    tz: Tz,
+) -> String {
+    let footer = ret.footer(lang);
+    format!(
+        "{}\n\n{}{}",
+        ForexResponse::SinglePair(ret.resp).render(lang, tz),
+        render_notes(notes, lang),
+        footer
     )
-    .reply_to(msg.id)
-    .parse_mode(ParseMode::Html)
-    .await?;
+    .trim_end()
+    .to_string()
+}
 
-    Ok(())
+/// Rate of a pair from the first source answering, shared by the bot and the REST API.
+pub(crate) async fn fetch_single_pair(
+    single_pair_args: SinglePairArg,
+) -> Result<Sourced<ConvertResponseData>, HandlerError> {
+    rate_sources::convert(&ConvertQuery {
+        from: single_pair_args.left,
+        amount: "1".into(),
+        to: single_pair_args.right,
+        date: single_pair_args.date,
+    })
+    .await
 }
 
 async fn base_rates(bot: Bot, msg: &Message, base_args: BaseRatesArg) -> Result<(), HandlerError> {
-    let http_client = http_client().clone();
-
-    let mut query_params: Vec<(&str, String)> = vec![];
-
-    if base_args.base.to_ascii_lowercase().as_str() != "usd" {
-        query_params.push(("base", base_args.base));
+    let notes = base_args.notes.clone();
+    let view = RatesView {
+        quotes: base_args.quotes.clone(),
+        sort: base_args.sort,
+        page: 0,
+    };
+    let page = PageQuery::new(&base_args.base, base_args.date, &view);
+    let ret = fetch_base_rates(base_args).await?;
+    let lang = lang_for(msg);
+    let tz = timezone_for(msg.chat.id.0);
+
+    let keyboard = page
+        .zip(ret.resp.data.as_ref())
+        .map(|(page, data)| rate_pages::keyboard(&page, view.pages(data), lang));
+    let mut reply = Reply::new(base_rates_text(ret, view, &notes, lang, tz))
+        .html()
+        .quote();
+    if let Some(keyboard) = keyboard {
+        reply = reply.keyboard(keyboard);
     }
+    reply.send(&bot, msg).await?;
 
-    if let Some(date) = base_args.date {
-        query_params.push(("date", date.format("%Y-%m-%d").to_string()));
-    }
+    Ok(())
+}
 
-    let ret: ForexResp<RatesResponseData> = http_client
-        .get(FOREX_RATES_ENDPOINT)
-        .query(&query_params)
-        .send()
-        .await
-        .context("failed calling forex rates api")
-        .as_internal_err()?
-        .json()
-        .await?;
-
-    bot.send_message(
-        msg.chat.id,
-        ForexResponse::BaseRatesResponse(ret).to_string(),
+/// Reply of base rates seen through `view`, with notes on how currencies were read and its source.
+pub(crate) fn base_rates_text(
+    ret: Sourced<RatesResponseData>,
+    view: RatesView,
+    notes: &[Localized],
+    lang: Lang,
+    tz: Tz,
+) -> String {
+    let footer = ret.footer(lang);
+    format!(
+        "{}\n\n{}{}",
+        ForexResponse::BaseRates(ret.resp, view).render(lang, tz),
+        render_notes(notes, lang),
+        footer
     )
-    .reply_to(msg.id)
-    .parse_mode(ParseMode::Html)
-    .await?;
+    .trim_end()
+    .to_string()
+}
 
-    Ok(())
+/// Rates of all currencies against a base from the first source answering, shared by the bot and the REST API.
+pub(crate) async fn fetch_base_rates(
+    base_args: BaseRatesArg,
+) -> Result<Sourced<RatesResponseData>, HandlerError> {
+    rate_sources::rates(&RatesQuery {
+        base: base_args.base,
+        date: base_args.date,
+    })
+    .await
 }
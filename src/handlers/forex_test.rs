@@ -1,8 +1,15 @@
 
+use std::time::Duration;
+
 use chrono::{TimeZone, Utc};
 
+use crate::testing::fake_bot::{
+    Call, FakeBotApi, HandlerTreeError, message_update, private_message,
+};
+use crate::testing::fake_pfm::{FakePfmApi, Mode};
 use crate::{
     commands::Args,
+    handler,
     handlers::forex::{BaseRatesArg, SinglePairArg},
 };
 
@@ -58,6 +65,44 @@ fn mixed_case_parsing_test() {
     );
 }
 
+#[test]
+fn crypto_tickers_parsing_test() {
+    let ret: SinglePairArg = Args("doge/idr".into()).try_into().unwrap();
+    assert_eq!("DOGE", ret.left);
+    assert_eq!("IDR", ret.right);
+
+    let ret: SinglePairArg = Args("MATIC/USDT 2024-01-01".into()).try_into().unwrap();
+    assert_eq!("MATIC", ret.left);
+    assert_eq!("USDT", ret.right);
+
+    let ret = BaseRatesArg::try_from(Args("pepe".into())).unwrap();
+    assert_eq!("PEPE", ret.base);
+
+    // well formed, but not a known asset
+    assert!(SinglePairArg::try_from(Args("DOGGO/IDR".into())).is_err());
+}
+
+#[test]
+fn currency_names_parsing_test() {
+    let ret: SinglePairArg = Args("yen/rupiah".into()).try_into().unwrap();
+    assert_eq!("JPY", ret.left);
+    assert_eq!("IDR", ret.right);
+    assert!(ret.notes.is_empty());
+
+    let ret: SinglePairArg = Args("emas/IDR".into()).try_into().unwrap();
+    assert_eq!("XAU", ret.left);
+
+    let ret: SinglePairArg = Args("dollar/idr".into()).try_into().unwrap();
+    assert_eq!("USD", ret.left);
+    assert_eq!(1, ret.notes.len());
+
+    let ret = BaseRatesArg::try_from(Args("ringgit 2022-02-02".into())).unwrap();
+    assert_eq!("MYR", ret.base);
+
+    // several currencies go by it and none is preferred
+    assert!(SinglePairArg::try_from(Args("kr/idr".into())).is_err());
+}
+
 #[test]
 fn invalid_format_single_currency() {
     let args = Args("USD".into());
@@ -104,7 +149,7 @@ fn valid_currency_uppercase_with_date() {
     let parsed = BaseRatesArg::try_from(args).expect("should parse uppercase currency + date");
     assert_eq!(parsed.base, "IDR");
 
-    let expected = Utc.ymd(2022, 2, 2).and_hms(0, 0, 0);
+    let expected = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
     assert_eq!(parsed.date.unwrap(), expected);
 }
 
@@ -114,7 +159,7 @@ fn valid_currency_mixed_case_with_date() {
     let parsed = BaseRatesArg::try_from(args).expect("should parse mixed-case currency + date");
     assert_eq!(parsed.base, "USD"); // normalized to uppercase
 
-    let expected = Utc.ymd(2025, 12, 8).and_hms(0, 0, 0);
+    let expected = Utc.with_ymd_and_hms(2025, 12, 8, 0, 0, 0).unwrap();
     assert_eq!(parsed.date.unwrap(), expected);
 }
 
@@ -130,8 +175,296 @@ fn invalid_currency_format() {
     assert!(BaseRatesArg::try_from(Args("U2D".into())).is_err());
 }
 
+#[test]
+fn flexible_dates_parsing() {
+    let feb_2nd = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+
+    let ret: SinglePairArg = Args("USD/IDR 2 Feb 2022".into()).try_into().unwrap();
+    assert_eq!(Some(feb_2nd), ret.date);
+
+    let ret = BaseRatesArg::try_from(Args("usd eur,yen 02/02/2022 strength".into())).unwrap();
+    assert_eq!(Some(feb_2nd), ret.date);
+    assert_eq!(vec!["EUR", "JPY"], ret.quotes);
+
+    // a saturday reads as the friday before
+    let ret: SinglePairArg = Args("USD/IDR 2022-02-05".into()).try_into().unwrap();
+    assert_eq!(
+        Some(Utc.with_ymd_and_hms(2022, 2, 4, 0, 0, 0).unwrap()),
+        ret.date
+    );
+    assert_eq!(1, ret.notes.len());
+
+    assert!(SinglePairArg::try_from(Args("USD/IDR 3000-01-01".into())).is_err());
+    assert!(BaseRatesArg::try_from(Args("USD 2022-13-01".into())).is_err());
+}
+
 #[test]
 fn invalid_date_format() {
     let res = BaseRatesArg::try_from(Args("USD wrongdate".into()));
     assert!(res.is_err());
 }
+
+async fn forex(
+    pfm: &FakePfmApi,
+    user_id: i64,
+    text: &str,
+) -> (Result<(), HandlerTreeError>, Vec<Call>) {
+    let api = FakeBotApi::start().await;
+    let ret = pfm
+        .scope(api.dispatch(
+            handler(),
+            message_update(1, private_message(1, user_id, text)),
+        ))
+        .await;
+
+    (ret, api.sent_messages())
+}
+
+#[tokio::test]
+async fn watchlist_from_upstream() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, sent) = forex(&pfm, 320_001, "/forex").await;
+
+    ret.unwrap();
+    assert_eq!(6, pfm.requests().len());
+    assert_eq!(1, sent.len());
+    assert_eq!(Some(1), sent[0].reply_to());
+    assert_eq!(Some("HTML"), sent[0].parse_mode());
+    assert_eq!(
+        Some(
+            "Forex data on 2024-03-08:
+- <b>USD/IDR= IDR 15,730.00</b>
+- <b>BTC/USD= USD 68,245.12</b>
+- <b>XAU/USD= USD 2,178.40</b>
+- <b>XAU/IDR= IDR 34,266,232.00</b>
+- <b>XAG/USD= USD 24.37</b>
+- <b>XAG/IDR= IDR 383,340.10</b>
+
+<i>Source: pfm API, updated just now</i>"
+        ),
+        sent[0].text()
+    );
+}
+
+#[tokio::test]
+async fn historical_pair_from_upstream() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, sent) = forex(&pfm, 320_002, "/forex usd/idr 2022-02-02").await;
+
+    ret.unwrap();
+    assert_eq!(
+        vec!["convert_date-2022-02-02_from-usd-1_to-idr.json"],
+        pfm.requests()
+    );
+    assert_eq!(
+        Some(
+            "USD/IDR on 2022-02-02 is:\n<b>IDR 14,362.00</b>\n\n<i>Source: pfm API, updated just now</i>"
+        ),
+        sent[0].text()
+    );
+}
+
+#[tokio::test]
+async fn base_rates_from_upstream() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, sent) = forex(&pfm, 320_003, "/forex IDR 2022-02-02").await;
+
+    ret.unwrap();
+    assert_eq!(
+        Some(
+            "Rates with base IDR on 2022-02-02 (5 currencies):
+
+<b>IDR</b>: 1
+eur: 0.0000617
+jpy: 0.008012
+sgd: 0.0000937
+usd: 0.0000696
+
+<i>Source: pfm API, updated just now</i>"
+        ),
+        sent[0].text()
+    );
+
+    // usd is upstream's default base
+    let (ret, _) = forex(&pfm, 320_003, "/forex USD").await;
+    ret.unwrap();
+    assert_eq!("rates.json", pfm.requests()[1]);
+}
+
+#[tokio::test]
+async fn upstream_error_field_is_shown() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ErrorField("currency XPD is not supported".into()));
+
+    let (ret, sent) = forex(&pfm, 320_004, "/forex XPD/IDR").await;
+
+    ret.unwrap();
+    // nothing was rendered to credit a source for
+    assert_eq!(
+        Some("forex api error: currency XPD is not supported"),
+        sent[0].text()
+    );
+}
+
+#[tokio::test]
+async fn unknown_currency_is_rejected_before_upstream() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, sent) = forex(&pfm, 320_008, "/forex USD/IRD").await;
+
+    assert!(ret.is_err());
+    let text = sent[0].text().unwrap();
+    assert!(
+        text.starts_with("Unknown currency IRD, did you mean IDR (Rupiah), IQD (Iraqi Dinar), IRR (Iranian Rial)?"),
+        "{}",
+        text
+    );
+    assert!(pfm.requests().is_empty());
+}
+
+#[tokio::test]
+async fn syntax_errors_point_at_the_mistake() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, sent) = forex(&pfm, 320_011, "/forex USD//IDR").await;
+
+    assert!(ret.is_err());
+    assert_eq!(Some("HTML"), sent[0].parse_mode());
+    let text = sent[0].text().unwrap();
+    assert!(
+        text.starts_with(
+            "Expected &lt;to&gt; here, not \"/\".\n<pre>USD//IDR\n    ^</pre>Usage: /forex &lt;from&gt;/&lt;to&gt; [date]"
+        ),
+        "{}",
+        text
+    );
+    assert!(pfm.requests().is_empty());
+}
+
+#[tokio::test]
+async fn ambiguous_name_is_noted() {
+    let pfm = FakePfmApi::start().await;
+
+    let (ret, sent) = forex(&pfm, 320_009, "/forex dollar/rupiah 2022-02-02").await;
+
+    ret.unwrap();
+    assert_eq!(
+        vec!["convert_date-2022-02-02_from-usd-1_to-idr.json"],
+        pfm.requests()
+    );
+    let text = sent[0].text().unwrap();
+    assert!(
+        text.contains(
+            "\n\n<i>Read \"dollar\" as USD, it may also mean AUD, CAD, HKD, SGD, NZD, ….</i>\n<i>Source: pfm API"
+        ),
+        "{}",
+        text
+    );
+}
+
+#[tokio::test]
+async fn upstream_failures_reply_friendly_error() {
+    for mode in [Mode::ServerError, Mode::MalformedJson] {
+        let pfm = FakePfmApi::start().await;
+        pfm.set_mode(mode.clone());
+
+        let (ret, sent) = forex(&pfm, 320_005, "/forex USD/IDR").await;
+
+        assert!(ret.is_err(), "{:?}", mode);
+        assert_eq!(1, sent.len());
+        assert_eq!(Some(1), sent[0].reply_to());
+
+        let text = sent[0].text().unwrap();
+        assert!(
+            text.starts_with("The rates service couldn't answer this request"),
+            "{:?}: {}",
+            mode,
+            text
+        );
+        assert!(text.contains("Ref: "), "{:?}: {}", mode, text);
+    }
+}
+
+#[tokio::test]
+async fn slow_upstream_is_awaited() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::Slow(Duration::from_millis(300)));
+
+    let (ret, sent) = forex(&pfm, 320_006, "/forex USD/IDR").await;
+
+    ret.unwrap();
+    assert_eq!(
+        Some(
+            "USD/IDR on 2024-03-08 is:\n<b>IDR 15,730.00</b>\n\n<i>Source: pfm API, updated just now</i>"
+        ),
+        sent[0].text()
+    );
+}
+
+#[tokio::test]
+async fn failed_responses_are_not_cached() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ServerError);
+
+    let (ret, _) = forex(&pfm, 320_007, "/forex XAU/USD").await;
+    assert!(ret.is_err());
+
+    pfm.set_mode(Mode::Replay);
+    let (ret, _) = forex(&pfm, 320_007, "/forex XAU/USD").await;
+    ret.unwrap();
+
+    // served from cache
+    let (ret, _) = forex(&pfm, 320_007, "/forex XAU/USD").await;
+    ret.unwrap();
+
+    assert_eq!(2, pfm.requests().len());
+}
+
+#[tokio::test]
+async fn times_follow_chat_timezone() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(1, 320_010, "/settings tz wita")),
+    )
+    .await
+    .unwrap();
+    assert!(
+        api.sent_messages()[0]
+            .text()
+            .unwrap()
+            .starts_with("Times in this chat are now shown in Asia/Makassar, it is ")
+    );
+
+    pfm.scope(api.dispatch(
+        handler(),
+        message_update(2, private_message(2, 320_010, "/forex usd/idr 2022-02-02")),
+    ))
+    .await
+    .unwrap();
+    // a day of rates keeps its date, the chat's timezone doesn't move it to 08:00
+    assert!(
+        api.sent_messages()[1]
+            .text()
+            .unwrap()
+            .starts_with("USD/IDR on 2022-02-02 is:")
+    );
+
+    api.dispatch(
+        handler(),
+        message_update(3, private_message(3, 320_010, "/settings tz mars")),
+    )
+    .await
+    .unwrap_err();
+    assert!(
+        api.sent_messages()[2]
+            .text()
+            .unwrap()
+            .starts_with("Unknown timezone mars.")
+    );
+}
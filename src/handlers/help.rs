@@ -1,13 +1,206 @@
-use crate::error::HandlerError;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use teloxide::prelude::*;
-use teloxide::utils::command::BotCommands;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+use teloxide::utils::html;
+
+use crate::commands::{Args, aliases_of, is_coming_soon, listed_commands, resolve_name, summary};
+use crate::error::HandlerError;
+use crate::handlers::reply::Reply;
+use crate::handlers::text_transform::{self, TextTransform};
+use crate::handlers::{convert, forex};
+use crate::i18n::{Lang, Localized, lang_for, lang_for_user, t};
+use crate::utils::syntax::Syntax;
+
+/// Prefix of callback data of help buttons, followed by the command name, or nothing for the index.
+pub(crate) const CALLBACK_PREFIX: &str = "help:";
+
+// seed of transform examples, so a page reads the same every time
+const EXAMPLE_SEED: u64 = 42;
+
+// command buttons per keyboard row
+const BUTTONS_PER_ROW: usize = 3;
 
-pub(crate) async fn help_handler(bot: Bot, msg: &Message) -> Result<(), HandlerError> {
-    bot.send_message(
-        msg.chat.id,
-        crate::commands::Command::descriptions().to_string(),
-    )
-    .await?;
+pub(crate) async fn help_handler(bot: Bot, msg: &Message, args: Args) -> Result<(), HandlerError> {
+    let lang = lang_for(msg);
+    let name = resolve_name(args.0.trim());
+
+    let (text, keyboard) = if name.is_empty() {
+        (index_text(lang), index_keyboard())
+    } else {
+        (page_text(lang, &name)?, page_keyboard(lang))
+    };
+
+    Reply::new(text)
+        .html()
+        .quote()
+        .keyboard(keyboard)
+        .send(&bot, msg)
+        .await?;
 
     Ok(())
 }
+
+/// Press of a help button, switching the message between the index and command pages.
+pub(crate) async fn help_callback(bot: Bot, query: CallbackQuery) -> Result<(), HandlerError> {
+    bot.answer_callback_query(query.id.clone()).await?;
+
+    let Some(name) = query
+        .data
+        .as_deref()
+        .and_then(|data| data.strip_prefix(CALLBACK_PREFIX))
+    else {
+        return Ok(());
+    };
+    // too old to be edited
+    let Some(msg) = query.regular_message() else {
+        return Ok(());
+    };
+    let lang = lang_for_user(msg.chat.id.0, Some(&query.from));
+
+    let (text, keyboard) = if name.is_empty() {
+        (index_text(lang), index_keyboard())
+    } else {
+        (page_text(lang, name)?, page_keyboard(lang))
+    };
+
+    bot.edit_message_text(msg.chat.id, msg.id, text)
+        .parse_mode(ParseMode::Html)
+        .reply_markup(keyboard)
+        .await?;
+
+    Ok(())
+}
+
+/// Short index: one line per available command, unfinished ones on a line of their own.
+pub(crate) fn index_text(lang: Lang) -> String {
+    let mut ret = t(lang, "help.header").to_string();
+    ret.push('\n');
+
+    let (soon, available): (Vec<String>, Vec<String>) = listed_commands()
+        .into_iter()
+        .partition(|name| is_coming_soon(name));
+    for name in available {
+        ret.push_str(&format!(
+            "\n/{}{} — {}",
+            name,
+            alias_list(&name)
+                .map(|aliases| format!(" ({})", aliases))
+                .unwrap_or_default(),
+            html::escape(&summary(lang, &name))
+        ));
+    }
+
+    if !soon.is_empty() {
+        let soon: Vec<String> = soon.iter().map(|name| format!("/{}", name)).collect();
+        ret.push_str("\n\n");
+        ret.push_str(
+            &Localized::new("help.coming_soon")
+                .arg("commands", soon.join(", "))
+                .render(lang),
+        );
+    }
+
+    ret.push_str("\n\n");
+    ret.push_str(t(lang, "help.footer"));
+
+    ret
+}
+
+/// Detailed page of a command: syntax, examples, arguments and related commands.
+pub(crate) fn page_text(lang: Lang, name: &str) -> Result<String, Localized> {
+    if !listed_commands().iter().any(|listed| listed == name) {
+        return Err(Localized::new("help.unknown_command").arg("command", name));
+    }
+
+    let body = if is_coming_soon(name) {
+        t(lang, "common.coming_soon").to_string()
+    } else if let Some(transform) = text_transform::by_name(name) {
+        transform_page(lang, transform)
+    } else {
+        t(lang, &format!("help.page.{}", name)).to_string()
+    };
+
+    let aliases = alias_list(name)
+        .map(|aliases| {
+            let line = Localized::new("help.aliases").arg("aliases", aliases);
+            format!("\n<i>{}</i>", html::escape(&line.render(lang)))
+        })
+        .unwrap_or_default();
+
+    Ok(format!(
+        "<b>/{}</b> — {}{}\n\n{}{}",
+        name,
+        html::escape(&summary(lang, name)),
+        aliases,
+        usage(lang, name),
+        body
+    ))
+}
+
+// e.g. `/kurs, /fx` of forex, none without aliases
+fn alias_list(name: &str) -> Option<String> {
+    let aliases: Vec<String> = aliases_of(name)
+        .iter()
+        .map(|alias| format!("/{}", alias))
+        .collect();
+
+    (!aliases.is_empty()).then(|| aliases.join(", "))
+}
+
+// usage made from the forms a command parses, empty for the rest
+fn usage(lang: Lang, name: &str) -> String {
+    let syntax: &Syntax = match name {
+        "forex" => &forex::SYNTAX,
+        "convert" => &convert::SYNTAX,
+        _ => return String::new(),
+    };
+
+    let lines: Vec<String> = syntax
+        .usage(lang)
+        .iter()
+        .map(|line| format!("<code>{}</code>", html::escape(line)))
+        .collect();
+
+    format!("<b>{}</b>\n{}\n\n", t(lang, "help.usage"), lines.join("\n"))
+}
+
+// text transforms share a page, with an example of each made from a fixed seed
+fn transform_page(lang: Lang, transform: &dyn TextTransform) -> String {
+    let input = t(lang, "help.transform_example");
+    let output = transform.transform(input, &mut StdRng::seed_from_u64(EXAMPLE_SEED));
+    let related: Vec<String> = text_transform::transforms()
+        .iter()
+        .filter(|other| other.name() != transform.name())
+        .map(|other| format!("/{}", other.name()))
+        .collect();
+
+    Localized::new("help.page.transform")
+        .arg("command", transform.name())
+        .arg("input", input)
+        .arg("output", html::escape(&output))
+        .arg("related", related.join(", "))
+        .render(lang)
+}
+
+fn index_keyboard() -> InlineKeyboardMarkup {
+    let buttons: Vec<InlineKeyboardButton> = listed_commands()
+        .into_iter()
+        .filter(|name| !is_coming_soon(name))
+        .map(|name| {
+            InlineKeyboardButton::callback(
+                format!("/{}", name),
+                format!("{}{}", CALLBACK_PREFIX, name),
+            )
+        })
+        .collect();
+
+    InlineKeyboardMarkup::new(buttons.chunks(BUTTONS_PER_ROW).map(<[_]>::to_vec))
+}
+
+fn page_keyboard(lang: Lang) -> InlineKeyboardMarkup {
+    InlineKeyboardMarkup::new([[InlineKeyboardButton::callback(
+        t(lang, "help.back"),
+        CALLBACK_PREFIX,
+    )]])
+}
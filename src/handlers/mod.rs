@@ -12,5 +12,45 @@ pub(crate) mod convert;
 #[cfg(test)]
 mod convert_test;
 
+pub(crate) mod admin;
+
+#[cfg(test)]
+mod admin_test;
+
+pub(crate) mod stats;
+
+#[cfg(test)]
+mod stats_test;
+
+pub(crate) mod rate_sources;
+
+#[cfg(test)]
+mod rate_sources_test;
+
+pub(crate) mod text_transform;
+
+#[cfg(test)]
+mod text_transform_test;
+
 pub(crate) mod help;
-pub(crate) mod spongebob;
+pub(crate) mod rate_buttons;
+
+#[cfg(test)]
+mod rate_buttons_test;
+
+pub(crate) mod rate_pages;
+
+#[cfg(test)]
+mod rate_pages_test;
+
+pub(crate) mod rate_stats;
+
+#[cfg(test)]
+mod rate_stats_test;
+
+pub(crate) mod reply;
+
+#[cfg(test)]
+mod reply_test;
+
+pub(crate) mod settings;
@@ -0,0 +1,397 @@
+//! rate_buttons puts buttons under forex and convert replies: swap the pair, previous and next day, refresh,
+//! and the change over 7 or 30 days. A press edits the reply in place.
+//!
+//! The whole query travels in the callback data, signed with the bot token so a client can't forge one,
+//! e.g. to press on behalf of someone else.
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use chrono_tz::Tz;
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use sha2::Sha256;
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+use teloxide::{ApiError, RequestError};
+
+use crate::config::config;
+use crate::error::{HandlerError, answer_callback};
+use crate::handlers::convert::{self, amounts};
+use crate::handlers::forex;
+use crate::handlers::forex::ConvertResponseData;
+use crate::handlers::rate_sources::{self, ConvertQuery, Sourced};
+use crate::i18n::{Lang, Localized, format_number, t};
+use crate::utils::date::{last_trading_day, today};
+use crate::utils::money::{Money, MoneyStyle};
+use crate::utils::time::timezone_for;
+
+/// Prefix of callback data of rate buttons.
+pub(crate) const CALLBACK_PREFIX: &str = "fx:";
+
+// telegram's limit of callback data, in bytes
+const MAX_CALLBACK_DATA: usize = 64;
+
+// truncated HMAC-SHA256, plenty against guessing within the 64 bytes
+const SIGNATURE_BYTES: usize = 6;
+
+const FIELD_SEP: char = '|';
+
+const DATE_FORMAT: &str = "%y%m%d";
+
+// periods of the change buttons, in days
+const CHANGE_DAYS: [u64; 2] = [7, 30];
+
+/// Rate or conversion shown in a reply, and who asked for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct RateQuery {
+    pub from: String,
+    pub to: String,
+
+    // converted amount, none for the rate of a pair
+    pub amount: Option<String>,
+
+    // none for latest
+    pub date: Option<NaiveDate>,
+
+    // telegram user id of who asked, 0 when sent on behalf of a chat
+    pub owner: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Action {
+    Swap,
+    PrevDay,
+    NextDay,
+    Refresh,
+    Change(u64),
+}
+
+impl Action {
+    fn code(&self) -> String {
+        match self {
+            Action::Swap => "s".into(),
+            Action::PrevDay => "p".into(),
+            Action::NextDay => "n".into(),
+            Action::Refresh => "r".into(),
+            Action::Change(days) => format!("c{}", days),
+        }
+    }
+
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "s" => Some(Action::Swap),
+            "p" => Some(Action::PrevDay),
+            "n" => Some(Action::NextDay),
+            "r" => Some(Action::Refresh),
+            _ => code
+                .strip_prefix('c')
+                .and_then(|days| days.parse().ok())
+                .filter(|days| CHANGE_DAYS.contains(days))
+                .map(Action::Change),
+        }
+    }
+}
+
+impl RateQuery {
+    /// Query of a reply to `msg`, owned by its sender.
+    pub(crate) fn new(
+        msg: &Message,
+        from: &str,
+        to: &str,
+        amount: Option<&str>,
+        date: Option<DateTime<Utc>>,
+    ) -> Self {
+        RateQuery {
+            from: from.to_ascii_uppercase(),
+            to: to.to_ascii_uppercase(),
+            amount: amount.map(|amount| amount.replace(',', "")),
+            date: date.map(|date| date.date_naive()),
+            owner: msg.from.as_ref().map_or(0, |user| user.id.0),
+        }
+    }
+
+    /// Query after pressing `action`, on `today`.
+    pub(crate) fn apply(&self, action: Action, today: NaiveDate) -> Self {
+        let mut ret = self.clone();
+        match action {
+            Action::Swap => std::mem::swap(&mut ret.from, &mut ret.to),
+            // markets are closed on weekends, e.g. the day before a monday is friday
+            Action::PrevDay => {
+                ret.date = self
+                    .date
+                    .unwrap_or(today)
+                    .checked_sub_days(Days::new(1))
+                    .map(last_trading_day);
+            }
+            Action::NextDay => {
+                ret.date = self
+                    .date
+                    .and_then(|date| date.checked_add_days(Days::new(1)))
+                    .filter(|date| *date < today);
+            }
+            Action::Refresh | Action::Change(_) => {}
+        }
+
+        ret
+    }
+
+    /// Signed callback data of pressing `action`, none when it doesn't fit telegram's limit.
+    pub(crate) fn encode(&self, action: Action) -> Option<String> {
+        let payload = [
+            action.code(),
+            self.from.clone(),
+            self.to.clone(),
+            self.amount.clone().unwrap_or_default(),
+            self.date
+                .map(|date| date.format(DATE_FORMAT).to_string())
+                .unwrap_or_default(),
+            to_base36(self.owner),
+        ]
+        .join(&FIELD_SEP.to_string());
+
+        let data = format!(
+            "{}{}{}{}",
+            CALLBACK_PREFIX,
+            payload,
+            FIELD_SEP,
+            hex(&mac(&payload).finalize().into_bytes()[..SIGNATURE_BYTES])
+        );
+        (data.len() <= MAX_CALLBACK_DATA).then_some(data)
+    }
+
+    /// Query and action of callback data, none when it isn't signed by this bot.
+    pub(crate) fn decode(data: &str) -> Option<(Self, Action)> {
+        let data = data.strip_prefix(CALLBACK_PREFIX)?;
+        let (payload, signature) = data.rsplit_once(FIELD_SEP)?;
+        mac(payload)
+            .verify_truncated_left(&unhex(signature)?)
+            .ok()?;
+
+        let fields: Vec<&str> = payload.split(FIELD_SEP).collect();
+        let [action, from, to, amount, date, owner] = fields[..] else {
+            return None;
+        };
+
+        let query = RateQuery {
+            from: from.to_string(),
+            to: to.to_string(),
+            amount: (!amount.is_empty()).then(|| amount.to_string()),
+            date: match date {
+                "" => None,
+                date => Some(NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?),
+            },
+            owner: u64::from_str_radix(owner, 36).ok()?,
+        };
+
+        Some((query, Action::from_code(action)?))
+    }
+
+    fn convert_query(&self) -> ConvertQuery {
+        ConvertQuery {
+            from: self.from.clone(),
+            amount: self.amount.clone().unwrap_or_else(|| "1".into()),
+            to: self.to.clone(),
+            date: self
+                .date
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+                .map(|date| date.and_utc()),
+        }
+    }
+}
+
+/// Buttons under a reply of `query`, none when the query is too long for callback data.
+pub(crate) fn keyboard(query: &RateQuery, lang: Lang) -> Option<InlineKeyboardMarkup> {
+    let button = |label: String, action: Action| {
+        query
+            .encode(action)
+            .map(|data| InlineKeyboardButton::callback(label, data))
+    };
+
+    let mut days = vec![button(
+        t(lang, "rate_buttons.prev_day").into(),
+        Action::PrevDay,
+    )?];
+    // latest rates have no next day
+    if query.date.is_some() {
+        days.push(button(
+            t(lang, "rate_buttons.next_day").into(),
+            Action::NextDay,
+        )?);
+    }
+
+    let mut tools = vec![button(
+        t(lang, "rate_buttons.refresh").into(),
+        Action::Refresh,
+    )?];
+    for period in CHANGE_DAYS {
+        let label = Localized::new("rate_buttons.change_button")
+            .arg("days", period)
+            .render(lang);
+        tools.push(button(label, Action::Change(period))?);
+    }
+
+    Some(InlineKeyboardMarkup::new([
+        vec![button(t(lang, "rate_buttons.swap").into(), Action::Swap)?],
+        days,
+        tools,
+    ]))
+}
+
+/// Press of a rate button, editing the reply with the new rate or conversion.
+pub(crate) async fn rate_callback(bot: Bot, query: CallbackQuery) -> Result<(), HandlerError> {
+    answer_callback(bot, query, |bot, query, msg, lang| {
+        Box::pin(press(bot, query, msg, lang))
+    })
+    .await
+}
+
+async fn press(
+    bot: &Bot,
+    query: &CallbackQuery,
+    msg: &Message,
+    lang: Lang,
+) -> Result<(), HandlerError> {
+    let (rate_query, action) = query
+        .data
+        .as_deref()
+        .and_then(RateQuery::decode)
+        .ok_or_else(|| Localized::new("rate_buttons.invalid"))?;
+
+    if rate_query.owner != 0 && rate_query.owner != query.from.id.0 && !config().buttons_anyone {
+        return Err(Localized::new("rate_buttons.not_owner").into());
+    }
+
+    let tz = timezone_for(msg.chat.id.0);
+    let rate_query = rate_query.apply(action, today(tz));
+    let resp = rate_sources::convert(&rate_query.convert_query()).await?;
+
+    let notes = match action {
+        Action::Change(days) => vec![change(&rate_query, &resp.resp.data, days, lang).await?],
+        _ => vec![],
+    };
+
+    let text = reply_text(&rate_query, resp, &notes, lang, tz);
+    let mut req = bot.edit_message_text(msg.chat.id, msg.id, text);
+    req.parse_mode = Some(ParseMode::Html);
+    req.reply_markup = keyboard(&rate_query, lang);
+
+    match req.await {
+        // refreshed to the same rate
+        Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
+
+// text of a reply of `query`, a conversion when it has an amount, else the rate of the pair
+fn reply_text(
+    query: &RateQuery,
+    resp: Sourced<ConvertResponseData>,
+    notes: &[Localized],
+    lang: Lang,
+    tz: Tz,
+) -> String {
+    match query.amount {
+        Some(_) => convert::reply_text(resp, notes, lang, tz),
+        None => forex::single_pair_text(resp, notes, lang, tz),
+    }
+}
+
+// change of the rate of `current` since `days` before it
+async fn change(
+    query: &RateQuery,
+    current: &Option<ConvertResponseData>,
+    days: u64,
+    lang: Lang,
+) -> Result<Localized, HandlerError> {
+    let current = current
+        .as_ref()
+        .ok_or_else(|| Localized::new("forex.no_data_returned"))?;
+    let since = current
+        .date
+        .date_naive()
+        .checked_sub_days(Days::new(days))
+        .ok_or_else(|| Localized::new("forex.no_data_returned"))?;
+
+    let past = RateQuery {
+        date: Some(since),
+        amount: None,
+        ..query.clone()
+    };
+    let resp = rate_sources::convert(&past.convert_query()).await?;
+    let past = resp
+        .resp
+        .data
+        .ok_or_else(|| Localized::new("forex.no_data_returned"))?;
+
+    let now = rate(current)?;
+    let then = rate(&past)?;
+    if then.is_zero() {
+        return Err(Localized::new("forex.invalid_response").into());
+    }
+
+    Ok(Localized::new("rate_buttons.change")
+        .arg("days", days)
+        .arg(
+            "change",
+            percent((now - then) / then * Decimal::ONE_HUNDRED, lang),
+        )
+        .arg(
+            "rate",
+            Money::new(&query.to, then).format(lang, MoneyStyle::Symbol),
+        )
+        .arg("date", since.format("%Y-%m-%d")))
+}
+
+// units of `to` for one `from`
+pub(crate) fn rate(data: &ConvertResponseData) -> Result<Decimal, Localized> {
+    let (from, to) = amounts(data)?;
+    to.amount
+        .checked_div(from.amount)
+        .ok_or_else(|| Localized::new("forex.invalid_response"))
+}
+
+pub(crate) fn percent(value: Decimal, lang: Lang) -> String {
+    let sign = if value.is_sign_positive() && !value.is_zero() {
+        "+"
+    } else {
+        ""
+    };
+
+    format!("{}{}%", sign, format_number(lang, value, 2))
+}
+
+fn mac(payload: &str) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(config().bot_token.as_bytes())
+        .expect("hmac accepts keys of any length");
+    mac.update(payload.as_bytes());
+
+    mac
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn unhex(text: &str) -> Option<Vec<u8>> {
+    if !text.len().is_multiple_of(2) || !text.is_ascii() {
+        return None;
+    }
+
+    (0..text.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&text[idx..idx + 2], 16).ok())
+        .collect()
+}
+
+fn to_base36(mut value: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut ret = vec![];
+    loop {
+        ret.push(DIGITS[(value % 36) as usize]);
+        value /= 36;
+        if value == 0 {
+            break;
+        }
+    }
+    ret.reverse();
+
+    String::from_utf8(ret).expect("base36 digits are ascii")
+}
@@ -0,0 +1,214 @@
+use chrono::NaiveDate;
+
+use crate::handler;
+use crate::handlers::rate_buttons::{Action, RateQuery, keyboard};
+use crate::i18n::Lang;
+use crate::testing::fake_bot::{
+    FakeBotApi, callback_update, group_message, message_update, private_message,
+};
+use crate::testing::fake_pfm::FakePfmApi;
+
+fn date(text: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+}
+
+fn pair(owner: u64, on: Option<&str>) -> RateQuery {
+    RateQuery {
+        from: "USD".into(),
+        to: "IDR".into(),
+        amount: None,
+        date: on.map(date),
+        owner,
+    }
+}
+
+#[test]
+fn callback_data_round_trip() {
+    let query = RateQuery {
+        amount: Some("1500000.50".into()),
+        ..pair(7_123_456_789, Some("2022-02-02"))
+    };
+
+    for action in [
+        Action::Swap,
+        Action::PrevDay,
+        Action::NextDay,
+        Action::Refresh,
+        Action::Change(30),
+    ] {
+        let data = query.encode(action).unwrap();
+        assert!(data.len() <= 64, "{}", data);
+        assert_eq!(Some((query.clone(), action)), RateQuery::decode(&data));
+    }
+}
+
+#[test]
+fn forged_callback_data_is_rejected() {
+    let data = pair(370_001, None).encode(Action::Refresh).unwrap();
+    let (payload, signature) = data.rsplit_once('|').unwrap();
+    let other = pair(370_009, None).encode(Action::Refresh).unwrap();
+    let (other_payload, _) = other.rsplit_once('|').unwrap();
+
+    // someone else's query with a signature copied from a real button
+    assert!(RateQuery::decode(&format!("{}|{}", other_payload, signature)).is_none());
+    assert!(RateQuery::decode(&format!("{}|000000000000", payload)).is_none());
+    assert!(RateQuery::decode("fx:r|USD|IDR|||1").is_none());
+}
+
+#[test]
+fn too_long_amounts_have_no_buttons() {
+    let query = RateQuery {
+        amount: Some("1".repeat(40)),
+        ..pair(370_001, None)
+    };
+
+    assert_eq!(None, query.encode(Action::Refresh));
+    assert!(keyboard(&query, Lang::En).is_none());
+}
+
+#[test]
+fn actions_move_the_query() {
+    let today = date("2024-03-10");
+    let latest = pair(1, None);
+
+    let swapped = latest.apply(Action::Swap, today);
+    assert_eq!(("IDR", "USD"), (swapped.from.as_str(), swapped.to.as_str()));
+
+    // weekends are skipped
+    assert_eq!(
+        Some(date("2024-03-08")),
+        latest.apply(Action::PrevDay, today).date
+    );
+    assert_eq!(
+        Some(date("2024-03-01")),
+        pair(1, Some("2024-03-04"))
+            .apply(Action::PrevDay, today)
+            .date
+    );
+    assert_eq!(
+        Some(date("2024-03-06")),
+        pair(1, Some("2024-03-07"))
+            .apply(Action::PrevDay, today)
+            .date
+    );
+    assert_eq!(
+        Some(date("2024-03-08")),
+        pair(1, Some("2024-03-07"))
+            .apply(Action::NextDay, today)
+            .date
+    );
+    // back to latest rates
+    assert_eq!(
+        None,
+        pair(1, Some("2024-03-09"))
+            .apply(Action::NextDay, today)
+            .date
+    );
+    assert_eq!(latest, latest.apply(Action::Change(7), today));
+}
+
+#[tokio::test]
+async fn forex_reply_has_buttons() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+
+    pfm.scope(api.dispatch(
+        handler(),
+        message_update(1, private_message(1, 370_002, "/forex usd/idr 2022-02-02")),
+    ))
+    .await
+    .unwrap();
+
+    let rows = api.sent_messages()[0].body["reply_markup"]["inline_keyboard"].clone();
+    let labels: Vec<Vec<&str>> = rows
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|row| {
+            row.as_array()
+                .unwrap()
+                .iter()
+                .map(|button| button["text"].as_str().unwrap())
+                .collect()
+        })
+        .collect();
+    assert_eq!(
+        vec![
+            vec!["⇄ Swap"],
+            vec!["◀ Previous day", "Next day ▶"],
+            vec!["🔄 Refresh", "📈 7d", "📈 30d"],
+        ],
+        labels
+    );
+
+    let data = rows[1][0]["callback_data"].as_str().unwrap();
+    assert_eq!(
+        Some((pair(370_002, Some("2022-02-02")), Action::PrevDay)),
+        RateQuery::decode(data)
+    );
+}
+
+#[tokio::test]
+async fn pressing_edits_the_reply() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+    let reply = private_message(10_000, 370_003, "USD/IDR on 2022-02-02");
+
+    let data = pair(370_003, Some("2022-02-02"))
+        .encode(Action::PrevDay)
+        .unwrap();
+    pfm.scope(api.dispatch(handler(), callback_update(1, 370_003, reply.clone(), &data)))
+        .await
+        .unwrap();
+
+    let data = pair(370_003, Some("2022-02-02"))
+        .encode(Action::Change(7))
+        .unwrap();
+    pfm.scope(api.dispatch(handler(), callback_update(2, 370_003, reply, &data)))
+        .await
+        .unwrap();
+
+    let edits = api.calls_to("editMessageText");
+    assert_eq!(Some(10_000), edits[0].message_id());
+    assert!(
+        edits[0]
+            .text()
+            .unwrap()
+            .starts_with("USD/IDR on 2022-02-01 is:\n<b>IDR 14,358.00</b>")
+    );
+    assert!(
+        edits[1]
+            .text()
+            .unwrap()
+            .contains("📈 Change over 7 days: <b>-0.04%</b>, from Rp14,368.00 on 2022-01-26"),
+        "{}",
+        edits[1].text().unwrap()
+    );
+    assert_eq!(2, api.calls_to("answerCallbackQuery").len());
+}
+
+#[tokio::test]
+async fn only_the_requester_may_press() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+    let reply = group_message(10_000, -370_000, 370_004, "USD/IDR");
+
+    let data = pair(370_004, Some("2022-02-02"))
+        .encode(Action::Refresh)
+        .unwrap();
+    let ret = pfm
+        .scope(api.dispatch(handler(), callback_update(1, 370_005, reply, &data)))
+        .await;
+
+    assert!(ret.is_err());
+    assert!(api.calls_to("editMessageText").is_empty());
+    assert!(pfm.requests().is_empty());
+    let answer = &api.calls_to("answerCallbackQuery")[0];
+    assert_eq!(Some(true), answer.body["show_alert"].as_bool());
+    let text = answer.text().unwrap();
+    assert!(
+        text.starts_with("Only the person who sent the command can use these buttons.\n\nRef: "),
+        "{}",
+        text
+    );
+}
@@ -0,0 +1,186 @@
+//! rate_pages turns pages of `/forex <base>`: the top list first, then the rest of the rates, alphabetically or by
+//! strength against the base. Anyone in the chat may turn pages, the callback data only names public rates.
+use chrono::{DateTime, NaiveDate, Utc};
+use teloxide::prelude::*;
+use teloxide::types::{InlineKeyboardButton, InlineKeyboardMarkup, ParseMode};
+use teloxide::{ApiError, RequestError};
+
+use crate::error::{HandlerError, answer_callback};
+use crate::handlers::forex::{
+    BaseRatesArg, RatesSort, RatesView, base_rates_text, fetch_base_rates,
+};
+use crate::i18n::{Lang, Localized, t};
+use crate::utils::currency::currency;
+use crate::utils::time::timezone_for;
+
+/// Prefix of callback data of base rates pages.
+pub(crate) const CALLBACK_PREFIX: &str = "fp:";
+
+const FIELD_SEP: char = '|';
+
+const DATE_FORMAT: &str = "%y%m%d";
+
+/// Page of base rates a button leads to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct PageQuery {
+    pub base: String,
+
+    // none for latest
+    pub date: Option<NaiveDate>,
+
+    pub sort: RatesSort,
+    pub page: usize,
+}
+
+impl PageQuery {
+    /// Query of a base rates reply, none when it shows requested quotes, which have no pages.
+    pub(crate) fn new(base: &str, date: Option<DateTime<Utc>>, view: &RatesView) -> Option<Self> {
+        view.quotes.is_empty().then(|| PageQuery {
+            base: base.to_ascii_uppercase(),
+            date: date.map(|date| date.date_naive()),
+            sort: view.sort,
+            page: view.page,
+        })
+    }
+
+    pub(crate) fn encode(&self) -> String {
+        format!(
+            "{}{}{sep}{}{sep}{}{sep}{}",
+            CALLBACK_PREFIX,
+            self.base,
+            self.date
+                .map(|date| date.format(DATE_FORMAT).to_string())
+                .unwrap_or_default(),
+            self.sort.code(),
+            self.page,
+            sep = FIELD_SEP
+        )
+    }
+
+    pub(crate) fn decode(data: &str) -> Option<Self> {
+        let fields: Vec<&str> = data
+            .strip_prefix(CALLBACK_PREFIX)?
+            .split(FIELD_SEP)
+            .collect();
+        let [base, date, sort, page] = fields[..] else {
+            return None;
+        };
+
+        Some(PageQuery {
+            base: currency(base)?.code.to_string(),
+            date: match date {
+                "" => None,
+                date => Some(NaiveDate::parse_from_str(date, DATE_FORMAT).ok()?),
+            },
+            sort: RatesSort::from_code(sort)?,
+            page: page.parse().ok()?,
+        })
+    }
+
+    fn view(&self) -> RatesView {
+        RatesView {
+            quotes: vec![],
+            sort: self.sort,
+            page: self.page,
+        }
+    }
+}
+
+/// Page buttons when there's more than one page, and a button switching the order.
+pub(crate) fn keyboard(query: &PageQuery, pages: usize, lang: Lang) -> InlineKeyboardMarkup {
+    let button = |label: String, page: usize, sort: RatesSort| {
+        let data = PageQuery {
+            page,
+            sort,
+            ..query.clone()
+        }
+        .encode();
+        InlineKeyboardButton::callback(label, data)
+    };
+
+    let mut rows = vec![];
+    if pages > 1 {
+        let page = query.page.min(pages - 1);
+        let mut row = vec![];
+        if page > 0 {
+            row.push(button(
+                t(lang, "rate_pages.prev").into(),
+                page - 1,
+                query.sort,
+            ));
+        }
+        let position = Localized::new("rate_pages.position")
+            .arg("page", page + 1)
+            .arg("pages", pages)
+            .render(lang);
+        row.push(button(position, page, query.sort));
+        if page + 1 < pages {
+            row.push(button(
+                t(lang, "rate_pages.next").into(),
+                page + 1,
+                query.sort,
+            ));
+        }
+        rows.push(row);
+    }
+
+    // a new order starts over from the top list
+    let (label, sort) = match query.sort {
+        RatesSort::Alphabetical => ("rate_pages.sort_strength", RatesSort::Strength),
+        RatesSort::Strength => ("rate_pages.sort_alphabetical", RatesSort::Alphabetical),
+    };
+    rows.push(vec![button(t(lang, label).into(), 0, sort)]);
+
+    InlineKeyboardMarkup::new(rows)
+}
+
+/// Press of a page button, editing the reply with the page.
+pub(crate) async fn page_callback(bot: Bot, query: CallbackQuery) -> Result<(), HandlerError> {
+    answer_callback(bot, query, |bot, query, msg, lang| {
+        Box::pin(turn(bot, query, msg, lang))
+    })
+    .await
+}
+
+async fn turn(
+    bot: &Bot,
+    query: &CallbackQuery,
+    msg: &Message,
+    lang: Lang,
+) -> Result<(), HandlerError> {
+    let page = query
+        .data
+        .as_deref()
+        .and_then(PageQuery::decode)
+        .ok_or_else(|| Localized::new("rate_buttons.invalid"))?;
+
+    let ret = fetch_base_rates(BaseRatesArg {
+        base: page.base.clone(),
+        date: page
+            .date
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|date| date.and_utc()),
+        notes: vec![],
+        quotes: vec![],
+        sort: page.sort,
+    })
+    .await?;
+
+    let view = page.view();
+    let keyboard = ret
+        .resp
+        .data
+        .as_ref()
+        .map(|data| keyboard(&page, view.pages(data), lang));
+
+    let text = base_rates_text(ret, view, &[], lang, timezone_for(msg.chat.id.0));
+    let mut req = bot.edit_message_text(msg.chat.id, msg.id, text);
+    req.parse_mode = Some(ParseMode::Html);
+    req.reply_markup = keyboard;
+
+    match req.await {
+        // the current page pressed again
+        Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => Ok(()),
+        Err(err) => Err(err.into()),
+    }
+}
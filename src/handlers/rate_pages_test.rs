@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+
+use chrono::{NaiveDate, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::commands::Args;
+use crate::handler;
+use crate::handlers::forex::{BaseRatesArg, RatesResponseData, RatesSort, RatesView};
+use crate::handlers::rate_pages::{PageQuery, keyboard};
+use crate::i18n::Lang;
+use crate::testing::fake_bot::{FakeBotApi, callback_update, message_update, private_message};
+use crate::testing::fake_pfm::FakePfmApi;
+
+// usd against the base, every top quote and 30 more
+fn many_rates() -> RatesResponseData {
+    let mut rates: HashMap<String, String> = [
+        ("usd", "1"),
+        ("idr", "15,730.00"),
+        ("eur", "0.9150"),
+        ("jpy", "147.05"),
+        ("btc", "0.0000148"),
+    ]
+    .into_iter()
+    .map(|(code, rate)| (code.to_string(), rate.to_string()))
+    .collect();
+    for i in 0..30u8 {
+        let code = format!("q{}{}", (b'a' + i / 26) as char, (b'a' + i % 26) as char);
+        rates.insert(code, format!("{}.5", i + 1));
+    }
+
+    RatesResponseData {
+        rates_date: Utc.with_ymd_and_hms(2024, 3, 8, 0, 0, 0).unwrap(),
+        base: "USD".into(),
+        rates,
+    }
+}
+
+fn lines(text: &str) -> Vec<&str> {
+    text.lines().skip(2).collect()
+}
+
+#[test]
+fn callback_data_round_trip() {
+    let query = PageQuery {
+        base: "IDR".into(),
+        date: NaiveDate::from_ymd_opt(2022, 2, 2),
+        sort: RatesSort::Strength,
+        page: 3,
+    };
+
+    let data = query.encode();
+    assert_eq!("fp:IDR|220202|s|3", data);
+    assert_eq!(Some(query), PageQuery::decode(&data));
+
+    assert!(PageQuery::decode("fp:DOGGO||a|1").is_none());
+    assert!(PageQuery::decode("fp:USD||x|1").is_none());
+    assert!(PageQuery::decode("fx:USD||a|1").is_none());
+}
+
+#[test]
+fn quotes_and_sort_parsing() {
+    let ret =
+        BaseRatesArg::try_from(Args("usd eur, yen,rupiah,eur 2022-02-02 strength".into())).unwrap();
+    assert_eq!("USD", ret.base);
+    assert_eq!(vec!["EUR", "JPY", "IDR"], ret.quotes);
+    assert_eq!(RatesSort::Strength, ret.sort);
+    assert!(ret.date.is_some());
+
+    let ret = BaseRatesArg::try_from(Args("usd az".into())).unwrap();
+    assert!(ret.quotes.is_empty());
+    assert_eq!(RatesSort::Alphabetical, ret.sort);
+
+    let err = BaseRatesArg::try_from(Args("usd eur,doggo".into())).unwrap_err();
+    assert_eq!(
+        Some("Unknown currency DOGGO, did you mean DOGE (Dogecoin)?"),
+        err.user_message(Lang::En).lines().next()
+    );
+
+    // repeated quotes count once
+    let many = "eur,jpy,gbp,cny,aud,cad,chf,hkd,sgd,idr,myr,thb,krw,inr,sar,xau,btc,php,vnd,nzd";
+    let ret = BaseRatesArg::try_from(Args(format!("usd {},eur", many))).unwrap();
+    assert_eq!(20, ret.quotes.len());
+    let err = BaseRatesArg::try_from(Args(format!("usd {},twd", many))).unwrap_err();
+    assert_eq!(
+        Some(
+            "At most 20 currencies can be listed, send /forex <base> to page through all of them."
+        ),
+        err.user_message(Lang::En).lines().next()
+    );
+}
+
+#[test]
+fn top_list_then_pages_of_the_rest() {
+    let data = many_rates();
+    let view = RatesView::default();
+    assert_eq!(3, view.pages(&data));
+
+    let text = view.render(&data, Lang::En, Tz::Asia__Jakarta);
+    assert_eq!(
+        vec![
+            "<b>USD</b>: 1",
+            "btc: 0.0000148",
+            "eur: 0.9150",
+            "idr: 15,730.00",
+            "jpy: 147.05"
+        ],
+        lines(&text)
+    );
+
+    let second = RatesView {
+        page: 1,
+        ..RatesView::default()
+    }
+    .render(&data, Lang::En, Tz::Asia__Jakarta);
+    assert_eq!(20, lines(&second).len());
+    assert_eq!("qaa: 1.5", lines(&second)[0]);
+
+    // pages past the end show the last one
+    let last = RatesView {
+        page: 9,
+        ..RatesView::default()
+    }
+    .render(&data, Lang::En, Tz::Asia__Jakarta);
+    assert_eq!(vec!["qbd: 30.5"], lines(&last)[9..].to_vec());
+    assert_eq!(10, lines(&last).len());
+}
+
+#[test]
+fn strength_sort_and_requested_quotes() {
+    let data = many_rates();
+    let view = RatesView {
+        sort: RatesSort::Strength,
+        ..RatesView::default()
+    };
+    assert_eq!(
+        vec![
+            "<b>USD</b>: 1",
+            "btc: 0.0000148",
+            "eur: 0.9150",
+            "jpy: 147.05",
+            "idr: 15,730.00"
+        ],
+        lines(&view.render(&data, Lang::En, Tz::Asia__Jakarta))
+    );
+
+    let view = RatesView {
+        quotes: vec!["IDR".into(), "SGD".into(), "EUR".into()],
+        sort: RatesSort::Strength,
+        page: 0,
+    };
+    assert_eq!(1, view.pages(&data));
+    assert_eq!(
+        vec!["eur: 0.9150", "idr: 15,730.00", "sgd: no data"],
+        lines(&view.render(&data, Lang::En, Tz::Asia__Jakarta))
+    );
+}
+
+#[test]
+fn keyboard_rows() {
+    let query = PageQuery {
+        base: "USD".into(),
+        date: None,
+        sort: RatesSort::Alphabetical,
+        page: 1,
+    };
+    let labels = |pages| -> Vec<Vec<String>> {
+        keyboard(&query, pages, Lang::En)
+            .inline_keyboard
+            .iter()
+            .map(|row| row.iter().map(|button| button.text.clone()).collect())
+            .collect()
+    };
+
+    assert_eq!(
+        vec![vec!["◀", "2/3", "▶"], vec!["Sort by strength"]],
+        labels(3)
+    );
+    assert_eq!(vec![vec!["Sort by strength"]], labels(1));
+}
+
+#[tokio::test]
+async fn requested_quotes_have_no_pages() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+
+    pfm.scope(api.dispatch(
+        handler(),
+        message_update(
+            1,
+            private_message(1, 380_001, "/forex IDR 2022-02-02 usd,eur"),
+        ),
+    ))
+    .await
+    .unwrap();
+
+    let sent = &api.sent_messages()[0];
+    assert_eq!(
+        Some(
+            "Rates with base IDR on 2022-02-02 (5 currencies):
+
+eur: 0.0000617
+usd: 0.0000696
+
+<i>Source: pfm API, updated just now</i>"
+        ),
+        sent.text()
+    );
+    assert!(sent.body["reply_markup"].is_null());
+}
+
+#[tokio::test]
+async fn pressing_turns_the_page() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+
+    pfm.scope(api.dispatch(
+        handler(),
+        message_update(1, private_message(1, 380_002, "/forex IDR 2022-02-02")),
+    ))
+    .await
+    .unwrap();
+    let toggle = api.sent_messages()[0].body["reply_markup"]["inline_keyboard"][0][0].clone();
+    assert_eq!(Some("Sort by strength"), toggle["text"].as_str());
+
+    let reply = private_message(10_000, 380_002, "Rates with base IDR");
+    pfm.scope(api.dispatch(
+        handler(),
+        callback_update(2, 380_002, reply, toggle["callback_data"].as_str().unwrap()),
+    ))
+    .await
+    .unwrap();
+
+    let edits = api.calls_to("editMessageText");
+    assert_eq!(Some(10_000), edits[0].message_id());
+    assert!(
+        edits[0].text().unwrap().contains(
+            "<b>IDR</b>: 1\neur: 0.0000617\nusd: 0.0000696\nsgd: 0.0000937\njpy: 0.008012"
+        ),
+        "{}",
+        edits[0].text().unwrap()
+    );
+    assert_eq!(
+        Some("Sort A–Z"),
+        edits[0].body["reply_markup"]["inline_keyboard"][0][0]["text"].as_str()
+    );
+    assert_eq!(1, api.calls_to("answerCallbackQuery").len());
+}
+
+#[tokio::test]
+async fn pages_past_the_end_show_the_top_list() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+
+    // a forged or stale button, the 5 rates of IDR fit the top list
+    let data = PageQuery {
+        base: "IDR".into(),
+        date: NaiveDate::from_ymd_opt(2022, 2, 2),
+        sort: RatesSort::Alphabetical,
+        page: 5,
+    }
+    .encode();
+    let reply = private_message(10_000, 380_003, "Rates with base IDR");
+    pfm.scope(api.dispatch(handler(), callback_update(1, 380_003, reply, &data)))
+        .await
+        .unwrap();
+
+    let edits = api.calls_to("editMessageText");
+    assert!(
+        edits[0]
+            .text()
+            .unwrap()
+            .contains("<b>IDR</b>: 1\neur: 0.0000617"),
+        "{}",
+        edits[0].text().unwrap()
+    );
+}
@@ -0,0 +1,195 @@
+//! Adapter of the coingecko API (https://www.coingecko.com/en/api), answering crypto pairs the pfm API lacks.
+//!
+//! Prices are asked against the fiat side of a pair, or USD when both sides are crypto.
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use serde::Deserialize;
+
+use crate::config::config;
+use crate::deps::http_client::get_json_cached;
+use crate::error::HandlerError;
+use crate::handlers::forex::ConvertResponseData;
+use crate::handlers::rate_sources::ConvertQuery;
+use crate::i18n::Lang;
+use crate::utils::currency::is_crypto;
+use crate::utils::money::Money;
+
+// coingecko ids of the crypto assets in the currency registry
+static COIN_IDS: &[(&str, &str)] = &[
+    ("BTC", "bitcoin"),
+    ("ETH", "ethereum"),
+    ("USDT", "tether"),
+    ("USDC", "usd-coin"),
+    ("BNB", "binancecoin"),
+    ("SOL", "solana"),
+    ("XRP", "ripple"),
+    ("DOGE", "dogecoin"),
+    ("ADA", "cardano"),
+    ("TRX", "tron"),
+    ("TON", "the-open-network"),
+    ("DOT", "polkadot"),
+    ("MATIC", "matic-network"),
+    ("LTC", "litecoin"),
+    ("SHIB", "shiba-inu"),
+    ("AVAX", "avalanche-2"),
+    ("LINK", "chainlink"),
+    ("DAI", "dai"),
+    ("PEPE", "pepe"),
+];
+
+// quote currency when both sides are crypto
+const CROSS_VS: &str = "usd";
+
+/// Body of `/simple/price`, e.g. `{"bitcoin":{"usd":68245.12,"last_updated_at":1709856000}}`.
+type SimplePriceResp = HashMap<String, HashMap<String, Decimal>>;
+
+/// Body of `/coins/{id}/history`, trimmed to the prices.
+#[derive(Debug, Deserialize)]
+struct HistoryResp {
+    market_data: Option<MarketData>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MarketData {
+    current_price: HashMap<String, Decimal>,
+}
+
+/// Base url of the API, none when it is disabled.
+pub(crate) fn base_url() -> Option<String> {
+    // tests running inside a fake coingecko API scope talk to it instead
+    #[cfg(test)]
+    if let Some(base_url) = crate::testing::fake_coingecko::scoped_base_url() {
+        return Some(base_url);
+    }
+
+    let base_url = config().crypto_api_url.trim_end_matches('/').to_string();
+    (!base_url.is_empty()).then_some(base_url)
+}
+
+pub(crate) fn coin_id(code: &str) -> Option<&'static str> {
+    COIN_IDS
+        .iter()
+        .find(|(coin, _)| coin.eq_ignore_ascii_case(code))
+        .map(|(_, id)| *id)
+}
+
+struct Quote {
+    price: Decimal,
+
+    // when the price was set, none for the quote currency itself
+    date: Option<DateTime<Utc>>,
+    age: Duration,
+}
+
+pub(crate) async fn convert(
+    base_url: &str,
+    query: &ConvertQuery,
+    ttl: Duration,
+) -> Result<(ConvertResponseData, Duration), HandlerError> {
+    let vs = [&query.to, &query.from]
+        .into_iter()
+        .find(|code| !is_crypto(code))
+        .map_or(CROSS_VS.to_string(), |code| code.to_ascii_lowercase());
+
+    let from = quote(base_url, &query.from, &vs, query.date, ttl).await?;
+    let to = quote(base_url, &query.to, &vs, query.date, ttl).await?;
+    if to.price.is_zero() {
+        return Err(HandlerError::ApiError(anyhow::anyhow!(
+            "coingecko priced {} at zero",
+            query.to
+        )));
+    }
+
+    let amount = Money::parse(&query.from, &query.amount)?.amount;
+    let converted = Money::new(&query.to, amount * from.price / to.price);
+    let value = converted.format_value(Lang::En);
+    let code = format!("{} {}", query.to, value);
+    let date = match query.date {
+        Some(date) => date,
+        None => from.date.or(to.date).unwrap_or_else(Utc::now),
+    };
+
+    Ok((
+        ConvertResponseData {
+            date,
+            from: HashMap::from([(query.from.clone(), query.amount.clone())]),
+            to: HashMap::from([(query.to.clone(), value)]),
+            // no currency symbols there
+            symbol: code.clone(),
+            code,
+        },
+        from.age.max(to.age),
+    ))
+}
+
+// price of one `code` in `vs`
+async fn quote(
+    base_url: &str,
+    code: &str,
+    vs: &str,
+    date: Option<DateTime<Utc>>,
+    ttl: Duration,
+) -> Result<Quote, HandlerError> {
+    if code.eq_ignore_ascii_case(vs) {
+        return Ok(Quote {
+            price: Decimal::ONE,
+            date: None,
+            age: Duration::ZERO,
+        });
+    }
+
+    let Some(id) = coin_id(code) else {
+        return Err(HandlerError::ApiError(anyhow::anyhow!(
+            "coingecko has no coin {}",
+            code
+        )));
+    };
+    let missing =
+        || HandlerError::ApiError(anyhow::anyhow!("coingecko has no {} price of {}", vs, id));
+
+    match date {
+        None => {
+            let params = [
+                ("ids", id.to_string()),
+                ("vs_currencies", vs.to_string()),
+                ("include_last_updated_at", "true".to_string()),
+                ("precision", "full".to_string()),
+            ];
+            let (resp, age): (SimplePriceResp, _) =
+                get_json_cached(&format!("{}/simple/price", base_url), &params, ttl).await?;
+            let prices = resp.get(id).ok_or_else(missing)?;
+
+            Ok(Quote {
+                price: *prices.get(vs).ok_or_else(missing)?,
+                date: prices
+                    .get("last_updated_at")
+                    .and_then(|at| at.to_i64())
+                    .and_then(|at| DateTime::from_timestamp(at, 0)),
+                age,
+            })
+        }
+        Some(date) => {
+            let params = [
+                ("date", date.format("%d-%m-%Y").to_string()),
+                ("localization", "false".to_string()),
+            ];
+            let (resp, age): (HistoryResp, _) =
+                get_json_cached(&format!("{}/coins/{}/history", base_url, id), &params, ttl)
+                    .await?;
+            let price = resp
+                .market_data
+                .and_then(|data| data.current_price.get(vs).copied())
+                .ok_or_else(missing)?;
+
+            Ok(Quote {
+                price,
+                date: Some(date),
+                age,
+            })
+        }
+    }
+}
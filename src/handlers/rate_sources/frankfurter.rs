@@ -0,0 +1,126 @@
+//! Adapter of the frankfurter API (https://frankfurter.dev), the secondary rates source.
+//!
+//! Its answers are reshaped like pfm ones, so replies render them the same way.
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::config::config;
+use crate::deps::http_client::get_json_cached;
+use crate::error::HandlerError;
+use crate::handlers::forex::{ConvertResponseData, RatesResponseData};
+use crate::handlers::rate_sources::{ConvertQuery, RatesQuery};
+use crate::i18n::{Lang, format_number};
+
+/// Body of `/latest` and `/YYYY-MM-DD`, e.g. `{"amount":100.0,"base":"USD","date":"2024-03-08","rates":{"IDR":1573000.0}}`.
+#[derive(Debug, Deserialize)]
+pub(crate) struct FrankfurterResp {
+    pub base: String,
+    pub date: NaiveDate,
+    pub rates: HashMap<String, Decimal>,
+}
+
+/// Base url of the API, none when it is disabled.
+pub(crate) fn base_url() -> Option<String> {
+    // tests running inside a fake frankfurter API scope talk to it instead
+    #[cfg(test)]
+    if let Some(base_url) = crate::testing::fake_frankfurter::scoped_base_url() {
+        return Some(base_url);
+    }
+
+    let base_url = config()
+        .forex_fallback_url
+        .trim_end_matches('/')
+        .to_string();
+    (!base_url.is_empty()).then_some(base_url)
+}
+
+fn endpoint(base_url: &str, date: Option<DateTime<Utc>>) -> String {
+    match date {
+        Some(date) => format!("{}/{}", base_url, date.format("%Y-%m-%d")),
+        None => format!("{}/latest", base_url),
+    }
+}
+
+pub(crate) async fn convert(
+    base_url: &str,
+    query: &ConvertQuery,
+    ttl: Duration,
+) -> Result<(ConvertResponseData, Duration), HandlerError> {
+    let params = [
+        ("amount", query.amount.replace(',', "")),
+        ("from", query.from.clone()),
+        ("to", query.to.clone()),
+    ];
+    let (resp, age): (FrankfurterResp, _) =
+        get_json_cached(&endpoint(base_url, query.date), &params, ttl).await?;
+
+    Ok((convert_data(resp, query)?, age))
+}
+
+pub(crate) fn convert_data(
+    resp: FrankfurterResp,
+    query: &ConvertQuery,
+) -> Result<ConvertResponseData, HandlerError> {
+    let Some(value) = resp.rates.get(&query.to) else {
+        return Err(HandlerError::ApiError(anyhow::anyhow!(
+            "frankfurter answered without {}",
+            query.to
+        )));
+    };
+    let value = format_rate(*value);
+    let code = format!("{} {}", query.to, value);
+
+    Ok(ConvertResponseData {
+        date: midnight(resp.date),
+        from: HashMap::from([(query.from.clone(), query.amount.clone())]),
+        to: HashMap::from([(query.to.clone(), value)]),
+        // no currency symbols there
+        symbol: code.clone(),
+        code,
+    })
+}
+
+pub(crate) async fn rates(
+    base_url: &str,
+    query: &RatesQuery,
+    ttl: Duration,
+) -> Result<(RatesResponseData, Duration), HandlerError> {
+    let params = [("from", query.base.clone())];
+    let (resp, age): (FrankfurterResp, _) =
+        get_json_cached(&endpoint(base_url, query.date), &params, ttl).await?;
+
+    Ok((rates_data(resp), age))
+}
+
+/// pfm keys rates by lowercase code and lists the base too.
+pub(crate) fn rates_data(resp: FrankfurterResp) -> RatesResponseData {
+    let mut rates: HashMap<String, String> = resp
+        .rates
+        .into_iter()
+        .map(|(code, rate)| (code.to_ascii_lowercase(), format_rate(rate)))
+        .collect();
+    rates.insert(resp.base.to_ascii_lowercase(), "1".into());
+
+    RatesResponseData {
+        rates_date: midnight(resp.date),
+        base: resp.base,
+        rates,
+    }
+}
+
+// cents for rates above one, small rates keep their significant digits
+fn format_rate(rate: Decimal) -> String {
+    if rate >= Decimal::ONE {
+        format_number(Lang::En, rate, 2)
+    } else {
+        rate.round_dp(8).normalize().to_string()
+    }
+}
+
+fn midnight(date: NaiveDate) -> DateTime<Utc> {
+    date.and_time(chrono::NaiveTime::MIN).and_utc()
+}
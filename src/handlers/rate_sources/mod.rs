@@ -0,0 +1,377 @@
+//! rate_sources answers rates requests from the first source able to: the pfm API, then the frankfurter API
+//! (coingecko for crypto pairs), then the last known good answer kept in storage.
+//!
+//! Every answer carries which source gave it and when it was fetched, so replies can tell how stale they are.
+use std::future::Future;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::config::config;
+use crate::deps::http_client::get_json_cached;
+use crate::deps::storage::{LastRates, storage};
+use crate::error::HandlerError;
+use crate::handlers::forex::{ConvertResponseData, ForexResp, RatesResponseData, cache_ttl};
+use crate::i18n::{Lang, Localized};
+use crate::utils::currency::is_crypto;
+use crate::utils::time::updated_ago;
+
+pub(crate) mod coingecko;
+pub(crate) mod frankfurter;
+
+// endpoints under config forex_api_url
+static PFM_CONVERT_PATH: &str = "convert";
+
+static PFM_RATES_PATH: &str = "rates";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum RateSource {
+    Pfm,
+    Frankfurter,
+    CoinGecko,
+}
+
+impl RateSource {
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            RateSource::Pfm => "pfm",
+            RateSource::Frankfurter => "frankfurter",
+            RateSource::CoinGecko => "coingecko",
+        }
+    }
+
+    pub(crate) fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "pfm" => Some(RateSource::Pfm),
+            "frankfurter" => Some(RateSource::Frankfurter),
+            "coingecko" => Some(RateSource::CoinGecko),
+            _ => None,
+        }
+    }
+
+    fn label_key(&self) -> &'static str {
+        match self {
+            RateSource::Pfm => "source.pfm",
+            RateSource::Frankfurter => "source.frankfurter",
+            RateSource::CoinGecko => "source.coingecko",
+        }
+    }
+}
+
+/// Where an answer came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct SourceInfo {
+    pub source: RateSource,
+
+    // served from storage after every live source failed
+    pub stored: bool,
+
+    // when `source` answered, cached answers keep their original time
+    pub fetched_at: DateTime<Utc>,
+}
+
+impl SourceInfo {
+    pub(crate) fn age(&self, now: DateTime<Utc>) -> Duration {
+        (now - self.fetched_at).to_std().unwrap_or_default()
+    }
+
+    pub(crate) fn render(&self, lang: Lang, now: DateTime<Utc>) -> String {
+        let source = Localized::new(self.source.label_key()).render(lang);
+        let source = if self.stored {
+            Localized::new("source.stored")
+                .arg("source", source)
+                .render(lang)
+        } else {
+            source
+        };
+
+        format!("{}, {}", source, updated_ago(self.fetched_at, now, lang))
+    }
+}
+
+/// Reply footer naming the sources of `infos`, each with its oldest answer.
+pub(crate) fn source_footer(infos: &[SourceInfo], lang: Lang) -> String {
+    let mut oldest: Vec<SourceInfo> = vec![];
+    for info in infos {
+        match oldest
+            .iter_mut()
+            .find(|seen| seen.source == info.source && seen.stored == info.stored)
+        {
+            Some(seen) => seen.fetched_at = seen.fetched_at.min(info.fetched_at),
+            None => oldest.push(*info),
+        }
+    }
+
+    let now = Utc::now();
+    let sources: Vec<String> = oldest.iter().map(|info| info.render(lang, now)).collect();
+
+    Localized::new("source.footer")
+        .arg("sources", sources.join("; "))
+        .render(lang)
+}
+
+/// Answer of a rates request with its source.
+#[derive(Debug, Clone)]
+pub(crate) struct Sourced<T> {
+    pub resp: ForexResp<T>,
+    pub info: SourceInfo,
+}
+
+impl<T> Sourced<T> {
+    /// Footer naming the source, empty when the answer is an error with no data to credit.
+    pub(crate) fn footer(&self, lang: Lang) -> String {
+        if self.resp.error.is_none() && self.resp.data.is_some() {
+            source_footer(&[self.info], lang)
+        } else {
+            String::new()
+        }
+    }
+}
+
+/// `amount` of `from` in `to`, at `date` or latest.
+#[derive(Debug, Clone)]
+pub(crate) struct ConvertQuery {
+    pub from: String,
+    pub amount: String,
+    pub to: String,
+    pub date: Option<DateTime<Utc>>,
+}
+
+impl ConvertQuery {
+    /// Key of the last known good answer in storage.
+    pub(crate) fn stored_key(&self) -> String {
+        stored_key(PFM_CONVERT_PATH, &self.pfm_params())
+    }
+
+    fn pfm_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![
+            ("from", format!("{} {}", self.from, self.amount)),
+            ("to", self.to.clone()),
+        ];
+        if let Some(date) = self.date {
+            params.push(("date", date.format("%Y-%m-%d").to_string()));
+        }
+
+        params
+    }
+}
+
+/// All rates against `base`, at `date` or latest.
+#[derive(Debug, Clone)]
+pub(crate) struct RatesQuery {
+    pub base: String,
+    pub date: Option<DateTime<Utc>>,
+}
+
+impl RatesQuery {
+    /// Key of the last known good answer in storage.
+    pub(crate) fn stored_key(&self) -> String {
+        stored_key(PFM_RATES_PATH, &self.pfm_params())
+    }
+
+    fn pfm_params(&self) -> Vec<(&'static str, String)> {
+        let mut params = vec![];
+
+        // usd is upstream's default base
+        if !self.base.eq_ignore_ascii_case("usd") {
+            params.push(("base", self.base.clone()));
+        }
+        if let Some(date) = self.date {
+            params.push(("date", date.format("%Y-%m-%d").to_string()));
+        }
+
+        params
+    }
+}
+
+pub(crate) async fn convert(
+    query: &ConvertQuery,
+) -> Result<Sourced<ConvertResponseData>, HandlerError> {
+    let ttl = cache_ttl(query.date.is_some());
+    let params = query.pfm_params();
+
+    let fallback = convert_fallback(query).map(|(source, base_url)| {
+        let answer = async move {
+            match source {
+                RateSource::CoinGecko => coingecko::convert(&base_url, query, ttl).await,
+                _ => frankfurter::convert(&base_url, query, ttl).await,
+            }
+        };
+        (source, answer)
+    });
+
+    first_answer(
+        query.stored_key(),
+        get_json_cached(&forex_endpoint(PFM_CONVERT_PATH), &params, ttl),
+        fallback,
+    )
+    .await
+}
+
+// frankfurter has no crypto, pairs with one go to coingecko instead
+fn convert_fallback(query: &ConvertQuery) -> Option<(RateSource, String)> {
+    if is_crypto(&query.from) || is_crypto(&query.to) {
+        coingecko::base_url().map(|base_url| (RateSource::CoinGecko, base_url))
+    } else {
+        frankfurter::base_url().map(|base_url| (RateSource::Frankfurter, base_url))
+    }
+}
+
+pub(crate) async fn rates(query: &RatesQuery) -> Result<Sourced<RatesResponseData>, HandlerError> {
+    let ttl = cache_ttl(query.date.is_some());
+    let params = query.pfm_params();
+
+    let fallback = frankfurter::base_url()
+        .filter(|_| !is_crypto(&query.base))
+        .map(|base_url| {
+            let answer = async move { frankfurter::rates(&base_url, query, ttl).await };
+            (RateSource::Frankfurter, answer)
+        });
+
+    first_answer(
+        query.stored_key(),
+        get_json_cached(&forex_endpoint(PFM_RATES_PATH), &params, ttl),
+        fallback,
+    )
+    .await
+}
+
+/// Full url of a pfm forex API endpoint.
+pub(crate) fn forex_endpoint(path: &str) -> String {
+    // tests running inside a fake pfm API scope talk to it instead
+    #[cfg(test)]
+    if let Some(base_url) = crate::testing::fake_pfm::scoped_base_url() {
+        return format!("{}/{}", base_url, path);
+    }
+
+    format!("{}/{}", config().forex_api_url.trim_end_matches('/'), path)
+}
+
+// pfm url of the request, answers of another pfm deployment are never mixed in
+fn stored_key(path: &str, params: &[(&str, String)]) -> String {
+    let endpoint = forex_endpoint(path);
+
+    match reqwest::Url::parse_with_params(&endpoint, params) {
+        Ok(url) => url.to_string(),
+        Err(_) => endpoint,
+    }
+}
+
+async fn first_answer<T, P, F>(
+    key: String,
+    pfm: P,
+    fallback: Option<(RateSource, F)>,
+) -> Result<Sourced<T>, HandlerError>
+where
+    T: Serialize + DeserializeOwned,
+    P: Future<Output = Result<(ForexResp<T>, Duration), HandlerError>>,
+    F: Future<Output = Result<(T, Duration), HandlerError>>,
+{
+    let pfm_ret = match pfm.await {
+        Ok((resp, age)) if resp.error.is_none() => {
+            return Ok(remember(&key, RateSource::Pfm, resp, age));
+        }
+        // upstream refusing a request, e.g. a currency it lacks, is its answer unless another source has one
+        Ok((resp, age)) => {
+            eprintln!(
+                "[rates] pfm refused {}: {}",
+                key,
+                resp.error.as_deref().unwrap_or_default()
+            );
+            Ok(remember(&key, RateSource::Pfm, resp, age))
+        }
+        Err(err) => {
+            eprintln!("[rates] pfm failed for {}\n{}", key, err.chain());
+            Err(err)
+        }
+    };
+
+    if let Some((source, fallback)) = fallback {
+        match fallback.await {
+            Ok((data, age)) => {
+                let resp = ForexResp {
+                    data: Some(data),
+                    error: None,
+                };
+                return Ok(remember(&key, source, resp, age));
+            }
+            Err(err) => eprintln!(
+                "[rates] {} failed for {}\n{}",
+                source.name(),
+                key,
+                err.chain()
+            ),
+        }
+    }
+
+    let pfm_err = match pfm_ret {
+        Ok(refused) => return Ok(refused),
+        Err(err) => err,
+    };
+    match stored(&key) {
+        Ok(Some(ret)) => Ok(ret),
+        Ok(None) => Err(pfm_err),
+        Err(err) => {
+            eprintln!("[rates] {:#}", err);
+            Err(pfm_err)
+        }
+    }
+}
+
+// keep successful answers for when every live source is down
+fn remember<T: Serialize>(
+    key: &str,
+    source: RateSource,
+    resp: ForexResp<T>,
+    age: Duration,
+) -> Sourced<T> {
+    let fetched_at = Utc::now() - chrono::Duration::from_std(age).unwrap_or_default();
+
+    if resp.data.is_some() && resp.error.is_none() {
+        let ret = serde_json::to_string(&resp)
+            .map_err(anyhow::Error::from)
+            .and_then(|body| {
+                storage().save_last_rates(
+                    key,
+                    &LastRates {
+                        source: source.name().into(),
+                        body,
+                        fetched_at,
+                    },
+                )
+            });
+        if let Err(err) = ret {
+            eprintln!("[rates] failed keeping answer of {}: {:#}", key, err);
+        }
+    }
+
+    Sourced {
+        resp,
+        info: SourceInfo {
+            source,
+            stored: false,
+            fetched_at,
+        },
+    }
+}
+
+fn stored<T: DeserializeOwned>(key: &str) -> Result<Option<Sourced<T>>, anyhow::Error> {
+    let Some(last) = storage().last_rates(key)? else {
+        return Ok(None);
+    };
+    let Some(source) = RateSource::from_name(&last.source) else {
+        return Ok(None);
+    };
+
+    Ok(Some(Sourced {
+        resp: serde_json::from_str(&last.body)?,
+        info: SourceInfo {
+            source,
+            stored: true,
+            fetched_at: last.fetched_at,
+        },
+    }))
+}
@@ -0,0 +1,416 @@
+use std::collections::HashMap;
+use std::future::Future;
+
+use chrono::{Duration, NaiveDate, SubsecRound as _, TimeZone, Utc};
+use rust_decimal_macros::dec;
+
+use crate::deps::storage::{LastRates, storage};
+use crate::error::HandlerError;
+use crate::handler;
+use crate::handlers::forex::{ConvertResponseData, ForexResp};
+use crate::handlers::rate_sources::frankfurter::{FrankfurterResp, convert_data};
+use crate::handlers::rate_sources::{
+    self, ConvertQuery, RateSource, RatesQuery, SourceInfo, source_footer,
+};
+use crate::i18n::Lang;
+use crate::testing::fake_bot::{FakeBotApi, message_update, private_message};
+use crate::testing::fake_coingecko::FakeCoinGeckoApi;
+use crate::testing::fake_frankfurter::FakeFrankfurterApi;
+use crate::testing::fake_pfm::{FakePfmApi, Mode};
+
+fn usd_idr(amount: &str) -> ConvertQuery {
+    ConvertQuery {
+        from: "USD".into(),
+        amount: amount.into(),
+        to: "IDR".into(),
+        date: None,
+    }
+}
+
+async fn scoped<F: Future>(
+    pfm: &FakePfmApi,
+    frankfurter: &FakeFrankfurterApi,
+    fut: F,
+) -> F::Output {
+    frankfurter.scope(pfm.scope(fut)).await
+}
+
+#[tokio::test]
+async fn pfm_answers_first() {
+    let pfm = FakePfmApi::start().await;
+    let frankfurter = FakeFrankfurterApi::start().await;
+    let query = usd_idr("100");
+
+    let ret = scoped(&pfm, &frankfurter, rate_sources::convert(&query))
+        .await
+        .unwrap();
+
+    assert_eq!(RateSource::Pfm, ret.info.source);
+    assert!(!ret.info.stored);
+    assert_eq!("IDR 1,573,000.00", ret.resp.data.unwrap().code);
+    assert!(frankfurter.requests().is_empty());
+
+    // kept for when every source is down
+    let key = pfm.scope(async { query.stored_key() }).await;
+    let last = storage().last_rates(&key).unwrap().unwrap();
+    assert_eq!("pfm", last.source);
+    assert_eq!(
+        ret.info.fetched_at.timestamp_millis(),
+        last.fetched_at.timestamp_millis()
+    );
+}
+
+#[tokio::test]
+async fn frankfurter_answers_when_pfm_fails() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ServerError);
+    let frankfurter = FakeFrankfurterApi::start().await;
+
+    let ret = scoped(&pfm, &frankfurter, rate_sources::convert(&usd_idr("1,000")))
+        .await
+        .unwrap();
+
+    assert_eq!(RateSource::Frankfurter, ret.info.source);
+    assert_eq!(
+        vec!["/latest?amount=1000&from=USD&to=IDR"],
+        frankfurter.requests()
+    );
+
+    let data = ret.resp.data.unwrap();
+    assert_eq!(
+        Utc.with_ymd_and_hms(2024, 3, 8, 0, 0, 0).unwrap(),
+        data.date
+    );
+    assert_eq!(Some(&"1,000".to_string()), data.from.get("USD"));
+    assert_eq!(Some(&"15,700,000.00".to_string()), data.to.get("IDR"));
+    assert_eq!("IDR 15,700,000.00", data.code);
+}
+
+#[test]
+fn frankfurter_small_amounts_keep_their_digits() {
+    let resp = FrankfurterResp {
+        base: "IDR".into(),
+        date: NaiveDate::from_ymd_opt(2024, 3, 8).unwrap(),
+        rates: HashMap::from([("USD".to_string(), dec!(0.0000637))]),
+    };
+    let query = ConvertQuery {
+        from: "IDR".into(),
+        amount: "1".into(),
+        to: "USD".into(),
+        date: None,
+    };
+
+    let data = convert_data(resp, &query).unwrap();
+
+    assert_eq!(Some(&"0.0000637".to_string()), data.to.get("USD"));
+    assert_eq!("USD 0.0000637", data.code);
+}
+
+#[tokio::test]
+async fn frankfurter_rates_are_reshaped() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::MalformedJson);
+    let frankfurter = FakeFrankfurterApi::start().await;
+    let query = RatesQuery {
+        base: "IDR".into(),
+        date: Some(Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap()),
+    };
+
+    let ret = scoped(&pfm, &frankfurter, rate_sources::rates(&query))
+        .await
+        .unwrap();
+
+    assert_eq!(vec!["/2022-02-02?from=IDR"], frankfurter.requests());
+
+    let data = ret.resp.data.unwrap();
+    assert_eq!("IDR", data.base);
+    assert_eq!(
+        Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap(),
+        data.rates_date
+    );
+    assert_eq!(Some(&"1".to_string()), data.rates.get("idr"));
+    assert_eq!(Some(&"0.000064".to_string()), data.rates.get("usd"));
+    assert_eq!(5, data.rates.len());
+}
+
+#[tokio::test]
+async fn last_known_rates_when_every_source_fails() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ServerError);
+    let frankfurter = FakeFrankfurterApi::start().await;
+    frankfurter.set_down(true);
+    let query = usd_idr("1");
+
+    // storage keeps milliseconds
+    let fetched_at = (Utc::now() - Duration::hours(3)).trunc_subsecs(3);
+    let resp = ForexResp {
+        data: Some(ConvertResponseData {
+            date: Utc.with_ymd_and_hms(2024, 3, 7, 0, 0, 0).unwrap(),
+            from: HashMap::from([("USD".into(), "1".into())]),
+            to: HashMap::from([("IDR".into(), "15,650.00".into())]),
+            code: "IDR 15,650.00".into(),
+            symbol: "Rp15,650.00".into(),
+        }),
+        error: None,
+    };
+    let key = pfm.scope(async { query.stored_key() }).await;
+    storage()
+        .save_last_rates(
+            &key,
+            &LastRates {
+                source: "frankfurter".into(),
+                body: serde_json::to_string(&resp).unwrap(),
+                fetched_at,
+            },
+        )
+        .unwrap();
+
+    let ret = scoped(&pfm, &frankfurter, rate_sources::convert(&query))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        SourceInfo {
+            source: RateSource::Frankfurter,
+            stored: true,
+            fetched_at,
+        },
+        ret.info
+    );
+    assert_eq!("IDR 15,650.00", ret.resp.data.unwrap().code);
+    assert_eq!(
+        "<i>Source: last known rates from Frankfurter, updated 3 hours ago</i>",
+        source_footer(&[ret.info], Lang::En)
+    );
+}
+
+#[tokio::test]
+async fn pfm_error_when_nothing_answers() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ServerError);
+    let frankfurter = FakeFrankfurterApi::start().await;
+
+    // frankfurter has no crypto, and coingecko is disabled
+    let query = ConvertQuery {
+        from: "BTC".into(),
+        amount: "1".into(),
+        to: "USD".into(),
+        date: None,
+    };
+    let ret = scoped(&pfm, &frankfurter, rate_sources::convert(&query)).await;
+
+    assert!(matches!(ret, Err(HandlerError::ApiError(_))), "{:?}", ret);
+    assert!(frankfurter.requests().is_empty());
+}
+
+#[test]
+fn footer_names_each_source_once() {
+    let now = Utc::now();
+    let info = |source, stored, minutes| SourceInfo {
+        source,
+        stored,
+        fetched_at: now - Duration::minutes(minutes),
+    };
+
+    assert_eq!(
+        "<i>Source: pfm API, updated just now</i>",
+        source_footer(&[info(RateSource::Pfm, false, 0)], Lang::En)
+    );
+    assert_eq!(
+        "<i>Source: pfm API, updated 5 minutes ago; Frankfurter, updated 2 days ago</i>",
+        source_footer(
+            &[
+                info(RateSource::Pfm, false, 1),
+                info(RateSource::Frankfurter, false, 2 * 24 * 60 + 5),
+                info(RateSource::Pfm, false, 5),
+            ],
+            Lang::En
+        )
+    );
+    assert_eq!(
+        "<i>Sumber: kurs terakhir dari API pfm, diperbarui 1 jam yang lalu</i>",
+        source_footer(&[info(RateSource::Pfm, true, 61)], Lang::Id)
+    );
+}
+
+#[tokio::test]
+async fn forex_reply_names_fallback_source() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ServerError);
+    let frankfurter = FakeFrankfurterApi::start().await;
+    let api = FakeBotApi::start().await;
+
+    scoped(
+        &pfm,
+        &frankfurter,
+        api.dispatch(
+            handler(),
+            message_update(1, private_message(1, 340_001, "/forex usd/eur")),
+        ),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(
+        Some(
+            "USD/EUR on 2024-03-08 is:\n<b>EUR 0.92</b>\n\n<i>Source: Frankfurter, updated just now</i>"
+        ),
+        api.sent_messages()[0].text()
+    );
+}
+
+#[tokio::test]
+async fn watchlist_keeps_pairs_some_source_answers() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ServerError);
+    let frankfurter = FakeFrankfurterApi::start().await;
+    let api = FakeBotApi::start().await;
+
+    scoped(
+        &pfm,
+        &frankfurter,
+        api.dispatch(
+            handler(),
+            message_update(1, private_message(1, 340_002, "/forex")),
+        ),
+    )
+    .await
+    .unwrap();
+
+    let text = api.sent_messages()[0].text().unwrap().to_string();
+    assert!(
+        text.contains("\n- <b>USD/IDR= IDR 15,700.00</b>\n"),
+        "{}",
+        text
+    );
+    assert!(
+        text.contains("\nerror: BTC/USD: The rates service couldn't answer"),
+        "{}",
+        text
+    );
+    assert!(
+        text.ends_with("<i>Source: Frankfurter, updated just now</i>"),
+        "{}",
+        text
+    );
+}
+
+fn crypto_query(from: &str, amount: &str, to: &str) -> ConvertQuery {
+    ConvertQuery {
+        from: from.into(),
+        amount: amount.into(),
+        to: to.into(),
+        date: None,
+    }
+}
+
+#[tokio::test]
+async fn crypto_pairs_pfm_lacks_go_to_coingecko() {
+    // no fixture of DOGE, the fake pfm answers 500
+    let pfm = FakePfmApi::start().await;
+    let frankfurter = FakeFrankfurterApi::start().await;
+    let coingecko = FakeCoinGeckoApi::start().await;
+
+    let ret = coingecko
+        .scope(scoped(
+            &pfm,
+            &frankfurter,
+            rate_sources::convert(&crypto_query("DOGE", "1,000", "IDR")),
+        ))
+        .await
+        .unwrap();
+
+    assert_eq!(RateSource::CoinGecko, ret.info.source);
+    assert!(frankfurter.requests().is_empty());
+    assert_eq!(
+        vec![
+            "/simple/price?ids=dogecoin&include_last_updated_at=true&precision=full&vs_currencies=idr"
+        ],
+        coingecko.requests()
+    );
+
+    let data = ret.resp.data.unwrap();
+    assert_eq!(
+        Utc.with_ymd_and_hms(2024, 3, 8, 0, 0, 0).unwrap(),
+        data.date
+    );
+    assert_eq!(Some(&"2,355,000.00".to_string()), data.to.get("IDR"));
+}
+
+#[tokio::test]
+async fn pfm_refusing_a_coin_goes_to_coingecko() {
+    let pfm = FakePfmApi::start().await;
+    pfm.set_mode(Mode::ErrorField("currency SOL is not supported".into()));
+    let coingecko = FakeCoinGeckoApi::start().await;
+
+    let query = ConvertQuery {
+        date: Some(Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap()),
+        ..crypto_query("EUR", "276", "SOL")
+    };
+    let ret = coingecko
+        .scope(pfm.scope(rate_sources::convert(&query)))
+        .await
+        .unwrap();
+
+    assert_eq!(RateSource::CoinGecko, ret.info.source);
+    assert_eq!(
+        vec!["/coins/solana/history?date=02-02-2022&localization=false"],
+        coingecko.requests()
+    );
+    assert_eq!("SOL 2.00", ret.resp.data.unwrap().code);
+
+    // the refusal stands when coingecko can't answer either
+    coingecko.set_down(true);
+    let ret = coingecko
+        .scope(pfm.scope(rate_sources::convert(&crypto_query("SOL", "3", "EUR"))))
+        .await
+        .unwrap();
+    assert_eq!(RateSource::Pfm, ret.info.source);
+    assert_eq!(
+        Some("currency SOL is not supported".to_string()),
+        ret.resp.error
+    );
+}
+
+#[tokio::test]
+async fn crypto_cross_pairs_keep_tiny_amounts() {
+    let pfm = FakePfmApi::start().await;
+    let coingecko = FakeCoinGeckoApi::start().await;
+
+    let ret = coingecko
+        .scope(pfm.scope(rate_sources::convert(&crypto_query("PEPE", "1", "BTC"))))
+        .await
+        .unwrap();
+
+    // both priced in USD
+    assert_eq!(2, coingecko.requests().len());
+    assert_eq!(
+        Some(&"0.0000000000176".to_string()),
+        ret.resp.data.unwrap().to.get("BTC")
+    );
+}
+
+#[tokio::test]
+async fn convert_reply_of_a_crypto_pair() {
+    let pfm = FakePfmApi::start().await;
+    let coingecko = FakeCoinGeckoApi::start().await;
+    let api = FakeBotApi::start().await;
+
+    coingecko
+        .scope(pfm.scope(api.dispatch(
+            handler(),
+            message_update(
+                1,
+                private_message(1, 340_003, "/convert pepe 1,000,000; usd"),
+            ),
+        )))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        Some(
+            "Conversion on 2024-03-08:\n<b>PEPE 1,000,000.00 = US$1.20</b>\n\n<i>Source: CoinGecko, updated just now</i>"
+        ),
+        api.sent_messages()[0].text()
+    );
+}
@@ -0,0 +1,380 @@
+//! rate_stats answers `/forex <pair> stats [range]`: open, close, low, high, average, change and daily volatility
+//! of a pair over a range of days, from daily historical rates.
+//!
+//! Stats of a range that is over never change, they're kept in the response cache.
+use std::sync::LazyLock;
+
+use chrono::{Datelike, Days, Months, NaiveDate, Weekday};
+use chrono_tz::Tz;
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use teloxide::prelude::*;
+
+use crate::deps::cache::response_cache;
+use crate::error::HandlerError;
+use crate::handlers::forex::{ConvertResponseData, HISTORICAL_CACHE_TTL};
+use crate::handlers::rate_buttons::{percent, rate};
+use crate::handlers::rate_sources::{self, ConvertQuery, SourceInfo, Sourced, source_footer};
+use crate::handlers::reply::Reply;
+use crate::i18n::{Lang, Localized, format_number, lang_for, t};
+use crate::utils::currency::render_notes;
+use crate::utils::date::{MAX_DATE_WORDS, parse_date, today};
+use crate::utils::money::{Money, MoneyStyle};
+use crate::utils::syntax::{Kind, Matched, Value};
+use crate::utils::time::timezone_for;
+
+/// Words after a pair asking for its stats.
+pub(crate) const STATS_WORDS: &[&str] = &["stats", "statistik"];
+
+// range when none is given
+const DEFAULT_DAYS: u64 = 30;
+
+// one upstream request per weekday, a month keeps a call to about 23 of them
+const MAX_DAYS: u64 = 31;
+
+// weekdays asked for at once, few enough to stay under upstream rate limits
+const CONCURRENT_DAYS: usize = 4;
+
+// decimals of volatility, a standard deviation of daily changes in percent
+const VOLATILITY_DECIMALS: u32 = 4;
+
+/// Range of days, see `parse_range`.
+pub(crate) static RANGE: Kind = Kind {
+    // 1 sep 2026 sampai jumat lalu
+    max_words: 2 * MAX_DATE_WORDS + 1,
+    // 17/10/2026..18/10/2026
+    spans_separators: true,
+    parse: |text, ctx, _| parse_range(text, ctx.today).map(|(start, end)| Value::Range(start, end)),
+    // nothing else goes after the stats word
+    claims: |_| true,
+};
+
+static RELATIVE_RANGE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{1,3})\s*([dwmy])$").expect("failed initializing stats range regex")
+});
+
+static RANGE_SEPARATOR: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\s*\.\.\s*|\s+(?:to|sampai|hingga)\s+").expect("failed initializing range regex")
+});
+
+/// Stats of `left/right` from `start` to `end`, both included.
+#[derive(Debug, Clone)]
+pub(crate) struct StatsArg {
+    pub(super) left: String,
+    pub(super) right: String,
+    pub(super) start: NaiveDate,
+    pub(super) end: NaiveDate,
+
+    // how ambiguous currency names were read
+    pub(super) notes: Vec<Localized>,
+}
+
+impl StatsArg {
+    /// Stats of the pair matched, over the last days when no range was given.
+    pub(crate) fn from_matched(matched: Matched, today: NaiveDate) -> Result<Self, HandlerError> {
+        let (start, end) = match matched.range("range") {
+            Some(range) => range,
+            None => parse_range("", today)?,
+        };
+
+        Ok(StatsArg {
+            left: matched.text("from"),
+            right: matched.text("to"),
+            start,
+            end,
+            notes: matched.notes,
+        })
+    }
+}
+
+/// First and last day of `range`: `30d`, `4w`, `1m` ending today, or two dates like `2024-01-01..2024-01-31`.
+pub(crate) fn parse_range(
+    range: &str,
+    today: NaiveDate,
+) -> Result<(NaiveDate, NaiveDate), Localized> {
+    let range = range.trim().to_lowercase();
+
+    let (start, end) = if range.is_empty() {
+        (today.checked_sub_days(Days::new(DEFAULT_DAYS)), Some(today))
+    } else if let Some(caps) = RELATIVE_RANGE.captures(&range) {
+        let count: u32 = caps[1].parse().map_err(|_| format_err())?;
+        let start = match &caps[2] {
+            "d" => today.checked_sub_days(Days::new(count as u64)),
+            "w" => today.checked_sub_days(Days::new(count as u64 * 7)),
+            "m" => today.checked_sub_months(Months::new(count)),
+            _ => today.checked_sub_months(Months::new(count * 12)),
+        };
+        (start, Some(today))
+    } else {
+        let dates: Vec<&str> = RANGE_SEPARATOR.splitn(&range, 2).collect();
+        let [start, end] = dates[..] else {
+            return Err(format_err());
+        };
+        (parse_date(start, today), parse_date(end, today))
+    };
+    let (start, end) = start.zip(end).ok_or_else(format_err)?;
+
+    if end > today {
+        return Err(Localized::new("date.future")
+            .arg("date", end.format("%Y-%m-%d"))
+            .arg("today", today.format("%Y-%m-%d")));
+    }
+    if start >= end || (end - start).num_days() > MAX_DAYS as i64 {
+        return Err(Localized::new("rate_stats.range").arg("max", MAX_DAYS));
+    }
+
+    Ok((start, end))
+}
+
+fn format_err() -> Localized {
+    Localized::new("rate_stats.format")
+}
+
+/// Rate of a pair on a day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RatePoint {
+    pub date: NaiveDate,
+    pub rate: Decimal,
+}
+
+/// Stats of a series of daily rates.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RangeStats {
+    pub open: RatePoint,
+    pub close: RatePoint,
+    pub low: RatePoint,
+    pub high: RatePoint,
+    pub average: Decimal,
+
+    // from open to close, in percent
+    pub change: Decimal,
+
+    // sample standard deviation of day to day changes in percent, none below two changes
+    pub volatility: Option<Decimal>,
+
+    pub days: usize,
+}
+
+impl RangeStats {
+    /// Stats of `series`, sorted by date. None without two days, or with a zero open rate.
+    pub(crate) fn compute(series: &[RatePoint]) -> Option<Self> {
+        let (open, close) = (*series.first()?, *series.last()?);
+        if series.len() < 2 || open.rate.is_zero() {
+            return None;
+        }
+
+        // the earliest day of a tie
+        let mut low = open;
+        let mut high = open;
+        for point in series {
+            if point.rate < low.rate {
+                low = *point;
+            }
+            if point.rate > high.rate {
+                high = *point;
+            }
+        }
+
+        let sum: Decimal = series.iter().map(|point| point.rate).sum();
+        let average = sum / Decimal::from(series.len());
+
+        Some(RangeStats {
+            open,
+            close,
+            low,
+            high,
+            average,
+            change: (close.rate - open.rate) / open.rate * Decimal::ONE_HUNDRED,
+            volatility: volatility(series),
+            days: series.len(),
+        })
+    }
+
+    pub(crate) fn render(&self, pair: (&str, &str), lang: Lang) -> String {
+        let (left, right) = pair;
+        let money =
+            |point: &RatePoint| Money::new(right, point.rate).format(lang, MoneyStyle::Symbol);
+        let volatility = match self.volatility {
+            Some(volatility) => format!("{}%", format_number(lang, volatility, 2)),
+            None => t(lang, "rate_stats.no_volatility").into(),
+        };
+
+        Localized::new("rate_stats.result")
+            .arg("days", self.days)
+            .arg("pair", format!("{}/{}", left, right))
+            .arg("start", self.open.date.format("%Y-%m-%d"))
+            .arg("end", self.close.date.format("%Y-%m-%d"))
+            .arg("open", money(&self.open))
+            .arg("close", money(&self.close))
+            .arg("low", money(&self.low))
+            .arg("low_date", self.low.date.format("%Y-%m-%d"))
+            .arg("high", money(&self.high))
+            .arg("high_date", self.high.date.format("%Y-%m-%d"))
+            .arg(
+                "average",
+                Money::new(right, self.average).format(lang, MoneyStyle::Symbol),
+            )
+            .arg("change", percent(self.change, lang))
+            .arg("volatility", volatility)
+            .render(lang)
+    }
+}
+
+fn volatility(series: &[RatePoint]) -> Option<Decimal> {
+    let changes: Vec<f64> = series
+        .windows(2)
+        .filter_map(|days| (days[1].rate / days[0].rate - Decimal::ONE).to_f64())
+        .collect();
+    if changes.len() < 2 {
+        return None;
+    }
+
+    let mean = changes.iter().sum::<f64>() / changes.len() as f64;
+    let variance = changes
+        .iter()
+        .map(|change| (change - mean).powi(2))
+        .sum::<f64>()
+        / (changes.len() - 1) as f64;
+
+    Decimal::from_f64(variance.sqrt() * 100.0).map(|value| value.round_dp(VOLATILITY_DECIMALS))
+}
+
+// stats and the sources of their rates, as cached
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedStats {
+    stats: RangeStats,
+    infos: Vec<SourceInfo>,
+
+    // weekdays without a rate, upstream failing or answering with an error
+    skipped: usize,
+}
+
+pub(crate) async fn stats(bot: Bot, msg: &Message, arg: StatsArg) -> Result<(), HandlerError> {
+    let lang = lang_for(msg);
+    let notes = arg.notes.clone();
+    let pair = (arg.left.clone(), arg.right.clone());
+
+    let CachedStats {
+        stats,
+        infos,
+        skipped,
+    } = fetch_stats(arg, timezone_for(msg.chat.id.0)).await?;
+    let mut notes = notes;
+    if skipped > 0 {
+        notes.push(Localized::plural("rate_stats.skipped", skipped as i64));
+    }
+
+    let text = format!(
+        "{}\n\n{}{}",
+        stats.render((&pair.0, &pair.1), lang),
+        render_notes(&notes, lang),
+        source_footer(&infos, lang)
+    );
+    Reply::new(text).html().quote().send(&bot, msg).await?;
+
+    Ok(())
+}
+
+async fn fetch_stats(arg: StatsArg, tz: Tz) -> Result<CachedStats, HandlerError> {
+    let key = format!("stats:{}/{}:{}:{}", arg.left, arg.right, arg.start, arg.end);
+    if let Some(cached) = response_cache().get(&key)
+        && let Ok(cached) = serde_json::from_str(&cached)
+    {
+        return Ok(cached);
+    }
+
+    let today = today(tz);
+    let days: Vec<NaiveDate> = weekdays(arg.start, arg.end).collect();
+    let mut answers: Vec<(
+        NaiveDate,
+        Result<Sourced<ConvertResponseData>, HandlerError>,
+    )> = stream::iter(days.iter().copied())
+        .map(|day| {
+            let query = ConvertQuery {
+                from: arg.left.clone(),
+                amount: "1".into(),
+                to: arg.right.clone(),
+                // today's historical rates may not be out yet
+                date: (day < today)
+                    .then(|| day.and_hms_opt(0, 0, 0).map(|day| day.and_utc()))
+                    .flatten(),
+            };
+            async move { (day, rate_sources::convert(&query).await) }
+        })
+        .buffer_unordered(CONCURRENT_DAYS)
+        .collect()
+        .await;
+    answers.sort_by_key(|(day, _)| *day);
+
+    let mut series: Vec<RatePoint> = vec![];
+    let mut infos = vec![];
+    let mut skipped = 0;
+    let mut first_err = None;
+    for (day, ret) in answers {
+        let resp = match ret {
+            Ok(resp) => resp,
+            Err(err) => {
+                eprintln!(
+                    "[rate_stats] skipped {}/{} on {}: {}",
+                    arg.left,
+                    arg.right,
+                    day,
+                    err.chain()
+                );
+                skipped += 1;
+                first_err.get_or_insert(err);
+                continue;
+            }
+        };
+        infos.push(resp.info);
+
+        let point = resp
+            .resp
+            .data
+            .filter(|_| resp.resp.error.is_none())
+            .and_then(|data| Some((data.date.date_naive(), rate(&data).ok()?)));
+        let Some((date, rate)) = point else {
+            skipped += 1;
+            continue;
+        };
+        // holidays answer with the day before, already in the series
+        if series.iter().any(|point| point.date == date) {
+            continue;
+        }
+        series.push(RatePoint { date, rate });
+    }
+
+    // stats of a few scattered days would mislead more than they tell
+    let stats = RangeStats::compute(&series).filter(|_| skipped * 2 <= days.len());
+    let stats = match (stats, first_err) {
+        (Some(stats), _) => stats,
+        (None, Some(err)) => return Err(err),
+        (None, None) => return Err(Localized::new("rate_stats.no_data").into()),
+    };
+    let ret = CachedStats {
+        stats,
+        infos,
+        skipped,
+    };
+
+    // days that failed may answer next time
+    if arg.end < today
+        && skipped == 0
+        && let Ok(value) = serde_json::to_string(&ret)
+    {
+        response_cache().insert(key, value, HISTORICAL_CACHE_TTL);
+    }
+
+    Ok(ret)
+}
+
+// days markets are open from `start` to `end`
+fn weekdays(start: NaiveDate, end: NaiveDate) -> impl Iterator<Item = NaiveDate> {
+    start
+        .iter_days()
+        .take_while(move |day| *day <= end)
+        .filter(|day| !matches!(day.weekday(), Weekday::Sat | Weekday::Sun))
+}
@@ -0,0 +1,228 @@
+use chrono::NaiveDate;
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+use crate::commands::Args;
+use crate::handler;
+use crate::handlers::forex::ForexArgs;
+use crate::handlers::rate_stats::{RangeStats, RatePoint, parse_range};
+use crate::i18n::Lang;
+use crate::testing::fake_bot::{FakeBotApi, message_update, private_message};
+use crate::testing::fake_pfm::FakePfmApi;
+
+fn date(text: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+}
+
+fn series(rates: &[(&str, Decimal)]) -> Vec<RatePoint> {
+    rates
+        .iter()
+        .map(|(day, rate)| RatePoint {
+            date: date(day),
+            rate: *rate,
+        })
+        .collect()
+}
+
+#[test]
+fn stats_of_a_series() {
+    let stats = RangeStats::compute(&series(&[
+        ("2024-03-04", dec!(100)),
+        ("2024-03-05", dec!(110)),
+        ("2024-03-06", dec!(99)),
+        ("2024-03-07", dec!(110)),
+        ("2024-03-08", dec!(105)),
+    ]))
+    .unwrap();
+
+    assert_eq!(date("2024-03-04"), stats.open.date);
+    assert_eq!(dec!(105), stats.close.rate);
+    assert_eq!(
+        (date("2024-03-06"), dec!(99)),
+        (stats.low.date, stats.low.rate)
+    );
+    // the earliest day of a tie
+    assert_eq!(date("2024-03-05"), stats.high.date);
+    assert_eq!(dec!(104.8), stats.average);
+    assert_eq!(dec!(5), stats.change);
+    assert_eq!(5, stats.days);
+}
+
+#[test]
+fn volatility_needs_two_changes() {
+    let stats = RangeStats::compute(&series(&[
+        ("2024-03-04", dec!(100)),
+        ("2024-03-05", dec!(110)),
+        ("2024-03-06", dec!(99)),
+    ]))
+    .unwrap();
+    assert_eq!(Some(dec!(14.1421)), stats.volatility);
+
+    let flat = RangeStats::compute(&series(&[
+        ("2024-03-04", dec!(100)),
+        ("2024-03-05", dec!(100)),
+        ("2024-03-06", dec!(100)),
+    ]))
+    .unwrap();
+    assert_eq!(Some(Decimal::ZERO), flat.volatility);
+
+    let two = RangeStats::compute(&series(&[
+        ("2024-03-04", dec!(100)),
+        ("2024-03-05", dec!(101)),
+    ]))
+    .unwrap();
+    assert_eq!(None, two.volatility);
+    assert!(
+        two.render(("USD", "IDR"), Lang::En)
+            .contains("Daily volatility: n/a")
+    );
+
+    assert!(RangeStats::compute(&series(&[("2024-03-04", dec!(100))])).is_none());
+    assert!(
+        RangeStats::compute(&series(&[("2024-03-04", dec!(0)), ("2024-03-05", dec!(1))])).is_none()
+    );
+}
+
+#[test]
+fn ranges() {
+    let today = date("2026-10-18");
+
+    assert_eq!(
+        Ok((date("2026-09-18"), today)),
+        parse_range("", today).map_err(|err| err.render(Lang::En))
+    );
+    assert_eq!(date("2026-10-11"), parse_range("7d", today).unwrap().0);
+    assert_eq!(date("2026-09-20"), parse_range("4w", today).unwrap().0);
+    assert_eq!(date("2026-09-18"), parse_range("1M", today).unwrap().0);
+    assert_eq!(
+        (date("2024-01-01"), date("2024-01-31")),
+        parse_range("2024-01-01..2024-01-31", today).unwrap()
+    );
+    assert_eq!(
+        (date("2026-10-04"), date("2026-10-17")),
+        parse_range("2 weeks ago to kemarin", today).unwrap()
+    );
+    assert_eq!(
+        (date("2026-09-20"), date("2026-10-16")),
+        parse_range("20 sep 2026 sampai jumat lalu", today).unwrap()
+    );
+
+    let err = |range| parse_range(range, today).unwrap_err().render(Lang::En);
+    assert_eq!(
+        "A range goes from an earlier day to a later one, at most 31 days.",
+        err("2024-01-31..2024-01-01")
+    );
+    assert_eq!(
+        "A range goes from an earlier day to a later one, at most 31 days.",
+        err("1y")
+    );
+    assert!(err("2026-10-01..2026-10-20").contains("is in the future"));
+    assert!(err("someday").starts_with("Stats take a range like 30d"));
+}
+
+#[test]
+fn stats_arguments() {
+    let ForexArgs::Stats(arg) =
+        ForexArgs::try_from(Args("yen/rupiah statistik 2024-01-01..2024-01-31".into())).unwrap()
+    else {
+        panic!("not stats");
+    };
+    assert_eq!(("JPY", "IDR"), (arg.left.as_str(), arg.right.as_str()));
+    assert_eq!(date("2024-01-01"), arg.start);
+
+    assert!(ForexArgs::try_from(Args("USD stats 30d".into())).is_err());
+    assert!(matches!(
+        ForexArgs::try_from(Args("USD/IDR 2024-01-01".into())),
+        Ok(ForexArgs::SinglePair(_))
+    ));
+}
+
+#[tokio::test]
+async fn stats_of_past_range_are_cached() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+    let command = "/forex USD/IDR stats 2022-01-29..2022-02-06";
+
+    pfm.scope(api.dispatch(
+        handler(),
+        message_update(1, private_message(1, 390_001, command)),
+    ))
+    .await
+    .unwrap();
+
+    // weekdays only
+    assert_eq!(5, pfm.requests().len());
+    assert_eq!(
+        Some(
+            "📊 <b>USD/IDR</b> from 2022-01-31 to 2022-02-04, 5 trading days:
+Open: Rp14,380.00
+Close: Rp14,395.00
+Low: Rp14,340.00 on 2022-02-03
+High: Rp14,395.00 on 2022-02-04
+Average: Rp14,367.00
+Change: <b>+0.10%</b>
+Daily volatility: 0.25%
+
+<i>Source: pfm API, updated just now</i>"
+        ),
+        api.sent_messages()[0].text()
+    );
+
+    pfm.scope(api.dispatch(
+        handler(),
+        message_update(2, private_message(2, 390_001, command)),
+    ))
+    .await
+    .unwrap();
+
+    assert_eq!(5, pfm.requests().len());
+    assert_eq!(api.sent_messages()[0].text(), api.sent_messages()[1].text());
+}
+
+#[tokio::test]
+async fn failed_days_are_left_out() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+    pfm.fail_next(2, StatusCode::INTERNAL_SERVER_ERROR);
+
+    pfm.scope(api.dispatch(
+        handler(),
+        message_update(
+            1,
+            private_message(1, 390_002, "/forex USD/IDR stats 2022-01-29..2022-02-06"),
+        ),
+    ))
+    .await
+    .unwrap();
+
+    assert_eq!(5, pfm.requests().len());
+    let text = api.sent_messages()[0].text().unwrap().to_string();
+    assert!(text.contains(", 3 trading days:"), "{}", text);
+    assert!(
+        text.contains("<i>2 days without a rate were left out.</i>"),
+        "{}",
+        text
+    );
+}
+
+#[tokio::test]
+async fn stats_need_most_days() {
+    let pfm = FakePfmApi::start().await;
+    let api = FakeBotApi::start().await;
+    pfm.fail_next(3, StatusCode::INTERNAL_SERVER_ERROR);
+
+    let ret = pfm
+        .scope(api.dispatch(
+            handler(),
+            message_update(
+                1,
+                private_message(1, 390_003, "/forex USD/IDR stats 2022-01-29..2022-02-06"),
+            ),
+        ))
+        .await;
+
+    assert!(ret.is_err());
+    assert_eq!(5, pfm.requests().len());
+    assert!(!api.sent_messages()[0].text().unwrap().contains("📊"));
+}
@@ -0,0 +1,102 @@
+//! reply sends the answer of a command, remembering which message answers which command.
+//!
+//! When a user edits a command message, the command runs again and its previous answer is edited in place,
+//! so fixing a typo doesn't leave the error and the result stacked in the chat.
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use teloxide::prelude::*;
+use teloxide::sugar::request::RequestReplyExt;
+use teloxide::types::{InlineKeyboardMarkup, MessageId, ParseMode};
+use teloxide::{ApiError, RequestError};
+
+use crate::deps::cache::Cache;
+
+static REPLIES: LazyLock<Cache> = LazyLock::new(|| Cache::new(MAX_TRACKED_REPLIES));
+
+const MAX_TRACKED_REPLIES: usize = 4096;
+
+// telegram lets users edit their messages for 48 hours
+const REPLY_TTL: Duration = Duration::from_secs(48 * 60 * 60);
+
+/// Answer of a command, sent with [`Reply::send`].
+pub(crate) struct Reply {
+    text: String,
+    parse_mode: Option<ParseMode>,
+    quote: bool,
+    keyboard: Option<InlineKeyboardMarkup>,
+}
+
+impl Reply {
+    pub(crate) fn new(text: impl Into<String>) -> Self {
+        Reply {
+            text: text.into(),
+            parse_mode: None,
+            quote: false,
+            keyboard: None,
+        }
+    }
+
+    pub(crate) fn html(mut self) -> Self {
+        self.parse_mode = Some(ParseMode::Html);
+        self
+    }
+
+    /// Reply to the command message, instead of only sending to its chat.
+    pub(crate) fn quote(mut self) -> Self {
+        self.quote = true;
+        self
+    }
+
+    pub(crate) fn keyboard(mut self, keyboard: InlineKeyboardMarkup) -> Self {
+        self.keyboard = Some(keyboard);
+        self
+    }
+
+    /// Send the answer of command `msg`, or edit the previous answer when `msg` is an edited command.
+    pub(crate) async fn send(self, bot: &Bot, msg: &Message) -> Result<(), RequestError> {
+        if msg.edit_date().is_some()
+            && let Some(reply_id) = tracked(msg)
+        {
+            let mut req = bot.edit_message_text(msg.chat.id, reply_id, &self.text);
+            req.parse_mode = self.parse_mode;
+            req.reply_markup = self.keyboard.clone();
+
+            match req.await {
+                Ok(_) | Err(RequestError::Api(ApiError::MessageNotModified)) => return Ok(()),
+                // deleted by someone, or not editable anymore
+                Err(err) => eprintln!(
+                    "[reply] cannot edit reply {} in chat {}, sending a new one: {}",
+                    reply_id, msg.chat.id, err
+                ),
+            }
+        }
+
+        let mut req = bot.send_message(msg.chat.id, self.text);
+        req.parse_mode = self.parse_mode;
+        if let Some(keyboard) = self.keyboard {
+            req = req.reply_markup(keyboard);
+        }
+        let sent = if self.quote {
+            req.reply_to(msg.id).await?
+        } else {
+            req.await?
+        };
+
+        REPLIES.insert(key(msg), sent.id.0.to_string(), REPLY_TTL);
+
+        Ok(())
+    }
+}
+
+/// Message answering command `msg`, while it can still be edited.
+pub(crate) fn tracked(msg: &Message) -> Option<MessageId> {
+    REPLIES
+        .get(&key(msg))
+        .and_then(|id| id.parse().ok())
+        .map(MessageId)
+}
+
+fn key(msg: &Message) -> String {
+    format!("{}:{}", msg.chat.id, msg.id)
+}
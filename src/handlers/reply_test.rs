@@ -0,0 +1,74 @@
+use crate::handler;
+use crate::testing::fake_bot::{
+    FakeBotApi, edited_message_update, message_update, private_message,
+};
+
+#[tokio::test]
+async fn edited_command_edits_its_reply() {
+    let api = FakeBotApi::start().await;
+
+    let ret = api
+        .dispatch(
+            handler(),
+            message_update(1, private_message(5, 360_001, "/help forexx")),
+        )
+        .await;
+    assert!(ret.is_err());
+
+    api.dispatch(
+        handler(),
+        edited_message_update(2, private_message(5, 360_001, "/help forex")),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(1, api.sent_messages().len());
+    let edits = api.calls_to("editMessageText");
+    assert_eq!(1, edits.len());
+    assert_eq!(Some(360_001), edits[0].chat_id());
+    // first message sent by the fake
+    assert_eq!(Some(10_000), edits[0].message_id());
+    assert!(edits[0].text().unwrap().starts_with("<b>/forex</b>"));
+    assert!(edits[0].body["reply_markup"]["inline_keyboard"].is_array());
+}
+
+#[tokio::test]
+async fn edited_answer_keeps_being_edited() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(5, 360_002, "/help forex")),
+    )
+    .await
+    .unwrap();
+    for (update_id, text) in [(2, "/help convertt"), (3, "/help convert")] {
+        let _ = api
+            .dispatch(
+                handler(),
+                edited_message_update(update_id, private_message(5, 360_002, text)),
+            )
+            .await;
+    }
+
+    let edits = api.calls_to("editMessageText");
+    assert_eq!(1, api.sent_messages().len());
+    assert_eq!(2, edits.len());
+    assert!(edits.iter().all(|edit| edit.message_id() == Some(10_000)));
+    assert!(edits[0].text().unwrap().contains("convertt"));
+    assert!(edits[1].text().unwrap().starts_with("<b>/convert</b>"));
+}
+
+#[tokio::test]
+async fn edits_of_unanswered_messages_are_ignored() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        edited_message_update(1, private_message(5, 360_003, "/help")),
+    )
+    .await
+    .unwrap();
+
+    assert!(api.calls().iter().all(|call| call.method == "getMe"));
+}
@@ -0,0 +1,149 @@
+use chrono::Utc;
+use teloxide::prelude::*;
+
+use crate::commands::Args;
+use crate::deps::storage::storage;
+use crate::error::HandlerError;
+use crate::handlers::reply::Reply;
+use crate::i18n::{Lang, Localized, lang_for};
+use crate::utils::time::{format_time, parse_timezone, timezone_for};
+
+pub(crate) async fn settings_handler(
+    bot: Bot,
+    msg: &Message,
+    args: Args,
+) -> Result<(), HandlerError> {
+    let parts: Vec<&str> = args.0.split_whitespace().collect();
+
+    match parts.as_slice() {
+        [] => show_settings(bot, msg).await,
+        ["lang", code] => set_lang(bot, msg, code).await,
+        ["broadcast", value] => set_broadcast(bot, msg, value).await,
+        ["tz" | "timezone", name] => set_timezone(bot, msg, name).await,
+        _ => Err(Localized::new("settings.usage").into()),
+    }
+}
+
+async fn show_settings(bot: Bot, msg: &Message) -> Result<(), HandlerError> {
+    let lang = lang_for(msg);
+    let settings = storage()
+        .chat_settings(msg.chat.id.0)
+        .map_err(HandlerError::StorageError)?;
+
+    let chat_lang = match settings.lang.as_deref().and_then(Lang::from_code) {
+        Some(chat_lang) => Localized::new("lang.name").render(chat_lang),
+        None => Localized::new("settings.not_set").render(lang),
+    };
+
+    let broadcast = if settings.broadcast {
+        Localized::new("settings.on")
+    } else {
+        Localized::new("settings.off")
+    };
+
+    let text = Localized::new("settings.current")
+        .arg("lang", chat_lang)
+        .arg("broadcast", broadcast.render(lang))
+        .arg("timezone", timezone_for(msg.chat.id.0).name())
+        .render(lang);
+
+    Reply::new(text).quote().send(&bot, msg).await?;
+
+    Ok(())
+}
+
+async fn set_lang(bot: Bot, msg: &Message, code: &str) -> Result<(), HandlerError> {
+    let Some(new_lang) = Lang::from_code(code) else {
+        let supported = Lang::ALL
+            .iter()
+            .map(|l| l.code())
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(Localized::new("settings.unknown_lang")
+            .arg("lang", code)
+            .arg("supported", supported)
+            .into());
+    };
+
+    ensure_can_change_settings(&bot, msg).await?;
+
+    storage()
+        .set_chat_lang(msg.chat.id.0, new_lang.code())
+        .map_err(HandlerError::StorageError)?;
+
+    let text = Localized::new("settings.lang_updated")
+        .arg("lang", Localized::new("lang.name").render(new_lang))
+        .render(new_lang);
+
+    Reply::new(text).quote().send(&bot, msg).await?;
+
+    Ok(())
+}
+
+async fn set_broadcast(bot: Bot, msg: &Message, value: &str) -> Result<(), HandlerError> {
+    let broadcast = match value.to_ascii_lowercase().as_str() {
+        "on" => true,
+        "off" => false,
+        _ => return Err(Localized::new("settings.usage").into()),
+    };
+
+    ensure_can_change_settings(&bot, msg).await?;
+
+    storage()
+        .set_chat_broadcast(msg.chat.id.0, broadcast)
+        .map_err(HandlerError::StorageError)?;
+
+    let key = if broadcast {
+        "settings.broadcast_on"
+    } else {
+        "settings.broadcast_off"
+    };
+
+    Reply::new(Localized::new(key).render(lang_for(msg)))
+        .quote()
+        .send(&bot, msg)
+        .await?;
+
+    Ok(())
+}
+
+async fn set_timezone(bot: Bot, msg: &Message, name: &str) -> Result<(), HandlerError> {
+    let Some(tz) = parse_timezone(name) else {
+        return Err(Localized::new("settings.unknown_timezone")
+            .arg("timezone", name)
+            .into());
+    };
+
+    ensure_can_change_settings(&bot, msg).await?;
+
+    storage()
+        .set_chat_timezone(msg.chat.id.0, tz.name())
+        .map_err(HandlerError::StorageError)?;
+
+    let text = Localized::new("settings.timezone_updated")
+        .arg("timezone", tz.name())
+        .arg("now", format_time(Utc::now(), tz))
+        .render(lang_for(msg));
+
+    Reply::new(text).quote().send(&bot, msg).await?;
+
+    Ok(())
+}
+
+/// Anyone may change settings of their private chat, only admins may change a group's.
+async fn ensure_can_change_settings(bot: &Bot, msg: &Message) -> Result<(), HandlerError> {
+    if msg.chat.is_private() {
+        return Ok(());
+    }
+
+    let Some(ref user) = msg.from else {
+        return Err(Localized::new("settings.admins_only").into());
+    };
+
+    let member = bot.get_chat_member(msg.chat.id, user.id).await?;
+    if !member.is_privileged() {
+        return Err(Localized::new("settings.admins_only").into());
+    }
+
+    Ok(())
+}
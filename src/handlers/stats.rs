@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use chrono::{Days, NaiveDate, Utc};
+use rust_decimal::Decimal;
+use teloxide::prelude::*;
+use teloxide::types::Chat;
+
+use crate::commands::Args;
+use crate::config::config;
+use crate::deps::storage::{CommandEvent, UsageRow, storage};
+use crate::error::HandlerError;
+use crate::handlers::reply::Reply;
+use crate::i18n::{Lang, Localized, format_number, lang_for, t};
+
+const TOP_COMMANDS: usize = 5;
+
+const COMPACTION_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+const SPARKLINE_BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub(crate) fn chat_type(chat: &Chat) -> &'static str {
+    if chat.is_private() {
+        "private"
+    } else if chat.is_supergroup() {
+        "supergroup"
+    } else if chat.is_group() {
+        "group"
+    } else {
+        "channel"
+    }
+}
+
+/// Store one usage event of a handled command. Failing to record never fails the command.
+pub(crate) fn record(
+    msg: &Message,
+    command: &'static str,
+    ret: &Result<(), HandlerError>,
+    latency: Duration,
+) {
+    let event = CommandEvent {
+        at: Utc::now(),
+        chat_id: msg.chat.id.0,
+        chat_type: chat_type(&msg.chat),
+        command,
+        outcome: match ret {
+            Ok(_) => "ok",
+            Err(err) => err.kind(),
+        },
+        latency,
+    };
+
+    if let Err(err) = storage().record_command(&event) {
+        eprintln!("[stats] {:#}", err);
+    }
+}
+
+pub(crate) async fn stats_handler(bot: Bot, msg: &Message, args: Args) -> Result<(), HandlerError> {
+    if !args.0.trim().is_empty() {
+        return Err(Localized::new("stats.usage").into());
+    }
+
+    let text = usage_report(Some(msg.chat.id.0), lang_for(msg))?;
+    Reply::new(text).quote().send(&bot, msg).await?;
+
+    Ok(())
+}
+
+/// Rendered usage of one chat, or of all chats when `chat_id` is empty.
+pub(crate) fn usage_report(chat_id: Option<i64>, lang: Lang) -> Result<String, HandlerError> {
+    let window_days = config().stats_window_days.max(1);
+    let today = Utc::now().date_naive();
+    let since = today - Days::new(window_days as u64 - 1);
+
+    let rows = storage()
+        .command_usage(chat_id, since)
+        .map_err(HandlerError::StorageError)?;
+    let header = match chat_id {
+        Some(_) => "stats.chat_header",
+        None => "stats.global_header",
+    };
+
+    Ok(UsageReport::new(&rows, today, window_days).render(lang, header))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CommandUsage {
+    pub command: String,
+    pub count: u64,
+    pub errors: u64,
+    pub latency_ms: u64,
+}
+
+/// Usage summed over a window of days ending today.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct UsageReport {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub total: u64,
+    pub errors: u64,
+
+    // most used first
+    pub commands: Vec<CommandUsage>,
+
+    // `HandlerError` variants, most frequent first
+    pub error_kinds: Vec<(String, u64)>,
+
+    // one count per day, oldest first
+    pub daily: Vec<u64>,
+}
+
+impl UsageReport {
+    pub(crate) fn new(rows: &[UsageRow], today: NaiveDate, window_days: u32) -> Self {
+        let from = today - Days::new(window_days.max(1) as u64 - 1);
+        let mut daily = vec![0; window_days.max(1) as usize];
+        let mut commands: HashMap<&str, CommandUsage> = HashMap::new();
+        let mut error_kinds: HashMap<&str, u64> = HashMap::new();
+
+        for row in rows
+            .iter()
+            .filter(|row| row.day >= from && row.day <= today)
+        {
+            daily[(row.day - from).num_days() as usize] += row.count;
+
+            let usage = commands
+                .entry(&row.command)
+                .or_insert_with(|| CommandUsage {
+                    command: row.command.clone(),
+                    count: 0,
+                    errors: 0,
+                    latency_ms: 0,
+                });
+            usage.count += row.count;
+            usage.latency_ms += row.latency_ms;
+
+            if row.outcome != "ok" {
+                usage.errors += row.count;
+                *error_kinds.entry(&row.outcome).or_default() += row.count;
+            }
+        }
+
+        let mut commands: Vec<_> = commands.into_values().collect();
+        commands.sort_by(|a, b| b.count.cmp(&a.count).then(a.command.cmp(&b.command)));
+
+        let mut error_kinds: Vec<_> = error_kinds
+            .into_iter()
+            .map(|(kind, count)| (kind.to_string(), count))
+            .collect();
+        error_kinds.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        UsageReport {
+            from,
+            to: today,
+            total: commands.iter().map(|usage| usage.count).sum(),
+            errors: commands.iter().map(|usage| usage.errors).sum(),
+            commands,
+            error_kinds,
+            daily,
+        }
+    }
+
+    pub(crate) fn render(&self, lang: Lang, header: &'static str) -> String {
+        let mut ret = Localized::plural(header, self.daily.len() as i64).render(lang);
+
+        if self.total == 0 {
+            ret.push('\n');
+            ret.push_str(t(lang, "stats.empty"));
+            return ret;
+        }
+
+        ret.push('\n');
+        ret.push_str(
+            &Localized::new("stats.summary")
+                .arg("total", format_number(lang, self.total.into(), 0))
+                .arg("errors", format_number(lang, self.errors.into(), 0))
+                .arg("rate", percentage(lang, self.errors, self.total))
+                .render(lang),
+        );
+
+        ret.push_str("\n\n");
+        ret.push_str(
+            &Localized::new("stats.activity")
+                .arg("from", self.from)
+                .arg("to", self.to)
+                .arg("sparkline", sparkline(&self.daily))
+                .render(lang),
+        );
+
+        ret.push_str("\n\n");
+        ret.push_str(t(lang, "stats.top_header"));
+        for (idx, usage) in self.commands.iter().take(TOP_COMMANDS).enumerate() {
+            ret.push('\n');
+            ret.push_str(
+                &Localized::new("stats.top_item")
+                    .arg("rank", idx + 1)
+                    .arg("command", &usage.command)
+                    .arg("count", format_number(lang, usage.count.into(), 0))
+                    .arg("rate", percentage(lang, usage.errors, usage.count))
+                    .arg(
+                        "latency",
+                        format_number(
+                            lang,
+                            Decimal::from(usage.latency_ms) / Decimal::from(usage.count),
+                            0,
+                        ),
+                    )
+                    .render(lang),
+            );
+        }
+
+        if !self.error_kinds.is_empty() {
+            ret.push_str("\n\n");
+            ret.push_str(t(lang, "stats.errors_header"));
+            for (kind, count) in &self.error_kinds {
+                ret.push_str(&format!(
+                    "\n- {}: {}",
+                    kind,
+                    format_number(lang, (*count).into(), 0)
+                ));
+            }
+        }
+
+        ret
+    }
+}
+
+fn percentage(lang: Lang, part: u64, total: u64) -> String {
+    if total == 0 {
+        return format!("{}%", format_number(lang, Decimal::ZERO, 1));
+    }
+
+    let value = Decimal::from(part) * Decimal::from(100) / Decimal::from(total);
+    format!("{}%", format_number(lang, value, 1))
+}
+
+/// One bar per value, scaled to the largest. Zero is always the lowest bar.
+pub(crate) fn sparkline(values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or_default();
+    let top = (SPARKLINE_BARS.len() - 1) as u64;
+
+    values
+        .iter()
+        .map(|&value| {
+            if max == 0 || value == 0 {
+                return SPARKLINE_BARS[0];
+            }
+
+            // non zero days never look empty
+            let level = (value * top).div_ceil(max).max(1);
+            SPARKLINE_BARS[level as usize]
+        })
+        .collect()
+}
+
+/// Periodically roll old events into daily counts and drop expired history.
+pub(crate) async fn compaction_job() {
+    let mut interval = tokio::time::interval(COMPACTION_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        let config = config();
+        let today = Utc::now().date_naive();
+        let events_before = today - Days::new(config.stats_retention_days as u64);
+        let daily_before = today - Days::new(config.stats_history_days as u64);
+
+        match storage().compact_command_usage(events_before, daily_before) {
+            Ok((0, 0)) => {}
+            Ok((rolled, dropped)) => println!(
+                "[stats] rolled up {} command events, dropped {} daily counts",
+                rolled, dropped
+            ),
+            Err(err) => eprintln!("[stats] {:#}", err),
+        }
+    }
+}
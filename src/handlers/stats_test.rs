@@ -0,0 +1,131 @@
+use chrono::NaiveDate;
+
+use crate::deps::storage::UsageRow;
+use crate::handler;
+use crate::handlers::stats::{CommandUsage, UsageReport, sparkline};
+use crate::i18n::{Lang, t};
+use crate::testing::fake_bot::{FakeBotApi, message_update, private_message};
+
+fn row(day: u32, command: &str, outcome: &str, count: u64, latency_ms: u64) -> UsageRow {
+    UsageRow {
+        day: NaiveDate::from_ymd_opt(2024, 3, day).unwrap(),
+        command: command.into(),
+        outcome: outcome.into(),
+        count,
+        latency_ms,
+    }
+}
+
+#[test]
+fn sparkline_scales_to_max() {
+    assert_eq!("▁▂▅█", sparkline(&[0, 1, 4, 8]));
+    assert_eq!("▁▁▁", sparkline(&[0, 0, 0]));
+    assert_eq!("▂█", sparkline(&[1, 1000]));
+    assert_eq!("", sparkline(&[]));
+}
+
+#[test]
+fn report_sums_window_only() {
+    let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+    let rows = vec![
+        // outside of the window
+        row(1, "forex", "ok", 100, 100),
+        row(8, "forex", "ok", 3, 300),
+        row(8, "forex", "ApiError", 1, 900),
+        row(10, "convert", "InvalidArguments", 2, 20),
+        row(10, "forex", "ok", 2, 200),
+    ];
+
+    let report = UsageReport::new(&rows, today, 3);
+
+    assert_eq!(NaiveDate::from_ymd_opt(2024, 3, 8).unwrap(), report.from);
+    assert_eq!(vec![4, 0, 4], report.daily);
+    assert_eq!(8, report.total);
+    assert_eq!(3, report.errors);
+    assert_eq!(
+        vec![
+            CommandUsage {
+                command: "forex".into(),
+                count: 6,
+                errors: 1,
+                latency_ms: 1400,
+            },
+            CommandUsage {
+                command: "convert".into(),
+                count: 2,
+                errors: 2,
+                latency_ms: 20,
+            },
+        ],
+        report.commands
+    );
+    assert_eq!(
+        vec![
+            ("InvalidArguments".to_string(), 2),
+            ("ApiError".to_string(), 1)
+        ],
+        report.error_kinds
+    );
+}
+
+#[test]
+fn report_render() {
+    let today = NaiveDate::from_ymd_opt(2024, 3, 10).unwrap();
+    let rows = vec![
+        row(9, "forex", "ok", 3, 300),
+        row(10, "forex", "ApiError", 1, 1700),
+    ];
+
+    let text = UsageReport::new(&rows, today, 2).render(Lang::En, "stats.chat_header");
+
+    assert_eq!(
+        "Usage of this chat in the last 2 days:
+Commands: 4, errors: 1 (25.0%)
+
+Daily activity, 2024-03-09 to 2024-03-10:
+█▄
+
+Top commands:
+1. /forex: 4, 25.0% errors, avg 500 ms
+
+Errors:
+- ApiError: 1",
+        text
+    );
+
+    let text = UsageReport::new(&[], today, 1).render(Lang::Id, "stats.global_header");
+    assert_eq!(
+        "Penggunaan di semua chat dalam 1 hari terakhir:\nBelum ada perintah yang tercatat.",
+        text
+    );
+}
+
+#[tokio::test]
+async fn arguments_are_rejected() {
+    let api = FakeBotApi::start().await;
+
+    let ret = api
+        .dispatch(
+            handler(),
+            message_update(1, private_message(1, 400_001, "/stats global")),
+        )
+        .await;
+
+    assert!(ret.is_err());
+    let text = api.sent_messages()[0].text().unwrap().to_string();
+    assert!(text.starts_with(t(Lang::En, "stats.usage")), "{}", text);
+}
+
+#[tokio::test]
+async fn report_quotes_the_command() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(4, 400_002, "/stats")),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(Some(4), api.sent_messages()[0].reply_to());
+}
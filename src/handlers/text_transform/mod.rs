@@ -0,0 +1,142 @@
+//! text_transform holds the commands rewriting a text for fun, like /spongebob or /uwu.
+//!
+//! Every command takes the text after it, or the text of the replied message, answers the transformed text
+//! and deletes the caller's message. Transforms get their randomness from the caller, so tests can seed it.
+use rand::RngCore;
+use teloxide::prelude::*;
+use teloxide::sugar::request::RequestReplyExt;
+
+use crate::commands::Args;
+use crate::error::HandlerError;
+use crate::i18n::Localized;
+
+mod transforms;
+
+pub(crate) use transforms::{Clap, Leet, Reverse, SmallCaps, SpongeBob, Uwu, Vaporwave, Zalgo};
+
+// telegram rejects longer messages
+const MAX_MESSAGE_CHARS: usize = 4096;
+
+static TRANSFORMS: &[&dyn TextTransform] = &[
+    &SpongeBob, &Uwu, &Zalgo, &Reverse, &Leet, &SmallCaps, &Vaporwave, &Clap,
+];
+
+pub(crate) trait TextTransform: Sync {
+    /// Command name without slash.
+    fn name(&self) -> &'static str;
+
+    fn transform(&self, text: &str, rng: &mut dyn RngCore) -> String;
+}
+
+/// Transform of a command, none for other commands.
+pub(crate) fn by_name(name: &str) -> Option<&'static dyn TextTransform> {
+    TRANSFORMS
+        .iter()
+        .find(|transform| transform.name() == name)
+        .copied()
+}
+
+pub(crate) fn transforms() -> &'static [&'static dyn TextTransform] {
+    TRANSFORMS
+}
+
+pub(crate) async fn transform_handler(
+    bot: Bot,
+    msg: &Message,
+    args: Args,
+    transform: &dyn TextTransform,
+) -> Result<(), HandlerError> {
+    // Check if the command is a reply to another message
+    if let Some(reply_to_msg) = msg.reply_to_message() {
+        // Get text from the replied message (try text first, then caption)
+        let text_to_convert = reply_to_msg
+            .text()
+            .or_else(|| reply_to_msg.caption())
+            .ok_or_else(|| Localized::new("transform.reply_without_text"))?;
+
+        let transformed = apply(transform, text_to_convert)?;
+
+        // Reply to the message that was replied to
+        bot.send_message(msg.chat.id, transformed)
+            .reply_to(reply_to_msg.id)
+            .await?;
+    } else {
+        // If not a reply, check if args were provided
+        let text_to_convert = args.0.trim();
+
+        if text_to_convert.is_empty() {
+            return Err(Localized::new("transform.no_text").into());
+        }
+
+        let transformed = apply(transform, text_to_convert)?;
+
+        // Send the transformed text without replying to the command
+        bot.send_message(msg.chat.id, transformed).await?;
+    }
+
+    // Delete the caller's message (the command message)
+    // Ignore errors if bot lacks permission to delete messages
+    let ret = bot.delete_message(msg.chat.id, msg.id).await;
+    if let Err(err) = ret {
+        eprintln!("Cannot delete message: {}", err);
+    }
+
+    Ok(())
+}
+
+fn apply(transform: &dyn TextTransform, text: &str) -> Result<String, Localized> {
+    let transformed = transform.transform(text, &mut rand::thread_rng());
+    if transformed.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(Localized::new("transform.too_long"));
+    }
+
+    Ok(transformed)
+}
+
+/// Split text into what readers see as one character: a base with its combining marks, variation selectors
+/// and skin tones, emoji joined with zero width joiners, and flags of two regional indicators.
+pub(crate) fn clusters(text: &str) -> Vec<&str> {
+    let mut ret = vec![];
+    let mut start = 0;
+    let mut prev: Option<char> = None;
+    // regional indicators in the current cluster, a flag takes two
+    let mut regional = 0;
+
+    for (idx, ch) in text.char_indices() {
+        let joins = match prev {
+            None => false,
+            Some(prev) => is_extend(ch) || prev == ZWJ || (is_regional(ch) && regional % 2 == 1),
+        };
+
+        if !joins && idx > 0 {
+            ret.push(&text[start..idx]);
+            start = idx;
+            regional = 0;
+        }
+        if is_regional(ch) {
+            regional += 1;
+        }
+        prev = Some(ch);
+    }
+
+    if start < text.len() {
+        ret.push(&text[start..]);
+    }
+
+    ret
+}
+
+const ZWJ: char = '\u{200D}';
+
+fn is_extend(ch: char) -> bool {
+    matches!(ch as u32,
+        // combining diacritical marks, their extensions and the ones for symbols
+        0x0300..=0x036F | 0x1AB0..=0x1AFF | 0x1DC0..=0x1DFF | 0x20D0..=0x20FF | 0xFE20..=0xFE2F
+        // variation selectors, skin tones and emoji tags
+        | 0xFE00..=0xFE0F | 0x1F3FB..=0x1F3FF | 0xE0020..=0xE007F
+        | 0x200D)
+}
+
+fn is_regional(ch: char) -> bool {
+    ('\u{1F1E6}'..='\u{1F1FF}').contains(&ch)
+}
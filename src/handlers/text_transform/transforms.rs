@@ -0,0 +1,234 @@
+use rand::{Rng, RngCore};
+
+use super::{TextTransform, clusters};
+
+/// sPoNgEbOb mocking case: alternates the case of letters, starting randomly.
+pub(crate) struct SpongeBob;
+
+impl TextTransform for SpongeBob {
+    fn name(&self) -> &'static str {
+        "spongebob"
+    }
+
+    fn transform(&self, text: &str, rng: &mut dyn RngCore) -> String {
+        let mut ret = String::with_capacity(text.len());
+        let mut lowercase_next = rng.r#gen::<bool>();
+
+        for ch in text.chars() {
+            // letters without case, like CJK, don't take a turn
+            if !ch.is_lowercase() && !ch.is_uppercase() {
+                ret.push(ch);
+                continue;
+            }
+
+            if lowercase_next {
+                ret.extend(ch.to_lowercase());
+            } else {
+                ret.extend(ch.to_uppercase());
+            }
+            lowercase_next = !lowercase_next;
+        }
+
+        ret
+    }
+}
+
+// chance of a word starting with a stutter, h-hewwo
+const STUTTER_CHANCE: f64 = 0.15;
+
+static UWU_FACES: &[&str] = &["uwu", "owo", ">w<", "^w^", ":3", "(・`ω´・)"];
+
+/// Cutesy speech: r and l become w, n before a vowel becomes ny, stutters and a face at the end.
+pub(crate) struct Uwu;
+
+impl TextTransform for Uwu {
+    fn name(&self) -> &'static str {
+        "uwu"
+    }
+
+    fn transform(&self, text: &str, rng: &mut dyn RngCore) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut ret = String::with_capacity(text.len() + 8);
+
+        for (idx, &ch) in chars.iter().enumerate() {
+            let prev = idx.checked_sub(1).map(|prev| chars[prev]);
+            let next = chars.get(idx + 1).copied();
+
+            let uwu = match ch {
+                'r' | 'l' => "w".to_string(),
+                'R' | 'L' => "W".to_string(),
+                'n' | 'N' if next.is_some_and(|next| "aeiouAEIOU".contains(next)) => {
+                    let shouting = ch == 'N' && next.is_some_and(char::is_uppercase);
+                    format!("{}{}", ch, if shouting { 'Y' } else { 'y' })
+                }
+                _ => ch.to_string(),
+            };
+
+            let word_start = ch.is_alphabetic() && !prev.is_some_and(char::is_alphabetic);
+            if word_start && rng.gen_bool(STUTTER_CHANCE) {
+                ret.extend(uwu.chars().next());
+                ret.push('-');
+            }
+            ret.push_str(&uwu);
+        }
+
+        let face = UWU_FACES[rng.gen_range(0..UWU_FACES.len())];
+        format!("{} {}", ret.trim_end(), face)
+    }
+}
+
+// combining marks stacked above, through and below letters
+const ZALGO_UP: std::ops::RangeInclusive<u32> = 0x0300..=0x0315;
+const ZALGO_MID: std::ops::RangeInclusive<u32> = 0x0334..=0x0338;
+const ZALGO_DOWN: std::ops::RangeInclusive<u32> = 0x0316..=0x0333;
+
+// most marks of each kind on one character, keeps the text readable and under telegram's limit
+const ZALGO_MAX_MARKS: usize = 3;
+
+/// Glitchy text with combining marks piled on every character.
+pub(crate) struct Zalgo;
+
+impl TextTransform for Zalgo {
+    fn name(&self) -> &'static str {
+        "zalgo"
+    }
+
+    fn transform(&self, text: &str, rng: &mut dyn RngCore) -> String {
+        let mut ret = String::with_capacity(text.len() * 4);
+
+        for cluster in clusters(text) {
+            ret.push_str(cluster);
+            if cluster.chars().all(char::is_whitespace) {
+                continue;
+            }
+
+            for (marks, max) in [
+                (ZALGO_UP, ZALGO_MAX_MARKS),
+                (ZALGO_MID, 1),
+                (ZALGO_DOWN, ZALGO_MAX_MARKS),
+            ] {
+                for _ in 0..rng.gen_range(0..=max) {
+                    ret.extend(char::from_u32(rng.gen_range(marks.clone())));
+                }
+            }
+        }
+
+        ret
+    }
+}
+
+/// Text backwards, keeping accents and emoji whole.
+pub(crate) struct Reverse;
+
+impl TextTransform for Reverse {
+    fn name(&self) -> &'static str {
+        "reverse"
+    }
+
+    fn transform(&self, text: &str, _rng: &mut dyn RngCore) -> String {
+        clusters(text).into_iter().rev().collect()
+    }
+}
+
+/// 1337 5p34k, digits for look-alike letters.
+pub(crate) struct Leet;
+
+impl TextTransform for Leet {
+    fn name(&self) -> &'static str {
+        "leet"
+    }
+
+    fn transform(&self, text: &str, _rng: &mut dyn RngCore) -> String {
+        clusters(text)
+            .into_iter()
+            .map(|cluster| {
+                let digit = match lowercase_letter(cluster) {
+                    Some('a') => '4',
+                    Some('b') => '8',
+                    Some('e') => '3',
+                    Some('g') => '9',
+                    Some('i' | 'l') => '1',
+                    Some('o') => '0',
+                    Some('s') => '5',
+                    Some('t') => '7',
+                    _ => return cluster.to_string(),
+                };
+                digit.to_string()
+            })
+            .collect()
+    }
+}
+
+// small capitals of a to z, q and x have none so look-alikes are used
+const SMALL_CAPS: [char; 26] = [
+    'ᴀ', 'ʙ', 'ᴄ', 'ᴅ', 'ᴇ', 'ꜰ', 'ɢ', 'ʜ', 'ɪ', 'ᴊ', 'ᴋ', 'ʟ', 'ᴍ', 'ɴ', 'ᴏ', 'ᴘ', 'ǫ', 'ʀ', 'ꜱ',
+    'ᴛ', 'ᴜ', 'ᴠ', 'ᴡ', 'x', 'ʏ', 'ᴢ',
+];
+
+/// Latin letters as small capitals, ꜱᴍᴀʟʟ ᴄᴀᴘꜱ.
+pub(crate) struct SmallCaps;
+
+impl TextTransform for SmallCaps {
+    fn name(&self) -> &'static str {
+        "smallcaps"
+    }
+
+    fn transform(&self, text: &str, _rng: &mut dyn RngCore) -> String {
+        clusters(text)
+            .into_iter()
+            .map(|cluster| match lowercase_letter(cluster) {
+                Some(ch @ 'a'..='z') => SMALL_CAPS[(ch as u8 - b'a') as usize].to_string(),
+                _ => cluster.to_string(),
+            })
+            .collect()
+    }
+}
+
+// lowercase of a cluster that is one character, none when it carries marks, e.g. é or e with U+0301 stay whole
+fn lowercase_letter(cluster: &str) -> Option<char> {
+    let mut chars = cluster.chars();
+    let (Some(ch), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+
+    let mut lower = ch.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(lower), None) => Some(lower),
+        _ => None,
+    }
+}
+
+// distance from printable ASCII to its fullwidth form, ! is U+FF01
+const FULLWIDTH_OFFSET: u32 = 0xFEE0;
+
+/// ＡＥＳＴＨＥＴＩＣ fullwidth text.
+pub(crate) struct Vaporwave;
+
+impl TextTransform for Vaporwave {
+    fn name(&self) -> &'static str {
+        "vaporwave"
+    }
+
+    fn transform(&self, text: &str, _rng: &mut dyn RngCore) -> String {
+        text.chars()
+            .map(|ch| match ch {
+                ' ' => '\u{3000}',
+                '!'..='~' => char::from_u32(ch as u32 + FULLWIDTH_OFFSET).unwrap_or(ch),
+                _ => ch,
+            })
+            .collect()
+    }
+}
+
+/// Words 👏 between 👏 claps.
+pub(crate) struct Clap;
+
+impl TextTransform for Clap {
+    fn name(&self) -> &'static str {
+        "clap"
+    }
+
+    fn transform(&self, text: &str, _rng: &mut dyn RngCore) -> String {
+        text.split_whitespace().collect::<Vec<_>>().join(" 👏 ")
+    }
+}
@@ -0,0 +1,147 @@
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::handler;
+use crate::handlers::help::page_text;
+use crate::handlers::text_transform::{
+    Clap, Leet, Reverse, SmallCaps, SpongeBob, TextTransform, Uwu, Vaporwave, Zalgo, by_name,
+    clusters,
+};
+use crate::i18n::Lang;
+use crate::testing::fake_bot::{FakeBotApi, message_update, private_message};
+
+fn run(transform: &dyn TextTransform, text: &str) -> String {
+    transform.transform(text, &mut StdRng::seed_from_u64(7))
+}
+
+#[test]
+fn clusters_keep_marks_and_emoji_whole() {
+    assert_eq!(vec!["a", "b", "c"], clusters("abc"));
+    assert_eq!(vec!["e\u{301}", "!"], clusters("e\u{301}!"));
+    assert_eq!(vec!["👍🏽", " ", "🇮🇩", "🇯🇵"], clusters("👍🏽 🇮🇩🇯🇵"));
+    assert_eq!(vec!["👩‍💻"], clusters("👩‍💻"));
+    assert!(clusters("").is_empty());
+}
+
+#[test]
+fn same_seed_same_output() {
+    for transform in [&SpongeBob as &dyn TextTransform, &Uwu, &Zalgo] {
+        assert_eq!(
+            run(transform, "hello lovely world"),
+            run(transform, "hello lovely world"),
+            "{}",
+            transform.name()
+        );
+    }
+}
+
+#[test]
+fn spongebob_alternates_cased_letters() {
+    let ret = run(&SpongeBob, "ábc 日本 déf");
+
+    assert_eq!("ábc 日本 déf", ret.to_lowercase());
+    // cased letters alternate, CJK doesn't take a turn
+    let cased: Vec<bool> = ret
+        .chars()
+        .filter(|ch| ch.is_alphabetic() && (ch.is_lowercase() || ch.is_uppercase()))
+        .map(char::is_uppercase)
+        .collect();
+    assert!(cased.windows(2).all(|pair| pair[0] != pair[1]), "{}", ret);
+}
+
+#[test]
+fn uwu_speech() {
+    let ret = run(&Uwu, "hello world, no really");
+
+    assert!(ret.contains("wowwd"), "{}", ret);
+    assert!(ret.contains("nyo"), "{}", ret);
+    assert!(!ret.contains('r') && !ret.contains('l'), "{}", ret);
+}
+
+#[test]
+fn zalgo_only_adds_marks() {
+    let ret = run(&Zalgo, "hi there");
+
+    let stripped: String = ret
+        .chars()
+        .filter(|ch| !('\u{300}'..='\u{36F}').contains(ch))
+        .collect();
+    assert_eq!("hi there", stripped);
+    assert!(ret.chars().count() > "hi there".len());
+}
+
+#[test]
+fn deterministic_transforms() {
+    assert_eq!("🇮🇩 ,e\u{301}tuor", run(&Reverse, "route\u{301}, 🇮🇩"));
+    assert_eq!("1337 5p34k", run(&Leet, "LEET speak"));
+    assert_eq!("ꜱᴍᴀʟʟ ᴄᴀᴘꜱ, ʏᴇᴀʜ", run(&SmallCaps, "Small caps, yeah"));
+    assert_eq!(
+        "ａｅｓｔｈｅｔｉｃ　１９９５！　é",
+        run(&Vaporwave, "aesthetic 1995! é")
+    );
+    assert_eq!("this 👏 is 👏 it", run(&Clap, "  this is\n it "));
+}
+
+#[test]
+fn letters_with_marks_stay_whole() {
+    // precomposed and combining accents alike, the letter isn't swapped from under its mark
+    assert_eq!(
+        "ᴄᴀꜰé ᴄᴀꜰE\u{301} ÅɴɢꜱᴛʀÖᴍ",
+        run(&SmallCaps, "Café CAFE\u{301} ÅNGSTRÖM")
+    );
+    assert_eq!("C4Fé 8Ë73 ДӨМ", run(&Leet, "CAFé BË7E ДӨМ"));
+}
+
+#[test]
+fn transforms_are_found_by_command_name() {
+    assert_eq!("smallcaps", by_name("smallcaps").unwrap().name());
+    assert!(by_name("forex").is_none());
+}
+
+#[tokio::test]
+async fn transform_commands_share_spongebob_behaviour() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(9, 350_001, "/vaporwave hi")),
+    )
+    .await
+    .unwrap();
+
+    let sent = api.sent_messages();
+    assert_eq!(Some("ｈｉ"), sent[0].text());
+    assert_eq!(None, sent[0].reply_to());
+    assert_eq!(vec![9], api.deleted());
+
+    let ret = api
+        .dispatch(
+            handler(),
+            message_update(2, private_message(10, 350_001, "/clap")),
+        )
+        .await;
+    assert!(ret.is_err());
+    assert!(
+        api.sent_messages()[1]
+            .text()
+            .unwrap()
+            .starts_with("No text provided.")
+    );
+}
+
+#[test]
+fn transforms_share_a_help_page() {
+    let page = page_text(Lang::En, "leet").unwrap();
+
+    assert!(
+        page.starts_with("<b>/leet</b> — Wr173 73x7 1n l337"),
+        "{}",
+        page
+    );
+    assert!(
+        page.contains("<code>/leet rust is easy</code> answers like ru57 15 345y"),
+        "{}",
+        page
+    );
+    assert!(page.contains("/spongebob, /uwu, /zalgo"), "{}", page);
+}
@@ -0,0 +1,466 @@
+pub(super) static MESSAGES: &[(&str, &str)] = &[
+    ("lang.name", "English"),
+    // common
+    ("common.coming_soon", "Coming soon...!"),
+    // errors
+    ("error.ref", "Ref: {id}"),
+    (
+        "error.telegram",
+        "Telegram did not accept my reply, please try again.",
+    ),
+    (
+        "error.network",
+        "I couldn't reach the rates service right now, please try again in a moment.",
+    ),
+    (
+        "error.api",
+        "The rates service couldn't answer this request, please try again later.",
+    ),
+    (
+        "error.storage",
+        "Something went wrong on my side, please try again later.",
+    ),
+    (
+        "error.upstream_unavailable",
+        "The rates service is unavailable at the moment, please try again in a few minutes.",
+    ),
+    // help
+    (
+        "help.header",
+        "This is Teknologi Umum Bot written in Rust supporting these commands:",
+    ),
+    ("cmd.help.description", "Show this help message, or details of a command"),
+    (
+        "cmd.forex.description",
+        "Fetch prices of some moneys.
+Arguments:
+- No arguments: Give list of author chosen rates.
+- Forex pair:
+  - USD/IDR (pair of currency, case insensitive)
+  - USD/IDR 2022-02-02 (YYYY-MM-DD, date is optional)
+- Base rates:
+  - USD (currency as a base, case insensitive)
+  - USD 2022-02-02 (YYYY-MM-DD, date is optional)
+  - USD EUR,JPY,IDR (only these quotes)
+  - USD strength (strongest first, or az for alphabetical)
+- Stats of a pair:
+  - USD/IDR stats 30d (or 4w, 1m, 2024-01-01..2024-01-31)",
+    ),
+    (
+        "cmd.convert.description",
+        "Convert between 2 currencies.
+Arguments:
+- <FROM_CODE> <FROM_AMOUNT>; <TO_CODE>
+- <FROM_CODE> <FROM_AMOUNT>; <TO_CODE>; <DATE> (optional date in YYYY-MM-DD format)
+- Example: USD 50,000; IDR
+- Example with date: USD 50,000; IDR; 2022-02-02
+- Semicolon separated.",
+    ),
+    (
+        "cmd.pm.description",
+        "Fetch Precious Metals(Gold & Silver) prices in multiple fiat currencies.
+Optional params:
+- Date of rates: YYYY-MM-DD",
+    ),
+    (
+        "cmd.zakat.description",
+        "Fetch Zakat information, such current nishab in Gold and Silver.
+Optional params:
+- Amount: your current holding in fiat, and bot will calculate if you reach nishab. E.g. IDR 1,000,000,000.02
+- Date of start: Date of you reach nishab, bot will calculate the end of the year in hijri calendar mapped into Gregory calendar.",
+    ),
+    (
+        "cmd.stock.description",
+        "Fetch information of a stock. Default to author picks.
+Optional params:
+- Stock ticker: e.g. BBCA",
+    ),
+    ("cmd.remindme.description", "Remind me."),
+    ("cmd.cpi.description", "Consumer Price Index data"),
+    ("cmd.spongebob.description", "sPoNgEbOb"),
+    ("cmd.uwu.description", "Make text cute, uwu"),
+    ("cmd.zalgo.description", "Glitch text with stacked marks"),
+    ("cmd.reverse.description", "Write text backwards"),
+    ("cmd.leet.description", "Wr173 73x7 1n l337"),
+    ("cmd.smallcaps.description", "Write text in small caps"),
+    ("cmd.vaporwave.description", "Write text in fullwidth vaporwave letters"),
+    ("cmd.clap.description", "Put claps between words"),
+    (
+        "cmd.settings.description",
+        "Show or change settings of this chat.
+Arguments:
+- No arguments: show current settings.
+- lang <id|en>: language of bot replies.
+- broadcast <on|off>: receive announcements from bot admins.
+- tz <timezone>: timezone of times in replies, WIB, WITA, WIT or a name like Asia/Makassar.",
+    ),
+    ("cmd.stats.description", "Show command usage of this chat: top commands, error rates and daily activity."),
+    (
+        "cmd.admin.description",
+        "Bot operator tools: stats, broadcast, bans, reload and cache",
+    ),
+    (
+        "help.coming_soon",
+        "Coming soon: {commands}",
+    ),
+    (
+        "help.footer",
+        "Tap a command for its details, or send /help &lt;command&gt;, e.g. /help convert.",
+    ),
+    ("help.aliases", "Also {aliases}"),
+    ("help.usage", "Usage"),
+    ("help.back", "« All commands"),
+    (
+        "help.unknown_command",
+        "There's no /{command} command, send /help for the list.",
+    ),
+    (
+        "help.page.help",
+        "<b>Usage</b>
+<code>/help</code>
+<code>/help &lt;command&gt;</code>
+
+<b>Arguments</b>
+- <i>command</i>: name of a command, with or without the slash.
+
+<b>Examples</b>
+<code>/help</code> lists every command.
+<code>/help forex</code> shows this page for /forex.",
+    ),
+    (
+        "help.page.forex",
+        "<b>Arguments</b>
+- <i>from</i>, <i>to</i>, <i>base</i>: currency code, name or symbol, e.g. USD, yen, rupiah, emas, BTC.
+- <i>quotes</i>: comma separated currencies to show, e.g. EUR,JPY,IDR. Popular ones first, with buttons to page through the rest, when left out.
+- <i>date</i>: day of historical rates, e.g. 2022-02-02, 17/10/2026, 17 Oct 2026, yesterday, -7d, last friday or 2 weeks ago. Weekends show the Friday before, latest rates when left out.
+- <i>az</i>, <i>strength</i>: order of base rates, alphabetical or strongest against the base first. Alphabetical when left out.
+- <i>range</i>: days of stats, 30d, 4w, 1m or two dates like 2024-01-01..2024-01-31, at most 31 days. The last 30 days when left out.
+
+<b>Examples</b>
+<code>/forex</code> shows a watchlist of popular pairs.
+<code>/forex USD/IDR</code> shows one pair.
+<code>/forex yen/rupiah 2022-02-02</code> shows a pair on a past day.
+<code>/forex USD/IDR 2 weeks ago</code> shows a pair two weeks back.
+<code>/forex IDR</code> shows rates of popular currencies against IDR, with pages of the rest.
+<code>/forex USD EUR,JPY,SGD strength</code> shows three rates against USD, strongest first.
+<code>/forex USD/IDR stats 30d</code> shows open, close, low, high, average, change and daily volatility of the last 30 days.
+
+<b>Related</b>
+/convert",
+    ),
+    (
+        "help.page.convert",
+        "<b>Arguments</b>
+- <i>from</i>, <i>to</i>: currency code, name or symbol, e.g. USD, dollar, ringgit, BTC.
+- <i>amount</i>: before or after <i>from</i>, commas for thousands and a dot for decimals, e.g. 50,000.25.
+- <i>date</i>: day of historical rates, e.g. 2022-02-02, 17/10/2026, 17 Oct 2026, yesterday, -7d, last friday or 2 weeks ago. Weekends show the Friday before, latest rates when left out.
+
+<b>Examples</b>
+<code>/convert</code> converts 1 USD to IDR.
+<code>/convert USD 50,000; IDR</code>
+<code>/convert 100 bucks; ringgit; 2022-02-02</code>
+
+<b>Related</b>
+/forex",
+    ),
+    (
+        "help.page.transform",
+        "<b>Usage</b>
+<code>/{command} &lt;text&gt;</code>
+Or reply to a message with <code>/{command}</code>.
+
+<b>Arguments</b>
+- <i>text</i>: text to transform, the replied message when left out.
+
+The command message is deleted afterwards.
+
+<b>Example</b>
+<code>/{command} {input}</code> answers like {output}
+
+<b>Related</b>
+{related}",
+    ),
+    ("help.transform_example", "rust is easy"),
+    (
+        "help.page.settings",
+        "<b>Usage</b>
+<code>/settings</code>
+<code>/settings lang &lt;id|en&gt;</code>
+<code>/settings broadcast &lt;on|off&gt;</code>
+<code>/settings tz &lt;timezone&gt;</code>
+
+<b>Arguments</b>
+- <i>lang</i>: language of bot replies in this chat.
+- <i>broadcast</i>: whether this chat receives announcements from bot admins.
+- <i>tz</i>: timezone of times in replies, WIB, WITA, WIT or a name like Asia/Makassar.
+
+Only group admins can change settings of a group.
+
+<b>Examples</b>
+<code>/settings lang id</code>
+<code>/settings tz WITA</code>
+
+<b>Related</b>
+/stats",
+    ),
+    (
+        "help.page.stats",
+        "<b>Usage</b>
+<code>/stats</code>
+
+Shows the most used commands of this chat, their error rates and daily activity.
+
+<b>Related</b>
+/settings",
+    ),
+    // forex
+    ("forex.pair_format", "Forex pair must be in format XXX/YYY"),
+    (
+        "forex.base_format",
+        "Base currency must be in format XXX, case insensitive",
+    ),
+    (
+        "forex.quote_format",
+        "Quote {quote} must be a currency code or name, e.g. \"USD EUR,JPY,IDR\"",
+    ),
+    (
+        "forex.too_many_quotes",
+        "At most {max} currencies can be listed, send /forex <base> to page through all of them.",
+    ),
+    ("forex.empty_data", "Empty forex data"),
+    ("forex.item_error", "error: {error}"),
+    ("forex.no_data", "no data"),
+    ("forex.watchlist", "Forex data on {date}:{content}"),
+    ("forex.api_error", "forex api error: {error}"),
+    ("forex.invalid_response", "invalid response: empty data"),
+    ("forex.no_data_returned", "no data returned"),
+    ("forex.single_pair", "{pair} on {date} is:\n<b>{rate}</b>"),
+    (
+        "forex.base_rates.one",
+        "Rates with base {base} on {date} ({count} currency):",
+    ),
+    (
+        "forex.base_rates.other",
+        "Rates with base {base} on {date} ({count} currencies):",
+    ),
+    // convert
+    (
+        "convert.amount_format",
+        "Amount must be a number with optional commas and decimal point. Got: {amount}",
+    ),
+    ("convert.result", "Conversion on {date}:\n<b>{from} = {to}</b>"),
+    // money
+    ("money.invalid_amount", "Invalid amount \"{amount}\"."),
+    ("money.compact.thousands", "{value}K"),
+    ("money.compact.millions", "{value}M"),
+    ("money.compact.billions", "{value}B"),
+    ("money.compact.trillions", "{value}T"),
+    // currencies
+    ("currency.unknown", "Unknown currency {code}."),
+    (
+        "currency.did_you_mean",
+        "Unknown currency {code}, did you mean {suggestions}?",
+    ),
+    (
+        "currency.ambiguous",
+        "\"{input}\" may mean {alternatives}, please use one of the codes.",
+    ),
+    (
+        "currency.read_as",
+        "<i>Read \"{input}\" as {code}, it may also mean {alternatives}.</i>",
+    ),
+    // syntax
+    ("syntax.usage", "Usage: {usage}"),
+    ("syntax.expected", "Expected {expected} here, not \"{got}\"."),
+    ("syntax.missing", "Missing {expected} at the end."),
+    ("syntax.unexpected", "Didn't expect \"{got}\" here."),
+    (
+        "syntax.currency",
+        "\"{value}\" isn't a currency code or name, e.g. USD, yen, rupiah or emas.",
+    ),
+    ("syntax.arg.from", "from"),
+    ("syntax.arg.to", "to"),
+    ("syntax.arg.base", "base"),
+    ("syntax.arg.quotes", "quotes"),
+    ("syntax.arg.date", "date"),
+    ("syntax.arg.range", "range"),
+    ("syntax.arg.amount", "amount"),
+    // rate sources
+    (
+        "date.invalid",
+        "Invalid date {date}, e.g. 2022-02-02, 17/10/2026, 17 Oct 2026, yesterday, -7d, last friday or 2 weeks ago.",
+    ),
+    (
+        "date.future",
+        "{date} is in the future, historical rates go up to today, {today}.",
+    ),
+    (
+        "date.weekend",
+        "<i>{date} is a weekend, showing rates of the last trading day, {trading}.</i>",
+    ),
+    ("rate_buttons.swap", "⇄ Swap"),
+    ("rate_buttons.prev_day", "◀ Previous day"),
+    ("rate_buttons.next_day", "Next day ▶"),
+    ("rate_buttons.refresh", "🔄 Refresh"),
+    ("rate_buttons.change_button", "📈 {days}d"),
+    (
+        "rate_buttons.change",
+        "📈 Change over {days} days: <b>{change}</b>, from {rate} on {date}",
+    ),
+    (
+        "rate_buttons.invalid",
+        "This button is no longer valid, please send the command again.",
+    ),
+    (
+        "rate_buttons.not_owner",
+        "Only the person who sent the command can use these buttons.",
+    ),
+    (
+        "rate_stats.result",
+        "📊 <b>{pair}</b> from {start} to {end}, {days} trading days:
+Open: {open}
+Close: {close}
+Low: {low} on {low_date}
+High: {high} on {high_date}
+Average: {average}
+Change: <b>{change}</b>
+Daily volatility: {volatility}",
+    ),
+    (
+        "rate_stats.format",
+        "Stats take a range like 30d, 4w, 1m or 2024-01-01..2024-01-31, e.g. \"/forex USD/IDR stats 30d\".",
+    ),
+    (
+        "rate_stats.range",
+        "A range goes from an earlier day to a later one, at most {max} days.",
+    ),
+    (
+        "rate_stats.no_data",
+        "Not enough daily rates in this range for stats, try a longer one.",
+    ),
+    ("rate_stats.skipped.one", "<i>{count} day without a rate was left out.</i>"),
+    ("rate_stats.skipped.other", "<i>{count} days without a rate were left out.</i>"),
+    ("rate_stats.no_volatility", "n/a"),
+    ("rate_pages.prev", "◀"),
+    ("rate_pages.next", "▶"),
+    ("rate_pages.position", "{page}/{pages}"),
+    ("rate_pages.sort_strength", "Sort by strength"),
+    ("rate_pages.sort_alphabetical", "Sort A–Z"),
+    ("source.footer", "<i>Source: {sources}</i>"),
+    ("source.pfm", "pfm API"),
+    ("source.frankfurter", "Frankfurter"),
+    ("source.coingecko", "CoinGecko"),
+    ("source.stored", "last known rates from {source}"),
+    // time
+    ("time.ago.now", "just now"),
+    ("time.ago.minutes.one", "{count} minute ago"),
+    ("time.ago.minutes.other", "{count} minutes ago"),
+    ("time.ago.hours.one", "{count} hour ago"),
+    ("time.ago.hours.other", "{count} hours ago"),
+    ("time.ago.days.one", "{count} day ago"),
+    ("time.ago.days.other", "{count} days ago"),
+    ("time.updated", "updated {ago}"),
+    // text transforms
+    (
+        "transform.reply_without_text",
+        "Replied message has no text or caption",
+    ),
+    (
+        "transform.no_text",
+        "No text provided. Either provide text after the command or reply to a message.",
+    ),
+    (
+        "transform.too_long",
+        "The text is too long once transformed, please send a shorter one.",
+    ),
+    // settings
+    (
+        "settings.current",
+        "Settings of this chat:
+Language: {lang}
+Broadcast: {broadcast}
+Timezone: {timezone}",
+    ),
+    ("settings.not_set", "not set, following each member's Telegram language"),
+    ("settings.lang_updated", "Language of this chat is now {lang}."),
+    (
+        "settings.unknown_lang",
+        "Unknown language {lang}. Supported: {supported}",
+    ),
+    (
+        "settings.usage",
+        "Usage: /settings lang <id|en>, /settings broadcast <on|off> or /settings tz <timezone>",
+    ),
+    ("settings.timezone_updated", "Times in this chat are now shown in {timezone}, it is {now}."),
+    (
+        "settings.unknown_timezone",
+        "Unknown timezone {timezone}. Use WIB, WITA, WIT or a name like Asia/Makassar.",
+    ),
+    ("settings.on", "on"),
+    ("settings.off", "off"),
+    (
+        "settings.broadcast_on",
+        "This chat will receive announcements from bot admins.",
+    ),
+    (
+        "settings.broadcast_off",
+        "This chat will no longer receive announcements from bot admins.",
+    ),
+    (
+        "settings.admins_only",
+        "Only group admins can change settings of this chat.",
+    ),
+    // stats
+    ("stats.chat_header.one", "Usage of this chat in the last {count} day:"),
+    ("stats.chat_header.other", "Usage of this chat in the last {count} days:"),
+    ("stats.global_header.one", "Usage across all chats in the last {count} day:"),
+    ("stats.global_header.other", "Usage across all chats in the last {count} days:"),
+    ("stats.usage", "Usage: /stats, it takes no arguments."),
+    ("stats.empty", "No commands recorded yet."),
+    ("stats.summary", "Commands: {total}, errors: {errors} ({rate})"),
+    ("stats.activity", "Daily activity, {from} to {to}:\n{sparkline}"),
+    ("stats.top_header", "Top commands:"),
+    ("stats.top_item", "{rank}. /{command}: {count}, {rate} errors, avg {latency} ms"),
+    ("stats.errors_header", "Errors:"),
+    // admin
+    ("admin.forbidden", "This command is for bot admins only."),
+    (
+        "admin.usage",
+        "Usage: /admin stats [global] | broadcast <text> | ban <user_id> | unban <user_id> | reload | cache clear",
+    ),
+    ("admin.invalid_user", "Invalid user id {user}, it must be a number."),
+    (
+        "admin.ban_target",
+        "Give a user id, or reply to a message of the user.",
+    ),
+    ("admin.ban_admin", "Bot admins can't be banned."),
+    ("admin.banned", "User {user} is banned."),
+    ("admin.already_banned", "User {user} was already banned."),
+    ("admin.unbanned", "User {user} is unbanned."),
+    ("admin.not_banned", "User {user} wasn't banned."),
+    ("admin.broadcast_empty", "Broadcast text must not be empty."),
+    (
+        "admin.broadcast_started",
+        "Broadcasting to {chats} chats, the result follows when it's done.",
+    ),
+    (
+        "admin.broadcast_done",
+        "Broadcast sent to {sent} chats, {failed} failed.",
+    ),
+    ("admin.cache_cleared", "Cleared {count} cached responses."),
+    ("admin.reloaded", "Config reloaded, {admins} admins."),
+    ("admin.reload_failed", "Config reload failed, keeping the old one: {error}"),
+    (
+        "admin.menus_failed",
+        "Command menus were not updated: {error}",
+    ),
+    (
+        "admin.stats",
+        "Uptime: {uptime}
+Commands served: {commands}
+Cached responses: {cache}
+Errors:
+{errors}",
+    ),
+    ("admin.stats_no_errors", "none"),
+];
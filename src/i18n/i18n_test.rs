@@ -0,0 +1,176 @@
+use std::collections::{BTreeSet, HashSet};
+
+use regex::Regex;
+use rust_decimal_macros::dec;
+use teloxide::utils::command::BotCommands;
+
+use crate::commands::{Command, is_coming_soon, listed_commands};
+use crate::handlers::text_transform;
+use crate::i18n::{Lang, Localized, en, format_number, id, t};
+
+fn catalog(lang: Lang) -> &'static [(&'static str, &'static str)] {
+    match lang {
+        Lang::En => en::MESSAGES,
+        Lang::Id => id::MESSAGES,
+    }
+}
+
+fn placeholders(text: &str) -> BTreeSet<String> {
+    let re = Regex::new(r"\{([a-z_]+)\}").unwrap();
+    re.captures_iter(text).map(|c| c[1].to_string()).collect()
+}
+
+#[test]
+fn no_duplicate_keys() {
+    for lang in Lang::ALL {
+        let mut seen = HashSet::new();
+        for (key, _) in catalog(lang) {
+            assert!(seen.insert(key), "duplicate key {} in {:?}", key, lang);
+        }
+    }
+}
+
+#[test]
+fn every_key_exists_in_every_locale() {
+    for lang in Lang::ALL {
+        for other in Lang::ALL {
+            let other_keys: HashSet<&str> = catalog(other).iter().map(|(k, _)| *k).collect();
+            for (key, _) in catalog(lang) {
+                assert!(
+                    other_keys.contains(key),
+                    "key {} from {:?} is missing in {:?}",
+                    key,
+                    lang,
+                    other
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn placeholders_match_across_locales() {
+    for (key, text) in en::MESSAGES {
+        for lang in Lang::ALL {
+            assert_eq!(
+                placeholders(text),
+                placeholders(t(lang, key)),
+                "placeholders of {} differ in {:?}",
+                key,
+                lang
+            );
+        }
+    }
+}
+
+#[test]
+fn every_command_has_description() {
+    for lang in Lang::ALL {
+        for cmd in Command::bot_commands() {
+            let key = format!("cmd.{}.description", cmd.command.trim_start_matches('/'));
+            assert!(
+                catalog(lang).iter().any(|(k, _)| *k == key),
+                "missing {} in {:?}",
+                key,
+                lang
+            );
+        }
+    }
+}
+
+#[test]
+fn every_available_command_has_help_page() {
+    for lang in Lang::ALL {
+        for name in listed_commands() {
+            // text transforms share help.page.transform
+            if is_coming_soon(&name) || text_transform::by_name(&name).is_some() {
+                continue;
+            }
+            let key = format!("help.page.{}", name);
+            assert!(
+                catalog(lang).iter().any(|(k, _)| *k == key),
+                "missing {} in {:?}",
+                key,
+                lang
+            );
+        }
+    }
+}
+
+#[test]
+fn lang_from_telegram_codes() {
+    assert_eq!(Some(Lang::En), Lang::from_code("en"));
+    assert_eq!(Some(Lang::En), Lang::from_code("en-US"));
+    assert_eq!(Some(Lang::Id), Lang::from_code("id"));
+    assert_eq!(Some(Lang::Id), Lang::from_code("ID"));
+    assert_eq!(Some(Lang::Id), Lang::from_code("in"));
+    assert_eq!(None, Lang::from_code("ja"));
+    assert_eq!(None, Lang::from_code(""));
+}
+
+#[test]
+fn render_with_args_and_fallback() {
+    let text = Localized::new("syntax.unexpected")
+        .arg("got", "USD//IDR")
+        .render(Lang::Id);
+    assert_eq!("\"USD//IDR\" tidak seharusnya ada di sini.", text);
+
+    // unknown key is shown as is instead of panicking
+    assert_eq!(
+        "no.such.key",
+        Localized::new("no.such.key").render(Lang::Id)
+    );
+}
+
+#[test]
+fn render_keeps_placeholders_in_values() {
+    // a value naming a later argument isn't filled by it
+    let text = Localized::new("admin.broadcast_done")
+        .arg("sent", "{failed}")
+        .arg("failed", 2)
+        .render(Lang::En);
+    assert_eq!("Broadcast sent to {failed} chats, 2 failed.", text);
+}
+
+#[test]
+fn render_plural() {
+    let one = Localized::plural("forex.base_rates", 1)
+        .arg("base", "USD")
+        .arg("date", "2022-02-02")
+        .render(Lang::En);
+    assert_eq!("Rates with base USD on 2022-02-02 (1 currency):", one);
+
+    let many = Localized::plural("forex.base_rates", 1500)
+        .arg("base", "USD")
+        .arg("date", "2022-02-02")
+        .render(Lang::En);
+    assert_eq!(
+        "Rates with base USD on 2022-02-02 (1,500 currencies):",
+        many
+    );
+
+    let many_id = Localized::plural("forex.base_rates", 1500)
+        .arg("base", "USD")
+        .arg("date", "2022-02-02")
+        .render(Lang::Id);
+    assert_eq!(
+        "Kurs dengan basis USD per 2022-02-02 (1.500 mata uang):",
+        many_id
+    );
+}
+
+#[test]
+fn number_formatting() {
+    assert_eq!(
+        "1,234,567.89",
+        format_number(Lang::En, dec!(1234567.891), 2)
+    );
+    assert_eq!(
+        "1.234.567,89",
+        format_number(Lang::Id, dec!(1234567.891), 2)
+    );
+    assert_eq!("-1,000", format_number(Lang::En, dec!(-1000), 0));
+    assert_eq!("0,50", format_number(Lang::Id, dec!(0.5), 2));
+    assert_eq!("999", format_number(Lang::En, dec!(999), 0));
+    assert_eq!("0", format_number(Lang::En, dec!(-0.001), 0));
+}
@@ -0,0 +1,475 @@
+pub(super) static MESSAGES: &[(&str, &str)] = &[
+    ("lang.name", "Bahasa Indonesia"),
+    // common
+    ("common.coming_soon", "Segera hadir...!"),
+    // errors
+    ("error.ref", "Ref: {id}"),
+    (
+        "error.telegram",
+        "Telegram menolak balasan saya, silakan coba lagi.",
+    ),
+    (
+        "error.network",
+        "Layanan kurs sedang tidak bisa dihubungi, silakan coba lagi sebentar lagi.",
+    ),
+    (
+        "error.api",
+        "Layanan kurs tidak bisa menjawab permintaan ini, silakan coba lagi nanti.",
+    ),
+    (
+        "error.storage",
+        "Ada masalah di sisi saya, silakan coba lagi nanti.",
+    ),
+    (
+        "error.upstream_unavailable",
+        "Layanan kurs sedang tidak tersedia, silakan coba lagi beberapa menit lagi.",
+    ),
+    // help
+    (
+        "help.header",
+        "Ini adalah Bot Teknologi Umum yang ditulis dengan Rust, mendukung perintah berikut:",
+    ),
+    ("cmd.help.description", "Tampilkan pesan bantuan ini, atau rincian sebuah perintah"),
+    (
+        "cmd.forex.description",
+        "Ambil harga beberapa mata uang.
+Argumen:
+- Tanpa argumen: daftar kurs pilihan penulis.
+- Pasangan kurs:
+  - USD/IDR (pasangan mata uang, huruf besar/kecil bebas)
+  - USD/IDR 2022-02-02 (YYYY-MM-DD, tanggal opsional)
+- Kurs dengan basis:
+  - USD (mata uang sebagai basis, huruf besar/kecil bebas)
+  - USD 2022-02-02 (YYYY-MM-DD, tanggal opsional)
+  - USD EUR,JPY,IDR (hanya kuotasi ini)
+  - USD strength (terkuat dulu, atau az untuk urutan abjad)
+- Statistik pasangan:
+  - USD/IDR stats 30d (atau 4w, 1m, 2024-01-01..2024-01-31)",
+    ),
+    (
+        "cmd.convert.description",
+        "Konversi antara 2 mata uang.
+Argumen:
+- <KODE_ASAL> <JUMLAH_ASAL>; <KODE_TUJUAN>
+- <KODE_ASAL> <JUMLAH_ASAL>; <KODE_TUJUAN>; <TANGGAL> (tanggal opsional dengan format YYYY-MM-DD)
+- Contoh: USD 50,000; IDR
+- Contoh dengan tanggal: USD 50,000; IDR; 2022-02-02
+- Dipisahkan titik koma.",
+    ),
+    (
+        "cmd.pm.description",
+        "Ambil harga Logam Mulia (Emas & Perak) dalam berbagai mata uang fiat.
+Parameter opsional:
+- Tanggal kurs: YYYY-MM-DD",
+    ),
+    (
+        "cmd.zakat.description",
+        "Ambil informasi Zakat, seperti nisab Emas dan Perak saat ini.
+Parameter opsional:
+- Jumlah: harta Anda saat ini dalam fiat, bot akan menghitung apakah sudah mencapai nisab. Contoh: IDR 1,000,000,000.02
+- Tanggal mulai: tanggal Anda mencapai nisab, bot akan menghitung akhir haul dalam kalender hijriah yang dipetakan ke kalender masehi.",
+    ),
+    (
+        "cmd.stock.description",
+        "Ambil informasi sebuah saham. Bawaan: pilihan penulis.
+Parameter opsional:
+- Kode saham: contoh BBCA",
+    ),
+    ("cmd.remindme.description", "Ingatkan saya."),
+    ("cmd.cpi.description", "Data Indeks Harga Konsumen"),
+    ("cmd.spongebob.description", "sPoNgEbOb"),
+    ("cmd.uwu.description", "Bikin teks jadi imut, uwu"),
+    ("cmd.zalgo.description", "Bikin teks rusak dengan tanda bertumpuk"),
+    ("cmd.reverse.description", "Tulis teks dari belakang"),
+    ("cmd.leet.description", "7ul15 73k5 d4l4m l337"),
+    ("cmd.smallcaps.description", "Tulis teks dengan huruf kapital kecil"),
+    ("cmd.vaporwave.description", "Tulis teks dengan huruf lebar ala vaporwave"),
+    ("cmd.clap.description", "Sisipkan tepuk tangan di antara kata"),
+    (
+        "cmd.settings.description",
+        "Tampilkan atau ubah pengaturan chat ini.
+Argumen:
+- Tanpa argumen: tampilkan pengaturan saat ini.
+- lang <id|en>: bahasa balasan bot.
+- broadcast <on|off>: terima pengumuman dari admin bot.
+- tz <zona waktu>: zona waktu balasan, WIB, WITA, WIT atau nama seperti Asia/Makassar.",
+    ),
+    ("cmd.stats.description", "Tampilkan penggunaan perintah di chat ini: perintah teratas, tingkat galat, dan aktivitas harian."),
+    (
+        "cmd.admin.description",
+        "Alat operator bot: statistik, siaran, blokir, muat ulang, dan cache",
+    ),
+    (
+        "help.coming_soon",
+        "Segera hadir: {commands}",
+    ),
+    (
+        "help.footer",
+        "Ketuk perintah untuk melihat rinciannya, atau kirim /help &lt;perintah&gt;, misalnya /help convert.",
+    ),
+    ("help.aliases", "Bisa juga {aliases}"),
+    ("help.usage", "Penggunaan"),
+    ("help.back", "« Semua perintah"),
+    (
+        "help.unknown_command",
+        "Tidak ada perintah /{command}, kirim /help untuk melihat daftarnya.",
+    ),
+    (
+        "help.page.help",
+        "<b>Penggunaan</b>
+<code>/help</code>
+<code>/help &lt;perintah&gt;</code>
+
+<b>Argumen</b>
+- <i>perintah</i>: nama perintah, dengan atau tanpa garis miring.
+
+<b>Contoh</b>
+<code>/help</code> menampilkan semua perintah.
+<code>/help forex</code> menampilkan halaman ini untuk /forex.",
+    ),
+    (
+        "help.page.forex",
+        "<b>Argumen</b>
+- <i>dari</i>, <i>ke</i>, <i>basis</i>: kode, nama, atau simbol mata uang, misalnya USD, yen, rupiah, emas, BTC.
+- <i>kuotasi</i>: mata uang yang ditampilkan, dipisah koma, misalnya EUR,JPY,IDR. Jika tidak diisi, mata uang populer tampil dulu dengan tombol halaman untuk sisanya.
+- <i>tanggal</i>: hari kurs historis, misalnya 2022-02-02, 17/10/2026, 17 Okt 2026, kemarin, -7d, jumat lalu atau 2 minggu lalu. Akhir pekan menampilkan hari Jumat sebelumnya, kurs terbaru jika tidak diisi.
+- <i>az</i>, <i>strength</i>: urutan kurs, menurut abjad atau yang terkuat terhadap basis dulu. Menurut abjad jika tidak diisi.
+- <i>rentang</i>: hari statistik, 30d, 4w, 1m atau dua tanggal seperti 2024-01-01..2024-01-31, paling lama 31 hari. 30 hari terakhir jika tidak diisi.
+
+<b>Contoh</b>
+<code>/forex</code> menampilkan daftar pasangan populer.
+<code>/forex USD/IDR</code> menampilkan satu pasangan.
+<code>/forex yen/rupiah 2022-02-02</code> menampilkan pasangan pada hari lampau.
+<code>/forex USD/IDR 2 minggu lalu</code> menampilkan pasangan dua minggu lalu.
+<code>/forex IDR</code> menampilkan kurs mata uang populer terhadap IDR, dengan halaman untuk sisanya.
+<code>/forex USD EUR,JPY,SGD strength</code> menampilkan tiga kurs terhadap USD, yang terkuat dulu.
+<code>/forex USD/IDR stats 30d</code> menampilkan pembukaan, penutupan, terendah, tertinggi, rata-rata, perubahan dan volatilitas harian 30 hari terakhir.
+
+<b>Terkait</b>
+/convert",
+    ),
+    (
+        "help.page.convert",
+        "<b>Argumen</b>
+- <i>dari</i>, <i>ke</i>: kode, nama, atau simbol mata uang, misalnya USD, dolar, ringgit, BTC.
+- <i>jumlah</i>: sebelum atau sesudah <i>dari</i>, koma untuk ribuan dan titik untuk desimal, misalnya 50,000.25.
+- <i>tanggal</i>: hari kurs historis, misalnya 2022-02-02, 17/10/2026, 17 Okt 2026, kemarin, -7d, jumat lalu atau 2 minggu lalu. Akhir pekan menampilkan hari Jumat sebelumnya, kurs terbaru jika tidak diisi.
+
+<b>Contoh</b>
+<code>/convert</code> mengonversi 1 USD ke IDR.
+<code>/convert USD 50,000; IDR</code>
+<code>/convert 100 bucks; ringgit; 2022-02-02</code>
+
+<b>Terkait</b>
+/forex",
+    ),
+    (
+        "help.page.transform",
+        "<b>Penggunaan</b>
+<code>/{command} &lt;teks&gt;</code>
+Atau balas sebuah pesan dengan <code>/{command}</code>.
+
+<b>Argumen</b>
+- <i>teks</i>: teks yang diubah, pesan yang dibalas jika tidak diisi.
+
+Pesan perintahnya dihapus setelahnya.
+
+<b>Contoh</b>
+<code>/{command} {input}</code> menjawab seperti {output}
+
+<b>Terkait</b>
+{related}",
+    ),
+    ("help.transform_example", "rust itu mudah"),
+    (
+        "help.page.settings",
+        "<b>Penggunaan</b>
+<code>/settings</code>
+<code>/settings lang &lt;id|en&gt;</code>
+<code>/settings broadcast &lt;on|off&gt;</code>
+<code>/settings tz &lt;zona waktu&gt;</code>
+
+<b>Argumen</b>
+- <i>lang</i>: bahasa balasan bot di chat ini.
+- <i>broadcast</i>: apakah chat ini menerima pengumuman dari admin bot.
+- <i>tz</i>: zona waktu di balasan, WIB, WITA, WIT atau nama seperti Asia/Makassar.
+
+Hanya admin grup yang dapat mengubah pengaturan grup.
+
+<b>Contoh</b>
+<code>/settings lang id</code>
+<code>/settings tz WITA</code>
+
+<b>Terkait</b>
+/stats",
+    ),
+    (
+        "help.page.stats",
+        "<b>Penggunaan</b>
+<code>/stats</code>
+
+Menampilkan perintah yang paling sering dipakai di chat ini, tingkat galatnya, dan aktivitas harian.
+
+<b>Terkait</b>
+/settings",
+    ),
+    // forex
+    (
+        "forex.pair_format",
+        "Pasangan kurs harus berformat XXX/YYY",
+    ),
+    (
+        "forex.base_format",
+        "Mata uang basis harus berformat XXX, huruf besar/kecil bebas",
+    ),
+    (
+        "forex.quote_format",
+        "Kuotasi {quote} harus berupa kode atau nama mata uang, misalnya \"USD EUR,JPY,IDR\"",
+    ),
+    (
+        "forex.too_many_quotes",
+        "Paling banyak {max} mata uang bisa didaftarkan, kirim /forex <basis> untuk melihat semuanya per halaman.",
+    ),
+    ("forex.empty_data", "Data kurs kosong"),
+    ("forex.item_error", "galat: {error}"),
+    ("forex.no_data", "tidak ada data"),
+    ("forex.watchlist", "Data kurs per {date}:{content}"),
+    ("forex.api_error", "galat api kurs: {error}"),
+    ("forex.invalid_response", "respons tidak valid: data kosong"),
+    ("forex.no_data_returned", "tidak ada data yang dikembalikan"),
+    ("forex.single_pair", "{pair} per {date} adalah:\n<b>{rate}</b>"),
+    (
+        "forex.base_rates.one",
+        "Kurs dengan basis {base} per {date} ({count} mata uang):",
+    ),
+    (
+        "forex.base_rates.other",
+        "Kurs dengan basis {base} per {date} ({count} mata uang):",
+    ),
+    // convert
+    (
+        "convert.amount_format",
+        "Jumlah harus berupa angka dengan koma ribuan dan titik desimal opsional. Diterima: {amount}",
+    ),
+    ("convert.result", "Konversi per {date}:\n<b>{from} = {to}</b>"),
+    // money
+    ("money.invalid_amount", "Jumlah \"{amount}\" tidak valid."),
+    ("money.compact.thousands", "{value} rb"),
+    ("money.compact.millions", "{value} jt"),
+    ("money.compact.billions", "{value} M"),
+    ("money.compact.trillions", "{value} T"),
+    // currencies
+    ("currency.unknown", "Mata uang {code} tidak dikenal."),
+    (
+        "currency.did_you_mean",
+        "Mata uang {code} tidak dikenal, mungkin maksudnya {suggestions}?",
+    ),
+    (
+        "currency.ambiguous",
+        "\"{input}\" bisa berarti {alternatives}, mohon gunakan salah satu kodenya.",
+    ),
+    (
+        "currency.read_as",
+        "<i>\"{input}\" dibaca sebagai {code}, bisa juga berarti {alternatives}.</i>",
+    ),
+    // syntax
+    ("syntax.usage", "Cara pakai: {usage}"),
+    ("syntax.expected", "Seharusnya {expected} di sini, bukan \"{got}\"."),
+    ("syntax.missing", "Kurang {expected} di akhir."),
+    ("syntax.unexpected", "\"{got}\" tidak seharusnya ada di sini."),
+    (
+        "syntax.currency",
+        "\"{value}\" bukan kode atau nama mata uang, misalnya USD, yen, rupiah atau emas.",
+    ),
+    ("syntax.arg.from", "dari"),
+    ("syntax.arg.to", "ke"),
+    ("syntax.arg.base", "basis"),
+    ("syntax.arg.quotes", "kuotasi"),
+    ("syntax.arg.date", "tanggal"),
+    ("syntax.arg.range", "rentang"),
+    ("syntax.arg.amount", "jumlah"),
+    // rate sources
+    (
+        "date.invalid",
+        "Tanggal {date} tidak valid, misalnya 2022-02-02, 17/10/2026, 17 Okt 2026, kemarin, -7d, jumat lalu atau 2 minggu lalu.",
+    ),
+    (
+        "date.future",
+        "{date} belum terjadi, kurs historis hanya sampai hari ini, {today}.",
+    ),
+    (
+        "date.weekend",
+        "<i>{date} jatuh di akhir pekan, yang ditampilkan kurs hari bursa terakhir, {trading}.</i>",
+    ),
+    ("rate_buttons.swap", "⇄ Tukar"),
+    ("rate_buttons.prev_day", "◀ Hari sebelumnya"),
+    ("rate_buttons.next_day", "Hari berikutnya ▶"),
+    ("rate_buttons.refresh", "🔄 Perbarui"),
+    ("rate_buttons.change_button", "📈 {days}h"),
+    (
+        "rate_buttons.change",
+        "📈 Perubahan {days} hari: <b>{change}</b>, dari {rate} pada {date}",
+    ),
+    (
+        "rate_buttons.invalid",
+        "Tombol ini sudah tidak berlaku, silakan kirim ulang perintahnya.",
+    ),
+    (
+        "rate_buttons.not_owner",
+        "Hanya pengirim perintah yang bisa memakai tombol ini.",
+    ),
+    (
+        "rate_stats.result",
+        "📊 <b>{pair}</b> dari {start} sampai {end}, {days} hari bursa:
+Pembukaan: {open}
+Penutupan: {close}
+Terendah: {low} pada {low_date}
+Tertinggi: {high} pada {high_date}
+Rata-rata: {average}
+Perubahan: <b>{change}</b>
+Volatilitas harian: {volatility}",
+    ),
+    (
+        "rate_stats.format",
+        "Statistik butuh rentang seperti 30d, 4w, 1m atau 2024-01-01..2024-01-31, misalnya \"/forex USD/IDR stats 30d\".",
+    ),
+    (
+        "rate_stats.range",
+        "Rentang dimulai dari hari yang lebih awal ke hari yang lebih akhir, paling lama {max} hari.",
+    ),
+    (
+        "rate_stats.no_data",
+        "Kurs harian di rentang ini tidak cukup untuk statistik, coba rentang yang lebih panjang.",
+    ),
+    ("rate_stats.skipped.one", "<i>{count} hari tanpa kurs tidak dihitung.</i>"),
+    ("rate_stats.skipped.other", "<i>{count} hari tanpa kurs tidak dihitung.</i>"),
+    ("rate_stats.no_volatility", "t/a"),
+    ("rate_pages.prev", "◀"),
+    ("rate_pages.next", "▶"),
+    ("rate_pages.position", "{page}/{pages}"),
+    ("rate_pages.sort_strength", "Urutkan menurut kekuatan"),
+    ("rate_pages.sort_alphabetical", "Urutkan A–Z"),
+    ("source.footer", "<i>Sumber: {sources}</i>"),
+    ("source.pfm", "API pfm"),
+    ("source.frankfurter", "Frankfurter"),
+    ("source.coingecko", "CoinGecko"),
+    ("source.stored", "kurs terakhir dari {source}"),
+    // time
+    ("time.ago.now", "baru saja"),
+    ("time.ago.minutes.one", "{count} menit yang lalu"),
+    ("time.ago.minutes.other", "{count} menit yang lalu"),
+    ("time.ago.hours.one", "{count} jam yang lalu"),
+    ("time.ago.hours.other", "{count} jam yang lalu"),
+    ("time.ago.days.one", "{count} hari yang lalu"),
+    ("time.ago.days.other", "{count} hari yang lalu"),
+    ("time.updated", "diperbarui {ago}"),
+    // text transforms
+    (
+        "transform.reply_without_text",
+        "Pesan yang dibalas tidak memiliki teks atau keterangan",
+    ),
+    (
+        "transform.no_text",
+        "Tidak ada teks. Tulis teks setelah perintah atau balas sebuah pesan.",
+    ),
+    (
+        "transform.too_long",
+        "Teksnya terlalu panjang setelah diubah, kirim yang lebih pendek.",
+    ),
+    // settings
+    (
+        "settings.current",
+        "Pengaturan chat ini:
+Bahasa: {lang}
+Siaran: {broadcast}
+Zona waktu: {timezone}",
+    ),
+    (
+        "settings.not_set",
+        "belum diatur, mengikuti bahasa Telegram masing-masing anggota",
+    ),
+    ("settings.lang_updated", "Bahasa chat ini sekarang {lang}."),
+    (
+        "settings.unknown_lang",
+        "Bahasa {lang} tidak dikenal. Yang didukung: {supported}",
+    ),
+    (
+        "settings.usage",
+        "Cara pakai: /settings lang <id|en>, /settings broadcast <on|off> atau /settings tz <zona waktu>",
+    ),
+    ("settings.timezone_updated", "Waktu di chat ini sekarang ditampilkan dalam {timezone}, sekarang {now}."),
+    (
+        "settings.unknown_timezone",
+        "Zona waktu {timezone} tidak dikenal. Gunakan WIB, WITA, WIT atau nama seperti Asia/Makassar.",
+    ),
+    ("settings.on", "aktif"),
+    ("settings.off", "nonaktif"),
+    (
+        "settings.broadcast_on",
+        "Chat ini akan menerima pengumuman dari admin bot.",
+    ),
+    (
+        "settings.broadcast_off",
+        "Chat ini tidak akan menerima pengumuman dari admin bot lagi.",
+    ),
+    (
+        "settings.admins_only",
+        "Hanya admin grup yang dapat mengubah pengaturan chat ini.",
+    ),
+    // stats
+    ("stats.chat_header.one", "Penggunaan di chat ini dalam {count} hari terakhir:"),
+    ("stats.chat_header.other", "Penggunaan di chat ini dalam {count} hari terakhir:"),
+    ("stats.global_header.one", "Penggunaan di semua chat dalam {count} hari terakhir:"),
+    ("stats.global_header.other", "Penggunaan di semua chat dalam {count} hari terakhir:"),
+    ("stats.usage", "Cara pakai: /stats, tanpa argumen."),
+    ("stats.empty", "Belum ada perintah yang tercatat."),
+    ("stats.summary", "Perintah: {total}, galat: {errors} ({rate})"),
+    ("stats.activity", "Aktivitas harian, {from} sampai {to}:\n{sparkline}"),
+    ("stats.top_header", "Perintah teratas:"),
+    ("stats.top_item", "{rank}. /{command}: {count}, galat {rate}, rata-rata {latency} ms"),
+    ("stats.errors_header", "Galat:"),
+    // admin
+    ("admin.forbidden", "Perintah ini hanya untuk admin bot."),
+    (
+        "admin.usage",
+        "Cara pakai: /admin stats [global] | broadcast <teks> | ban <user_id> | unban <user_id> | reload | cache clear",
+    ),
+    ("admin.invalid_user", "User id {user} tidak valid, harus berupa angka."),
+    (
+        "admin.ban_target",
+        "Berikan user id, atau balas pesan dari pengguna tersebut.",
+    ),
+    ("admin.ban_admin", "Admin bot tidak bisa diblokir."),
+    ("admin.banned", "Pengguna {user} diblokir."),
+    ("admin.already_banned", "Pengguna {user} sudah diblokir sebelumnya."),
+    ("admin.unbanned", "Blokir pengguna {user} dicabut."),
+    ("admin.not_banned", "Pengguna {user} tidak sedang diblokir."),
+    ("admin.broadcast_empty", "Teks siaran tidak boleh kosong."),
+    (
+        "admin.broadcast_started",
+        "Menyiarkan ke {chats} chat, hasilnya menyusul setelah selesai.",
+    ),
+    (
+        "admin.broadcast_done",
+        "Siaran terkirim ke {sent} chat, {failed} gagal.",
+    ),
+    ("admin.cache_cleared", "{count} respons tersimpan telah dihapus."),
+    ("admin.reloaded", "Konfigurasi dimuat ulang, {admins} admin."),
+    (
+        "admin.reload_failed",
+        "Gagal memuat ulang konfigurasi, tetap memakai yang lama: {error}",
+    ),
+    (
+        "admin.menus_failed",
+        "Menu perintah tidak diperbarui: {error}",
+    ),
+    (
+        "admin.stats",
+        "Waktu aktif: {uptime}
+Perintah dilayani: {commands}
+Respons tersimpan: {cache}
+Galat:
+{errors}",
+    ),
+    ("admin.stats_no_errors", "tidak ada"),
+];
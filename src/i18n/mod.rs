@@ -0,0 +1,228 @@
+//! i18n holds message catalogs of every text the bot sends.
+//!
+//! Each locale is a flat list of `key => text` in its own file. Texts use `{name}` placeholders,
+//! plural texts are split into `key.one` and `key.other`.
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::LazyLock;
+
+use rust_decimal::{Decimal, RoundingStrategy};
+use teloxide::types::{Message, User};
+
+use crate::config::config;
+use crate::deps::storage::storage;
+
+mod en;
+mod id;
+
+#[cfg(test)]
+mod i18n_test;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Lang {
+    En,
+    Id,
+}
+
+impl Lang {
+    pub(crate) const ALL: [Lang; 2] = [Lang::En, Lang::Id];
+
+    pub(crate) fn code(&self) -> &'static str {
+        match self {
+            Lang::En => "en",
+            Lang::Id => "id",
+        }
+    }
+
+    /// Parse language code, including telegram's IETF tags like `en-US` and the legacy `in` for Indonesian.
+    pub(crate) fn from_code(code: &str) -> Option<Lang> {
+        let primary = code.split(['-', '_']).next()?.to_ascii_lowercase();
+
+        match primary.as_str() {
+            "en" => Some(Lang::En),
+            "id" | "in" => Some(Lang::Id),
+            _ => None,
+        }
+    }
+
+    /// Thousands and decimal separators.
+    pub(crate) fn separators(&self) -> (&'static str, &'static str) {
+        match self {
+            Lang::En => (",", "."),
+            Lang::Id => (".", ","),
+        }
+    }
+
+    fn catalog(&self) -> &'static HashMap<&'static str, &'static str> {
+        match self {
+            Lang::En => &EN,
+            Lang::Id => &ID,
+        }
+    }
+
+    fn plural_category(&self, count: i64) -> &'static str {
+        match self {
+            Lang::En if count == 1 => "one",
+            Lang::En => "other",
+            // Indonesian nouns don't inflect for number
+            Lang::Id => "other",
+        }
+    }
+}
+
+static EN: LazyLock<HashMap<&'static str, &'static str>> =
+    LazyLock::new(|| en::MESSAGES.iter().copied().collect());
+
+static ID: LazyLock<HashMap<&'static str, &'static str>> =
+    LazyLock::new(|| id::MESSAGES.iter().copied().collect());
+
+pub(crate) fn default_lang() -> Lang {
+    Lang::from_code(&config().default_lang).unwrap_or(Lang::En)
+}
+
+/// Language to answer a message in: chat setting first, then sender's telegram language, then configured default.
+pub(crate) fn lang_for(msg: &Message) -> Lang {
+    lang_for_user(msg.chat.id.0, msg.from.as_ref())
+}
+
+/// Language to answer `user` in chat `chat_id`, for updates other than messages, e.g. button presses.
+pub(crate) fn lang_for_user(chat_id: i64, user: Option<&User>) -> Lang {
+    match storage().chat_settings(chat_id) {
+        Ok(settings) => {
+            if let Some(lang) = settings.lang.as_deref().and_then(Lang::from_code) {
+                return lang;
+            }
+        }
+        Err(err) => eprintln!("[i18n] {:#}", err),
+    }
+
+    user.and_then(|user| user.language_code.as_deref())
+        .and_then(Lang::from_code)
+        .unwrap_or_else(default_lang)
+}
+
+/// Raw text of a key, falling back to English, then to the key itself so a missing entry is visible but harmless.
+pub(crate) fn t(lang: Lang, key: &str) -> &str {
+    lang.catalog()
+        .get(key)
+        .or_else(|| EN.get(key))
+        .copied()
+        .unwrap_or(key)
+}
+
+/// Format number with locale separators, rounded half away from zero to `decimals` places.
+pub(crate) fn format_number(lang: Lang, value: Decimal, decimals: u32) -> String {
+    let (thousands_sep, decimal_sep) = lang.separators();
+    let rounded = value
+        .round_dp_with_strategy(decimals, RoundingStrategy::MidpointAwayFromZero)
+        .abs();
+    let plain = format!("{:.*}", decimals as usize, rounded);
+
+    let (int_part, frac_part) = match plain.split_once('.') {
+        Some((int_part, frac_part)) => (int_part, Some(frac_part)),
+        None => (plain.as_str(), None),
+    };
+
+    let mut grouped = String::with_capacity(plain.len() + int_part.len() / 3);
+    for (idx, ch) in int_part.chars().enumerate() {
+        if idx > 0 && (int_part.len() - idx) % 3 == 0 {
+            grouped.push_str(thousands_sep);
+        }
+        grouped.push(ch);
+    }
+
+    let sign = if value.is_sign_negative() && !rounded.is_zero() {
+        "-"
+    } else {
+        ""
+    };
+
+    match frac_part {
+        Some(frac_part) => format!("{}{}{}{}", sign, grouped, decimal_sep, frac_part),
+        None => format!("{}{}", sign, grouped),
+    }
+}
+
+/// A catalog key with its arguments, rendered once the reader's language is known.
+///
+/// It is also an error, so argument parsers can return it wrapped in `HandlerError::InvalidArguments`.
+#[derive(Debug, Clone)]
+pub(crate) struct Localized {
+    key: &'static str,
+    count: Option<i64>,
+    args: Vec<(&'static str, String)>,
+}
+
+impl Localized {
+    pub(crate) fn new(key: &'static str) -> Self {
+        Localized {
+            key,
+            count: None,
+            args: vec![],
+        }
+    }
+
+    /// Plural text, picks `key.one` or `key.other` and fills `{count}`.
+    pub(crate) fn plural(key: &'static str, count: i64) -> Self {
+        Localized {
+            key,
+            count: Some(count),
+            args: vec![],
+        }
+    }
+
+    pub(crate) fn arg(mut self, name: &'static str, value: impl Display) -> Self {
+        self.args.push((name, value.to_string()));
+        self
+    }
+
+    /// Text of the key with placeholders filled in one pass, so values holding `{name}` are kept as is.
+    pub(crate) fn render(&self, lang: Lang) -> String {
+        let plural_key = self
+            .count
+            .map(|count| format!("{}.{}", self.key, lang.plural_category(count)));
+        let template = t(lang, plural_key.as_deref().unwrap_or(self.key));
+        let count = self
+            .count
+            .map(|count| format_number(lang, Decimal::from(count), 0));
+
+        let mut ret = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            ret.push_str(&rest[..start]);
+            let after = &rest[start + 1..];
+            let name = after.find('}').map(|end| &after[..end]);
+            let value = name.and_then(|name| match (&count, name) {
+                (Some(count), "count") => Some(count.as_str()),
+                _ => self
+                    .args
+                    .iter()
+                    .find(|(arg, _)| *arg == name)
+                    .map(|(_, value)| value.as_str()),
+            });
+
+            match (name, value) {
+                (Some(name), Some(value)) => {
+                    ret.push_str(value);
+                    rest = &after[name.len() + 1..];
+                }
+                // unknown placeholders and lone braces stay as written
+                _ => {
+                    ret.push('{');
+                    rest = after;
+                }
+            }
+        }
+        ret.push_str(rest);
+
+        ret
+    }
+}
+
+impl Display for Localized {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Lang::En))
+    }
+}
+
+impl std::error::Error for Localized {}
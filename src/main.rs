@@ -3,34 +3,60 @@
 use axum::{Router, routing::get};
 use reqwest::StatusCode;
 use std::net::SocketAddr;
+use std::time::Instant;
 use teloxide::{
     dispatching::{UpdateFilterExt, UpdateHandler},
     prelude::*,
-    types::{ParseMode, Update},
+    types::{Me, Update},
     update_listeners::webhooks,
 };
 
-use crate::error::SendIfError;
+use crate::deps::metrics::metrics;
+use crate::error::{HandlerError, SendIfError};
+use crate::handlers::reply::Reply;
+use crate::handlers::text_transform;
+use crate::i18n::{Localized, lang_for};
 
+mod api;
 mod commands;
+#[cfg(test)]
+mod commands_test;
 mod config;
 mod deps;
 mod error;
+#[cfg(test)]
+mod error_test;
 mod handlers;
+mod i18n;
+#[cfg(test)]
+mod main_test;
+#[cfg(test)]
+mod testing;
 mod utils;
 use config::config;
 
-static WEBHOOK_ENDPOINT: &'static str = "https://api.mfirhas.com/webhook";
+static WEBHOOK_ENDPOINT: &str = "https://api.mfirhas.com/webhook";
 
 #[tokio::main]
 async fn main() {
     let bot = Bot::new(config().bot_token.clone());
 
+    tokio::spawn(handlers::stats::compaction_job());
+
+    // a bot without menus still works, commands are typed by hand
+    if let Err(err) = commands::publish_menus(&bot, &config().admin_ids, &[]).await {
+        eprintln!("[commands] failed publishing command menus: {}", err);
+    }
+
     // dev/local mode
     if cfg!(debug_assertions) {
         println!("kartel started in dev mode...");
-        commands::Command::repl(bot, handlers).await;
-        return ();
+        Dispatcher::builder(bot, handler())
+            .enable_ctrlc_handler()
+            .build()
+            .dispatch()
+            .await;
+        return;
     }
 
     println!("kartel started in production mode...");
@@ -59,7 +85,9 @@ async fn main() {
 
     // APIs
     let api_addr: SocketAddr = ([0, 0, 0, 0], config().api_port).into();
-    let app = Router::new().route("/ping", get(|| async { (StatusCode::OK, "pong") }));
+    let app = Router::new()
+        .route("/ping", get(|| async { (StatusCode::OK, "pong") }))
+        .merge(api::router());
     let api_listener = tokio::net::TcpListener::bind(api_addr).await.unwrap();
     let api_server = async {
         axum::serve(api_listener, app)
@@ -68,89 +96,173 @@ async fn main() {
     };
 
     tokio::join!(bot_server, api_server);
-
-    ()
 }
 
 fn handler() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
-    dptree::entry().branch(
-        Update::filter_message()
-            .filter_command::<crate::commands::Command>()
-            .endpoint(
-                |bot: Bot, msg: Message, cmd: crate::commands::Command| async move {
-                    handlers(bot, msg, cmd)
+    dptree::entry()
+        .branch(Update::filter_message().chain(command_handler()))
+        // an edited command runs again while its answer can still be edited
+        .branch(
+            Update::filter_edited_message()
+                .filter(|msg: Message| handlers::reply::tracked(&msg).is_some())
+                .chain(command_handler()),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(handlers::help::CALLBACK_PREFIX))
+                })
+                .endpoint(|bot: Bot, query: CallbackQuery| async move {
+                    handlers::help::help_callback(bot, query)
                         .await
                         .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
-                },
-            ),
-    )
+                }),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query.data.as_deref().is_some_and(|data| {
+                        data.starts_with(handlers::rate_buttons::CALLBACK_PREFIX)
+                    })
+                })
+                .endpoint(|bot: Bot, query: CallbackQuery| async move {
+                    handlers::rate_buttons::rate_callback(bot, query)
+                        .await
+                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+                }),
+        )
+        .branch(
+            Update::filter_callback_query()
+                .filter(|query: CallbackQuery| {
+                    query
+                        .data
+                        .as_deref()
+                        .is_some_and(|data| data.starts_with(handlers::rate_pages::CALLBACK_PREFIX))
+                })
+                .endpoint(|bot: Bot, query: CallbackQuery| async move {
+                    handlers::rate_pages::page_callback(bot, query)
+                        .await
+                        .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+                }),
+        )
+}
+
+fn command_handler() -> UpdateHandler<Box<dyn std::error::Error + Send + Sync + 'static>> {
+    dptree::filter(|msg: Message| !handlers::admin::is_banned_sender(&msg))
+        // like filter_command, with aliases resolved first
+        .filter_map(|msg: Message, me: Me| {
+            let text = msg.text().or_else(|| msg.caption())?;
+            commands::parse(text, me.username())
+        })
+        .filter(|msg: Message, cmd: crate::commands::Command| {
+            msg.edit_date().is_none() || cmd.reruns_on_edit()
+        })
+        .endpoint(
+            |bot: Bot, msg: Message, cmd: crate::commands::Command| async move {
+                handlers(bot, msg, cmd)
+                    .await
+                    .map_err(|e| Box::new(e) as Box<dyn std::error::Error + Send + Sync>)
+            },
+        )
 }
 
 async fn handlers(bot: Bot, msg: Message, cmd: crate::commands::Command) -> ResponseResult<()> {
-    match cmd {
-        commands::Command::Help => handlers::help::help_handler(bot, &msg).await?,
+    let command = cmd.name();
+    let started_at = Instant::now();
+
+    let ret = match cmd {
+        commands::Command::Help(args) => {
+            handlers::help::help_handler(bot.clone(), &msg, args).await
+        }
 
         commands::Command::Forex(args) => {
-            handlers::forex::forex_handler(bot.clone(), &msg, args)
-                .await
-                .send_if_err(bot, &msg)
-                .await?
+            handlers::forex::forex_handler(bot.clone(), &msg, args).await
         }
 
         commands::Command::Convert(args) => {
-            handlers::convert::convert_handler(bot.clone(), &msg, args)
+            handlers::convert::convert_handler(bot.clone(), &msg, args).await
+        }
+
+        // TODO
+        commands::Command::Pm(_args) => coming_soon(bot.clone(), &msg).await,
+
+        // TODO
+        commands::Command::Zakat(_args) => coming_soon(bot.clone(), &msg).await,
+
+        // TODO
+        commands::Command::Stock(_args) => coming_soon(bot.clone(), &msg).await,
+
+        // TODO
+        commands::Command::RemindMe(_args) => coming_soon(bot.clone(), &msg).await,
+
+        // TODO
+        commands::Command::Cpi(_args) => coming_soon(bot.clone(), &msg).await,
+
+        commands::Command::SpongeBob(args) => {
+            text_transform::transform_handler(bot.clone(), &msg, args, &text_transform::SpongeBob)
                 .await
-                .send_if_err(bot, &msg)
-                .await?
         }
 
-        commands::Command::PM(args) => {
-            // TODO
-            bot.send_message(msg.chat.id, "Coming soon...!")
-                .parse_mode(ParseMode::Html)
-                .await?;
-            ()
+        commands::Command::Uwu(args) => {
+            text_transform::transform_handler(bot.clone(), &msg, args, &text_transform::Uwu).await
         }
 
-        commands::Command::Zakat(args) => {
-            // TODO
-            bot.send_message(msg.chat.id, "Coming soon...!")
-                .parse_mode(ParseMode::Html)
-                .await?;
-            ()
+        commands::Command::Zalgo(args) => {
+            text_transform::transform_handler(bot.clone(), &msg, args, &text_transform::Zalgo).await
         }
 
-        commands::Command::Stock(args) => {
-            // TODO
-            bot.send_message(msg.chat.id, "Coming soon...!")
-                .parse_mode(ParseMode::Html)
-                .await?;
-            ()
+        commands::Command::Reverse(args) => {
+            text_transform::transform_handler(bot.clone(), &msg, args, &text_transform::Reverse)
+                .await
         }
 
-        commands::Command::RemindMe(args) => {
-            // TODO
-            bot.send_message(msg.chat.id, "Coming soon...!")
-                .parse_mode(ParseMode::Html)
-                .await?;
-            ()
+        commands::Command::Leet(args) => {
+            text_transform::transform_handler(bot.clone(), &msg, args, &text_transform::Leet).await
         }
 
-        commands::Command::CPI(args) => {
-            // TODO
-            bot.send_message(msg.chat.id, "Coming soon...!")
-                .parse_mode(ParseMode::Html)
-                .await?;
-            ()
+        commands::Command::SmallCaps(args) => {
+            text_transform::transform_handler(bot.clone(), &msg, args, &text_transform::SmallCaps)
+                .await
         }
 
-        commands::Command::SpongeBob(args) => {
-            handlers::spongebob::spongebob_handler(bot.clone(), &msg, args)
+        commands::Command::Vaporwave(args) => {
+            text_transform::transform_handler(bot.clone(), &msg, args, &text_transform::Vaporwave)
                 .await
-                .send_if_err(bot, &msg)
-                .await?
         }
-    }
+
+        commands::Command::Clap(args) => {
+            text_transform::transform_handler(bot.clone(), &msg, args, &text_transform::Clap).await
+        }
+
+        commands::Command::Settings(args) => {
+            handlers::settings::settings_handler(bot.clone(), &msg, args).await
+        }
+
+        commands::Command::Stats(args) => {
+            handlers::stats::stats_handler(bot.clone(), &msg, args).await
+        }
+
+        commands::Command::Admin(args) => {
+            handlers::admin::admin_handler(bot.clone(), &msg, args).await
+        }
+    };
+
+    metrics().record(&ret);
+    handlers::stats::record(&msg, command, &ret, started_at.elapsed());
+
+    ret.send_if_err(bot, &msg).await?;
+
+    Ok(())
+}
+
+async fn coming_soon(bot: Bot, msg: &Message) -> Result<(), HandlerError> {
+    Reply::new(Localized::new("common.coming_soon").render(lang_for(msg)))
+        .html()
+        .send(&bot, msg)
+        .await?;
 
     Ok(())
 }
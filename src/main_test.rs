@@ -0,0 +1,293 @@
+use serde_json::json;
+
+use crate::handler;
+use crate::testing::fake_bot::{
+    BOT_USERNAME, FakeBotApi, callback_update, group_message, message_update, private_message,
+    replying_to,
+};
+
+#[tokio::test]
+async fn spongebob_sends_text_and_deletes_command() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(5, 310_001, "/spongebob hello world")),
+    )
+    .await
+    .unwrap();
+
+    let sent = api.sent_messages();
+    assert_eq!(1, sent.len());
+    assert_eq!(Some(310_001), sent[0].chat_id());
+    assert_eq!("hello world", sent[0].text().unwrap().to_ascii_lowercase());
+    assert_eq!(None, sent[0].reply_to());
+    assert_eq!(vec![5], api.deleted());
+}
+
+#[tokio::test]
+async fn spongebob_replies_to_replied_message() {
+    let api = FakeBotApi::start().await;
+    let replied = private_message(3, 310_002, "you can't do that");
+
+    api.dispatch(
+        handler(),
+        message_update(
+            1,
+            replying_to(private_message(4, 310_002, "/spongebob"), replied),
+        ),
+    )
+    .await
+    .unwrap();
+
+    let sent = api.sent_messages();
+    assert_eq!(1, sent.len());
+    assert_eq!(Some(3), sent[0].reply_to());
+    assert_eq!(
+        "you can't do that",
+        sent[0].text().unwrap().to_ascii_lowercase()
+    );
+    assert_eq!(vec![4], api.deleted());
+}
+
+#[tokio::test]
+async fn invalid_arguments_reply_with_reference() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(7, 310_003, "/spongebob")),
+    )
+    .await
+    .unwrap_err();
+
+    let sent = api.sent_messages();
+    assert_eq!(1, sent.len());
+    assert_eq!(Some(7), sent[0].reply_to());
+
+    let text = sent[0].text().unwrap();
+    assert!(text.starts_with("No text provided."), "{}", text);
+    assert!(text.contains("\n\nRef: "), "{}", text);
+    assert!(api.deleted().is_empty());
+}
+
+#[tokio::test]
+async fn help_lists_commands() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(1, 310_004, "/help")),
+    )
+    .await
+    .unwrap();
+
+    let sent = api.sent_messages();
+    assert_eq!(1, sent.len());
+    let text = sent[0].text().unwrap();
+    assert!(
+        text.contains("\n/forex (/kurs, /fx) — Fetch prices of some moneys.\n"),
+        "{}",
+        text
+    );
+    assert!(!text.contains("/admin"), "{}", text);
+
+    // unfinished commands are only named
+    assert!(!text.contains("\n/zakat — "), "{}", text);
+    assert!(text.contains("Coming soon: /pm, /zakat"), "{}", text);
+
+    let buttons = &sent[0].body["reply_markup"]["inline_keyboard"];
+    assert_eq!("/help", buttons[0][0]["text"]);
+    assert_eq!("help:forex", buttons[0][1]["callback_data"]);
+    assert!(!buttons.to_string().contains("help:zakat"));
+}
+
+#[tokio::test]
+async fn help_pages_of_commands() {
+    let api = FakeBotApi::start().await;
+
+    for (id, text) in [(1, "/help convert"), (2, "/help /CONVERT")] {
+        api.dispatch(
+            handler(),
+            message_update(id, private_message(id, 310_008, text)),
+        )
+        .await
+        .unwrap();
+    }
+
+    let sent = api.sent_messages();
+    assert_eq!(2, sent.len());
+    assert_eq!(sent[0].text(), sent[1].text());
+    let text = sent[0].text().unwrap();
+    assert!(
+        text.starts_with(
+            "<b>/convert</b> — Convert between 2 currencies.\n<i>Also /c</i>\n\n<b>Usage</b>"
+        ),
+        "{}",
+        text
+    );
+    assert!(text.contains("<b>Related</b>\n/forex"), "{}", text);
+    assert_eq!(
+        "help:",
+        sent[0].body["reply_markup"]["inline_keyboard"][0][0]["callback_data"]
+    );
+
+    api.dispatch(
+        handler(),
+        message_update(3, private_message(3, 310_008, "/help zakat")),
+    )
+    .await
+    .unwrap();
+    let text = api.sent_messages()[2].text().unwrap().to_string();
+    assert!(text.ends_with("Coming soon...!"), "{}", text);
+
+    let ret = api
+        .dispatch(
+            handler(),
+            message_update(4, private_message(4, 310_008, "/help nope")),
+        )
+        .await;
+    assert!(ret.is_err());
+    let text = api.sent_messages()[3].text().unwrap().to_string();
+    assert!(
+        text.starts_with("There's no /nope command, send /help for the list."),
+        "{}",
+        text
+    );
+}
+
+#[tokio::test]
+async fn help_buttons_switch_pages() {
+    let api = FakeBotApi::start().await;
+    let help_message = private_message(10_000, 310_009, "help index");
+
+    api.dispatch(
+        handler(),
+        callback_update(1, 310_009, help_message.clone(), "help:stats"),
+    )
+    .await
+    .unwrap();
+
+    let edits = api.calls_to("editMessageText");
+    assert_eq!(1, edits.len());
+    assert_eq!(Some(10_000), edits[0].message_id());
+    assert!(edits[0].text().unwrap().starts_with("<b>/stats</b> — "));
+    assert_eq!(1, api.calls_to("answerCallbackQuery").len());
+
+    api.dispatch(
+        handler(),
+        callback_update(2, 310_009, help_message, "help:"),
+    )
+    .await
+    .unwrap();
+
+    let edits = api.calls_to("editMessageText");
+    assert!(
+        edits[1]
+            .text()
+            .unwrap()
+            .contains("\n/forex (/kurs, /fx) — ")
+    );
+    assert!(api.sent_messages().is_empty());
+}
+
+#[tokio::test]
+async fn unfinished_commands_say_coming_soon() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(1, 310_007, "/zakat")),
+    )
+    .await
+    .unwrap();
+
+    let sent = api.sent_messages();
+    assert_eq!(1, sent.len());
+    assert_eq!(Some("Coming soon...!"), sent[0].text());
+    assert_eq!(Some("HTML"), sent[0].parse_mode());
+}
+
+#[tokio::test]
+async fn commands_for_other_bots_are_ignored() {
+    let api = FakeBotApi::start().await;
+
+    api.dispatch(
+        handler(),
+        message_update(1, private_message(1, 310_005, "/help@some_other_bot")),
+    )
+    .await
+    .unwrap();
+    assert!(api.sent_messages().is_empty());
+
+    api.dispatch(
+        handler(),
+        message_update(
+            2,
+            private_message(2, 310_005, &format!("/help@{}", BOT_USERNAME)),
+        ),
+    )
+    .await
+    .unwrap();
+    assert_eq!(1, api.sent_messages().len());
+}
+
+#[tokio::test]
+async fn aliases_run_their_commands() {
+    let api = FakeBotApi::start().await;
+
+    for (id, text) in [
+        (1, "/help convert".to_string()),
+        (2, "/help c".to_string()),
+        (3, format!("/C@{} 100 usd", BOT_USERNAME)),
+        (4, "/c@some_other_bot 100 usd".to_string()),
+        (5, "/pengingat".to_string()),
+    ] {
+        let _ = api
+            .dispatch(
+                handler(),
+                message_update(id, private_message(id, 310_010, &text)),
+            )
+            .await;
+    }
+
+    let sent = api.sent_messages();
+    assert_eq!(4, sent.len());
+    assert_eq!(sent[0].text(), sent[1].text());
+    // usage of /convert, before any upstream is asked
+    let text = sent[2].text().unwrap();
+    assert!(text.starts_with("Missing ; at the end."), "{}", text);
+    assert_eq!(Some("Coming soon...!"), sent[3].text());
+}
+
+#[tokio::test]
+async fn group_settings_need_group_admin() {
+    let api = FakeBotApi::start().await;
+    api.respond_with(
+        "getChatMember",
+        json!({
+            "status": "member",
+            "user": { "id": 310_006, "is_bot": false, "first_name": "Tester" },
+        }),
+    );
+
+    api.dispatch(
+        handler(),
+        message_update(
+            1,
+            group_message(9, -100_310_006, 310_006, "/settings lang id"),
+        ),
+    )
+    .await
+    .unwrap_err();
+
+    assert_eq!(1, api.calls_to("getChatMember").len());
+    let sent = api.sent_messages();
+    assert_eq!(1, sent.len());
+    assert!(
+        sent[0]
+            .text()
+            .unwrap()
+            .starts_with("Only group admins can change settings of this chat.")
+    );
+}
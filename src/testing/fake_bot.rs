@@ -0,0 +1,336 @@
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::ops::ControlFlow;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    body::Bytes,
+    extract::{Path, State},
+    http::HeaderMap,
+    routing::post,
+};
+use chrono::Utc;
+use serde_json::{Value, json};
+use teloxide::dispatching::UpdateHandler;
+use teloxide::prelude::*;
+use teloxide::types::{Me, Update};
+
+pub(crate) type HandlerTreeError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+pub(crate) const BOT_USERNAME: &str = "kartel_test_bot";
+
+const BOT_ID: u64 = 1000;
+
+// ids of messages sent by the bot, far from ids of incoming test messages
+const FIRST_SENT_MESSAGE_ID: i32 = 10_000;
+
+/// One Bot API request sent by the bot.
+#[derive(Debug, Clone)]
+pub(crate) struct Call {
+    pub method: String,
+
+    // json body, or multipart fields by name with file contents left out
+    pub body: Value,
+}
+
+impl Call {
+    pub(crate) fn text(&self) -> Option<&str> {
+        self.body["text"].as_str()
+    }
+
+    pub(crate) fn parse_mode(&self) -> Option<&str> {
+        self.body["parse_mode"].as_str()
+    }
+
+    pub(crate) fn chat_id(&self) -> Option<i64> {
+        as_i64(&self.body["chat_id"])
+    }
+
+    pub(crate) fn message_id(&self) -> Option<i32> {
+        as_i64(&self.body["message_id"]).map(|id| id as i32)
+    }
+
+    /// Message this call replies to.
+    pub(crate) fn reply_to(&self) -> Option<i32> {
+        as_i64(&self.body["reply_parameters"]["message_id"]).map(|id| id as i32)
+    }
+}
+
+// multipart fields are strings
+fn as_i64(value: &Value) -> Option<i64> {
+    value
+        .as_i64()
+        .or_else(|| value.as_str().and_then(|value| value.parse().ok()))
+}
+
+/// In-process Bot API. Every request is recorded and answered with a plausible result.
+pub(crate) struct FakeBotApi {
+    addr: SocketAddr,
+    state: Arc<FakeState>,
+}
+
+#[derive(Default)]
+struct FakeState {
+    calls: Mutex<Vec<Call>>,
+    results: Mutex<HashMap<String, Value>>,
+    next_message_id: AtomicI32,
+}
+
+impl FakeBotApi {
+    pub(crate) async fn start() -> Self {
+        let state = Arc::new(FakeState {
+            next_message_id: AtomicI32::new(FIRST_SENT_MESSAGE_ID),
+            ..Default::default()
+        });
+        let app = Router::new()
+            .route("/:token/:method", post(call))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed binding fake bot api");
+        let addr = listener
+            .local_addr()
+            .expect("failed reading fake bot api address");
+        tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("failed serving fake bot api");
+        });
+
+        FakeBotApi { addr, state }
+    }
+
+    pub(crate) fn bot(&self) -> Bot {
+        let url = format!("http://{}", self.addr)
+            .parse()
+            .expect("failed parsing fake bot api url");
+
+        Bot::new("test-token").set_api_url(url)
+    }
+
+    /// Answer every later call of `method` with `result` instead of the default one.
+    pub(crate) fn respond_with(&self, method: &str, result: Value) {
+        self.state
+            .results
+            .lock()
+            .unwrap()
+            .insert(method.to_string(), result);
+    }
+
+    pub(crate) fn calls(&self) -> Vec<Call> {
+        self.state.calls.lock().unwrap().clone()
+    }
+
+    pub(crate) fn calls_to(&self, method: &str) -> Vec<Call> {
+        self.calls()
+            .into_iter()
+            .filter(|call| call.method == method)
+            .collect()
+    }
+
+    pub(crate) fn sent_messages(&self) -> Vec<Call> {
+        self.calls_to("sendMessage")
+    }
+
+    /// Ids of deleted messages.
+    pub(crate) fn deleted(&self) -> Vec<i32> {
+        self.calls_to("deleteMessage")
+            .iter()
+            .filter_map(Call::message_id)
+            .collect()
+    }
+
+    /// Run an update through the bot's handler tree, like the dispatcher does.
+    /// Returns what the dispatcher would pass to its error handler, unhandled updates are ok.
+    pub(crate) async fn dispatch(
+        &self,
+        handler: UpdateHandler<HandlerTreeError>,
+        update: Update,
+    ) -> Result<(), HandlerTreeError> {
+        let bot = self.bot();
+        let me: Me = bot.get_me().await.expect("fake getMe failed");
+
+        match handler
+            .dispatch(teloxide::dptree::deps![bot, me, update])
+            .await
+        {
+            ControlFlow::Break(ret) => ret,
+            ControlFlow::Continue(_) => Ok(()),
+        }
+    }
+}
+
+async fn call(
+    State(state): State<Arc<FakeState>>,
+    Path((_token, method)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Json<Value> {
+    // method names are case insensitive, teloxide sends them capitalized
+    let mut method = method;
+    if let Some(first) = method.get_mut(0..1) {
+        first.make_ascii_lowercase();
+    }
+
+    let body = parse_body(&headers, &body);
+    state.calls.lock().unwrap().push(Call {
+        method: method.clone(),
+        body: body.clone(),
+    });
+
+    let result = state.results.lock().unwrap().get(&method).cloned();
+    let result = result.unwrap_or_else(|| default_result(&state, &method, &body));
+
+    Json(json!({ "ok": true, "result": result }))
+}
+
+fn parse_body(headers: &HeaderMap, body: &Bytes) -> Value {
+    let content_type = headers
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default();
+
+    match content_type.split_once("boundary=") {
+        Some((_, boundary)) => parse_multipart(boundary, body),
+        None => serde_json::from_slice(body).unwrap_or(Value::Null),
+    }
+}
+
+// only text fields are kept, files are recorded by their field name with their size
+fn parse_multipart(boundary: &str, body: &Bytes) -> Value {
+    let body = String::from_utf8_lossy(body);
+    let mut fields = serde_json::Map::new();
+
+    for part in body.split(&format!("--{}", boundary)) {
+        let Some((head, content)) = part.split_once("\r\n\r\n") else {
+            continue;
+        };
+        let Some(name) = head
+            .split("name=\"")
+            .nth(1)
+            .and_then(|rest| rest.split('"').next())
+        else {
+            continue;
+        };
+        let content = content.strip_suffix("\r\n").unwrap_or(content);
+
+        let value = if head.contains("filename=") {
+            json!({ "file_size": content.len() })
+        } else {
+            serde_json::from_str(content).unwrap_or_else(|_| Value::String(content.into()))
+        };
+        fields.insert(name.to_string(), value);
+    }
+
+    Value::Object(fields)
+}
+
+fn default_result(state: &FakeState, method: &str, body: &Value) -> Value {
+    match method {
+        "getMe" => json!({
+            "id": BOT_ID,
+            "is_bot": true,
+            "first_name": "Kartel",
+            "username": BOT_USERNAME,
+            "can_join_groups": true,
+            "can_read_all_group_messages": false,
+            "supports_inline_queries": true,
+            "can_connect_to_business": false,
+            "has_main_web_app": false,
+        }),
+
+        method if method.starts_with("send") || method == "editMessageText" => {
+            let message_id = if method == "editMessageText" {
+                as_i64(&body["message_id"]).unwrap_or_default() as i32
+            } else {
+                state.next_message_id.fetch_add(1, Ordering::Relaxed)
+            };
+
+            let mut message = json!({
+                "message_id": message_id,
+                "date": Utc::now().timestamp(),
+                "chat": { "id": as_i64(&body["chat_id"]).unwrap_or_default(), "type": "private", "first_name": "Fake" },
+                "from": { "id": BOT_ID, "is_bot": true, "first_name": "Kartel", "username": BOT_USERNAME },
+            });
+            if let Some(text) = body["text"].as_str() {
+                message["text"] = text.into();
+            }
+
+            message
+        }
+
+        _ => Value::Bool(true),
+    }
+}
+
+/// Text message from user `user_id` in a private chat with the same id.
+pub(crate) fn private_message(message_id: i32, user_id: i64, text: &str) -> Value {
+    json!({
+        "message_id": message_id,
+        "date": Utc::now().timestamp(),
+        "chat": { "id": user_id, "type": "private", "first_name": "Tester" },
+        "from": user(user_id),
+        "text": text,
+    })
+}
+
+/// Text message from user `user_id` in a supergroup.
+pub(crate) fn group_message(message_id: i32, chat_id: i64, user_id: i64, text: &str) -> Value {
+    json!({
+        "message_id": message_id,
+        "date": Utc::now().timestamp(),
+        "chat": { "id": chat_id, "type": "supergroup", "title": "Test Group" },
+        "from": user(user_id),
+        "text": text,
+    })
+}
+
+pub(crate) fn user(user_id: i64) -> Value {
+    json!({
+        "id": user_id,
+        "is_bot": false,
+        "first_name": "Tester",
+        "language_code": "en",
+    })
+}
+
+/// Make `message` a reply to `replied`.
+pub(crate) fn replying_to(mut message: Value, replied: Value) -> Value {
+    message["reply_to_message"] = replied;
+    message
+}
+
+pub(crate) fn message_update(update_id: i32, message: Value) -> Update {
+    // update keys are borrowed while deserializing, which only works from a string
+    let update = json!({ "update_id": update_id, "message": message }).to_string();
+
+    serde_json::from_str(&update).expect("invalid test message update")
+}
+
+/// `message` edited by its sender, as sent when someone fixes a typo in a command.
+pub(crate) fn edited_message_update(update_id: i32, mut message: Value) -> Update {
+    message["edit_date"] = Utc::now().timestamp().into();
+    let update = json!({ "update_id": update_id, "edited_message": message }).to_string();
+
+    serde_json::from_str(&update).expect("invalid test edited message update")
+}
+
+/// Press of an inline keyboard button carrying `data`, attached to `message`, by user `user_id`.
+pub(crate) fn callback_update(update_id: i32, user_id: i64, message: Value, data: &str) -> Update {
+    let update = json!({
+        "update_id": update_id,
+        "callback_query": {
+            "id": format!("callback-{}", update_id),
+            "from": user(user_id),
+            "message": message,
+            "chat_instance": "1",
+            "data": data,
+        },
+    })
+    .to_string();
+
+    serde_json::from_str(&update).expect("invalid test callback update")
+}
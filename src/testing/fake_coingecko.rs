@@ -0,0 +1,185 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::{Value, json};
+
+tokio::task_local! {
+    static BASE_URL: String;
+}
+
+// when latest prices were set, 2024-03-08T00:00:00Z
+pub(crate) const LAST_UPDATED_AT: i64 = 1_709_856_000;
+
+/// Base url of the fake serving the current task, see `FakeCoinGeckoApi::scope`.
+pub(crate) fn scoped_base_url() -> Option<String> {
+    BASE_URL.try_with(Clone::clone).ok()
+}
+
+// USD price of each coin, the same for every date
+fn usd_prices() -> BTreeMap<&'static str, Decimal> {
+    BTreeMap::from([
+        ("bitcoin", dec!(68000)),
+        ("ethereum", dec!(3500)),
+        ("tether", dec!(1)),
+        ("dogecoin", dec!(0.15)),
+        ("solana", dec!(150)),
+        ("pepe", dec!(0.0000012)),
+    ])
+}
+
+// units of each fiat per USD
+fn fiat_rates() -> BTreeMap<&'static str, Decimal> {
+    BTreeMap::from([("usd", dec!(1)), ("idr", dec!(15700)), ("eur", dec!(0.92))])
+}
+
+/// In-process stand-in of the coingecko API, pricing a few coins from a fixed table.
+pub(crate) struct FakeCoinGeckoApi {
+    addr: SocketAddr,
+    state: Arc<CoinGeckoState>,
+}
+
+#[derive(Default)]
+struct CoinGeckoState {
+    down: AtomicBool,
+    requests: Mutex<Vec<String>>,
+}
+
+impl FakeCoinGeckoApi {
+    pub(crate) async fn start() -> Self {
+        let state = Arc::new(CoinGeckoState::default());
+        let app = Router::new()
+            .route("/simple/price", get(simple_price))
+            .route("/coins/:id/history", get(history))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed binding fake coingecko api");
+        let addr = listener
+            .local_addr()
+            .expect("failed reading fake coingecko api address");
+        tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("failed serving fake coingecko api");
+        });
+
+        FakeCoinGeckoApi { addr, state }
+    }
+
+    pub(crate) fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answer every request with HTTP 500.
+    pub(crate) fn set_down(&self, down: bool) {
+        self.state.down.store(down, Ordering::Relaxed);
+    }
+
+    /// Paths with query of received requests, in order.
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// Run `fut` with the crypto source pointing to this fake.
+    pub(crate) async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        BASE_URL.scope(self.base_url(), fut).await
+    }
+}
+
+impl CoinGeckoState {
+    // records the request, and whether to answer it
+    fn receive(&self, path: &str, query: &BTreeMap<String, String>) -> bool {
+        let query_string: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+        self.requests
+            .lock()
+            .unwrap()
+            .push(format!("{}?{}", path, query_string.join("&")));
+
+        !self.down.load(Ordering::Relaxed)
+    }
+}
+
+fn price(id: &str, vs: &str) -> Option<Decimal> {
+    Some(usd_prices().get(id)? * fiat_rates().get(vs)?)
+}
+
+// unknown coins and currencies are left out, like the real API does
+async fn simple_price(
+    State(state): State<Arc<CoinGeckoState>>,
+    Query(query): Query<BTreeMap<String, String>>,
+) -> Response {
+    if !state.receive("/simple/price", &query) {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "down").into_response();
+    }
+
+    let ids = query.get("ids").map(String::as_str).unwrap_or_default();
+    let vs_currencies = query
+        .get("vs_currencies")
+        .map(String::as_str)
+        .unwrap_or_default();
+
+    let mut body = serde_json::Map::new();
+    for id in ids.split(',') {
+        let mut prices = serde_json::Map::new();
+        for vs in vs_currencies.split(',') {
+            if let Some(price) = price(id, vs) {
+                prices.insert(vs.to_string(), number(price));
+            }
+        }
+        if !prices.is_empty() {
+            prices.insert("last_updated_at".into(), json!(LAST_UPDATED_AT));
+            body.insert(id.to_string(), Value::Object(prices));
+        }
+    }
+
+    Json(Value::Object(body)).into_response()
+}
+
+async fn history(
+    State(state): State<Arc<CoinGeckoState>>,
+    Path(id): Path<String>,
+    Query(query): Query<BTreeMap<String, String>>,
+) -> Response {
+    if !state.receive(&format!("/coins/{}/history", id), &query) {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "down").into_response();
+    }
+
+    let mut current_price = serde_json::Map::new();
+    for vs in fiat_rates().keys() {
+        if let Some(price) = price(&id, vs) {
+            current_price.insert(vs.to_string(), number(price));
+        }
+    }
+    if current_price.is_empty() {
+        return (
+            StatusCode::NOT_FOUND,
+            Json(json!({ "error": "coin not found" })),
+        )
+            .into_response();
+    }
+
+    Json(json!({
+        "id": id,
+        "market_data": { "current_price": current_price },
+    }))
+    .into_response()
+}
+
+// the real API answers floats
+fn number(value: Decimal) -> Value {
+    let value = value.normalize().to_string();
+    serde_json::from_str(&value).expect("decimal is a json number")
+}
@@ -0,0 +1,164 @@
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::{Value, json};
+
+tokio::task_local! {
+    static BASE_URL: String;
+}
+
+// date of latest rates
+pub(crate) const LATEST: &str = "2024-03-08";
+
+/// Base url of the fake serving the current task, see `FakeFrankfurterApi::scope`.
+pub(crate) fn scoped_base_url() -> Option<String> {
+    BASE_URL.try_with(Clone::clone).ok()
+}
+
+// units of each currency per USD, the same for every date
+fn usd_rates() -> BTreeMap<&'static str, Decimal> {
+    BTreeMap::from([
+        ("USD", dec!(1)),
+        ("IDR", dec!(15700)),
+        ("EUR", dec!(0.92)),
+        ("JPY", dec!(147.5)),
+        ("SGD", dec!(1.34)),
+    ])
+}
+
+/// In-process stand-in of the frankfurter API, computing answers from a small fixed table of rates.
+pub(crate) struct FakeFrankfurterApi {
+    addr: SocketAddr,
+    state: Arc<FrankfurterState>,
+}
+
+#[derive(Default)]
+struct FrankfurterState {
+    down: AtomicBool,
+    requests: Mutex<Vec<String>>,
+}
+
+impl FakeFrankfurterApi {
+    pub(crate) async fn start() -> Self {
+        let state = Arc::new(FrankfurterState::default());
+        let app = Router::new()
+            .route("/:date", get(rates))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed binding fake frankfurter api");
+        let addr = listener
+            .local_addr()
+            .expect("failed reading fake frankfurter api address");
+        tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("failed serving fake frankfurter api");
+        });
+
+        FakeFrankfurterApi { addr, state }
+    }
+
+    pub(crate) fn base_url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// Answer every request with HTTP 500.
+    pub(crate) fn set_down(&self, down: bool) {
+        self.state.down.store(down, Ordering::Relaxed);
+    }
+
+    /// Paths with query of received requests, in order.
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// Run `fut` with the fallback source pointing to this fake.
+    pub(crate) async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        BASE_URL.scope(self.base_url(), fut).await
+    }
+}
+
+async fn rates(
+    State(state): State<Arc<FrankfurterState>>,
+    Path(date): Path<String>,
+    Query(query): Query<BTreeMap<String, String>>,
+) -> Response {
+    let query_string: Vec<String> = query.iter().map(|(k, v)| format!("{}={}", k, v)).collect();
+    state
+        .requests
+        .lock()
+        .unwrap()
+        .push(format!("/{}?{}", date, query_string.join("&")));
+
+    if state.down.load(Ordering::Relaxed) {
+        return (StatusCode::INTERNAL_SERVER_ERROR, "down").into_response();
+    }
+
+    let date = if date == "latest" {
+        LATEST.into()
+    } else {
+        date
+    };
+    let usd_rates = usd_rates();
+    let base = query.get("from").map(String::as_str).unwrap_or("EUR");
+    let amount: Decimal = match query.get("amount").map(|amount| amount.parse()) {
+        Some(Ok(amount)) => amount,
+        Some(Err(_)) => return not_found(),
+        None => Decimal::ONE,
+    };
+    let Some(base_rate) = usd_rates.get(base) else {
+        return not_found();
+    };
+
+    let mut rates = serde_json::Map::new();
+    for (code, rate) in &usd_rates {
+        let wanted = match query.get("to") {
+            Some(to) => to.split(',').any(|to| to == *code),
+            None => code != &base,
+        };
+        if wanted {
+            rates.insert(code.to_string(), number(amount * rate / base_rate));
+        }
+    }
+    if let Some(to) = query.get("to")
+        && rates.len() != to.split(',').count()
+    {
+        return not_found();
+    }
+
+    Json(json!({
+        "amount": number(amount),
+        "base": base,
+        "date": date,
+        "rates": rates,
+    }))
+    .into_response()
+}
+
+fn not_found() -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({ "message": "not found" })),
+    )
+        .into_response()
+}
+
+// the real API answers floats
+fn number(value: Decimal) -> Value {
+    let value = value.round_dp(6).normalize().to_string();
+    serde_json::from_str(&value).expect("decimal is a json number")
+}
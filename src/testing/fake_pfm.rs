@@ -0,0 +1,219 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::future::Future;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use axum::{
+    Json, Router,
+    extract::{Path, Query, State},
+    http::{StatusCode, header},
+    response::{IntoResponse, Response},
+    routing::get,
+};
+use serde_json::{Value, json};
+
+use crate::config::config;
+use crate::deps::http_client::http_client;
+
+tokio::task_local! {
+    static BASE_URL: String;
+}
+
+// refresh fixtures from the real API with KARTEL_PFM_RECORD=1 cargo test
+const RECORD_ENV: &str = "KARTEL_PFM_RECORD";
+
+const FIXTURES_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/pfm");
+
+/// Base url of the fake serving the current task, see `FakePfmApi::scope`.
+pub(crate) fn scoped_base_url() -> Option<String> {
+    BASE_URL.try_with(Clone::clone).ok()
+}
+
+/// How the fake answers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Mode {
+    /// Recorded fixture of the request.
+    Replay,
+
+    /// Forward to the real API, and overwrite the fixture with a successful answer.
+    Record,
+
+    /// HTTP 500 with a plain text body.
+    ServerError,
+
+    /// HTTP 200 with a truncated json body.
+    MalformedJson,
+
+    /// HTTP 200 with only the `error` field set, like upstream rejecting a currency.
+    ErrorField(String),
+
+    /// Replay after a delay.
+    Slow(Duration),
+}
+
+/// In-process stand-in of the pfm forex API, serving recorded responses from `tests/fixtures/pfm`.
+pub(crate) struct FakePfmApi {
+    addr: SocketAddr,
+    state: Arc<PfmState>,
+}
+
+struct PfmState {
+    mode: Mutex<Mode>,
+    requests: Mutex<Vec<String>>,
+
+    // statuses answered before falling back to the mode, oldest first
+    failures: Mutex<VecDeque<StatusCode>>,
+}
+
+impl FakePfmApi {
+    pub(crate) async fn start() -> Self {
+        let mode = if std::env::var(RECORD_ENV).is_ok_and(|value| value == "1") {
+            Mode::Record
+        } else {
+            Mode::Replay
+        };
+        let state = Arc::new(PfmState {
+            mode: Mutex::new(mode),
+            requests: Mutex::new(vec![]),
+            failures: Mutex::new(VecDeque::new()),
+        });
+        let app = Router::new()
+            .route("/pfm/v2/forex/:endpoint", get(endpoint))
+            .with_state(state.clone());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("failed binding fake pfm api");
+        let addr = listener
+            .local_addr()
+            .expect("failed reading fake pfm api address");
+        tokio::spawn(async move {
+            axum::serve(listener, app)
+                .await
+                .expect("failed serving fake pfm api");
+        });
+
+        FakePfmApi { addr, state }
+    }
+
+    pub(crate) fn base_url(&self) -> String {
+        format!("http://{}/pfm/v2/forex", self.addr)
+    }
+
+    /// Answers are cached by url like real ones, a new mode only applies to requests not served from cache.
+    pub(crate) fn set_mode(&self, mode: Mode) {
+        *self.state.mode.lock().unwrap() = mode;
+    }
+
+    /// Answer the next `count` requests with a bare `status`, whatever the mode.
+    pub(crate) fn fail_next(&self, count: usize, status: StatusCode) {
+        self.state
+            .failures
+            .lock()
+            .unwrap()
+            .extend(std::iter::repeat_n(status, count));
+    }
+
+    /// Fixture names of received requests, in order.
+    pub(crate) fn requests(&self) -> Vec<String> {
+        self.state.requests.lock().unwrap().clone()
+    }
+
+    /// Run `fut` with forex endpoints pointing to this fake.
+    pub(crate) async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        BASE_URL.scope(self.base_url(), fut).await
+    }
+}
+
+async fn endpoint(
+    State(state): State<Arc<PfmState>>,
+    Path(endpoint): Path<String>,
+    Query(query): Query<BTreeMap<String, String>>,
+) -> Response {
+    let name = fixture_name(&endpoint, &query);
+    state.requests.lock().unwrap().push(name.clone());
+
+    if let Some(status) = state.failures.lock().unwrap().pop_front() {
+        return (status, status.to_string()).into_response();
+    }
+
+    let mode = state.mode.lock().unwrap().clone();
+    match mode {
+        Mode::Replay => replay(&name),
+        Mode::Record => record(&endpoint, &query, &name).await,
+        Mode::ServerError => {
+            (StatusCode::INTERNAL_SERVER_ERROR, "internal server error").into_response()
+        }
+        Mode::MalformedJson => (
+            [(header::CONTENT_TYPE, "application/json")],
+            r#"{"data": {"date": "2024-03-08T00:00:00Z", "fr"#,
+        )
+            .into_response(),
+        Mode::ErrorField(err) => Json(json!({ "error": err })).into_response(),
+        Mode::Slow(delay) => {
+            tokio::time::sleep(delay).await;
+            replay(&name)
+        }
+    }
+}
+
+/// File name of a recorded answer, e.g. `convert_from-usd-1_to-idr.json` for `convert?from=USD 1&to=IDR`.
+pub(crate) fn fixture_name(endpoint: &str, query: &BTreeMap<String, String>) -> String {
+    let mut name = endpoint.to_string();
+    for (key, value) in query {
+        name.push('_');
+        name.push_str(&slug(&format!("{}-{}", key, value)));
+    }
+    name.push_str(".json");
+
+    name
+}
+
+fn slug(text: &str) -> String {
+    let mut ret = String::with_capacity(text.len());
+    for ch in text.to_ascii_lowercase().chars() {
+        if ch.is_ascii_alphanumeric() || ch == '.' {
+            ret.push(ch);
+        } else if !ret.ends_with('-') {
+            ret.push('-');
+        }
+    }
+
+    ret.trim_matches('-').to_string()
+}
+
+fn replay(name: &str) -> Response {
+    match std::fs::read_to_string(format!("{}/{}", FIXTURES_DIR, name)) {
+        Ok(body) => ([(header::CONTENT_TYPE, "application/json")], body).into_response(),
+        Err(_) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("no fixture {}, record it with {}=1", name, RECORD_ENV),
+        )
+            .into_response(),
+    }
+}
+
+async fn record(endpoint: &str, query: &BTreeMap<String, String>, name: &str) -> Response {
+    let url = format!(
+        "{}/{}",
+        config().forex_api_url.trim_end_matches('/'),
+        endpoint
+    );
+    let resp = match http_client().get(&url).query(query).send().await {
+        Ok(resp) => resp,
+        Err(err) => return (StatusCode::BAD_GATEWAY, err.to_string()).into_response(),
+    };
+    let status = resp.status();
+    let body = resp.text().await.unwrap_or_default();
+
+    if status.is_success()
+        && let Ok(value) = serde_json::from_str::<Value>(&body)
+    {
+        let pretty = serde_json::to_string_pretty(&value).expect("failed formatting fixture");
+        std::fs::write(format!("{}/{}", FIXTURES_DIR, name), pretty + "\n")
+            .expect("failed writing fixture");
+    }
+
+    (status, body).into_response()
+}
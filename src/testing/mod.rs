@@ -0,0 +1,5 @@
+//! testing holds in-process fakes of the services the bot talks to, for tests going from an update to the replies.
+pub(crate) mod fake_bot;
+pub(crate) mod fake_coingecko;
+pub(crate) mod fake_frankfurter;
+pub(crate) mod fake_pfm;
@@ -0,0 +1,632 @@
+//! Registry of currencies the bot knows: ISO 4217, precious metals and supported crypto.
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::config::config;
+use crate::i18n::{Lang, Localized};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CurrencyKind {
+    Fiat,
+
+    // ISO 4217 fund codes and units of account, not circulating money
+    Fund,
+    Metal,
+    Crypto,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Currency {
+    pub code: &'static str,
+    pub name: &'static str,
+    pub kind: CurrencyKind,
+
+    // decimals of the minor unit. ISO lists none for metals and units of account, those are display precisions.
+    pub minor_units: u32,
+    pub symbol: &'static str,
+
+    // other names members may type, lowercase. English or Indonesian names and slang, each picking this currency
+    // over others going by it.
+    pub aliases: &'static [&'static str],
+}
+
+const fn fiat(
+    code: &'static str,
+    name: &'static str,
+    minor_units: u32,
+    symbol: &'static str,
+) -> Currency {
+    Currency {
+        code,
+        name,
+        kind: CurrencyKind::Fiat,
+        minor_units,
+        symbol,
+        aliases: &[],
+    }
+}
+
+const fn fund(code: &'static str, name: &'static str, minor_units: u32) -> Currency {
+    Currency {
+        code,
+        name,
+        kind: CurrencyKind::Fund,
+        minor_units,
+        symbol: code,
+        aliases: &[],
+    }
+}
+
+const fn crypto(
+    code: &'static str,
+    name: &'static str,
+    minor_units: u32,
+    symbol: &'static str,
+) -> Currency {
+    Currency {
+        code,
+        name,
+        kind: CurrencyKind::Crypto,
+        minor_units,
+        symbol,
+        aliases: &[],
+    }
+}
+
+const fn with_aliases(mut currency: Currency, aliases: &'static [&'static str]) -> Currency {
+    currency.aliases = aliases;
+    currency
+}
+
+pub(super) static CURRENCIES: &[Currency] = &[
+    with_aliases(fiat("AED", "UAE Dirham", 2, "د.إ"), &["dirham"]),
+    fiat("AFN", "Afghani", 2, "؋"),
+    fiat("ALL", "Lek", 2, "L"),
+    fiat("AMD", "Armenian Dram", 2, "֏"),
+    fiat("ANG", "Netherlands Antillean Guilder", 2, "ƒ"),
+    fiat("AOA", "Kwanza", 2, "Kz"),
+    fiat("ARS", "Argentine Peso", 2, "$"),
+    with_aliases(
+        fiat("AUD", "Australian Dollar", 2, "A$"),
+        &["aussie dollar", "dolar australia"],
+    ),
+    fiat("AWG", "Aruban Florin", 2, "ƒ"),
+    fiat("AZN", "Azerbaijan Manat", 2, "₼"),
+    fiat("BAM", "Convertible Mark", 2, "KM"),
+    fiat("BBD", "Barbados Dollar", 2, "Bds$"),
+    fiat("BDT", "Taka", 2, "৳"),
+    fiat("BGN", "Bulgarian Lev", 2, "лв"),
+    fiat("BHD", "Bahraini Dinar", 3, "BD"),
+    fiat("BIF", "Burundi Franc", 0, "FBu"),
+    fiat("BMD", "Bermudian Dollar", 2, "BD$"),
+    with_aliases(fiat("BND", "Brunei Dollar", 2, "B$"), &["dolar brunei"]),
+    fiat("BOB", "Boliviano", 2, "Bs"),
+    fund("BOV", "Mvdol", 2),
+    fiat("BRL", "Brazilian Real", 2, "R$"),
+    fiat("BSD", "Bahamian Dollar", 2, "B$"),
+    fiat("BTN", "Ngultrum", 2, "Nu."),
+    fiat("BWP", "Pula", 2, "P"),
+    fiat("BYN", "Belarusian Ruble", 2, "Br"),
+    fiat("BZD", "Belize Dollar", 2, "BZ$"),
+    with_aliases(fiat("CAD", "Canadian Dollar", 2, "C$"), &["dolar kanada"]),
+    fiat("CDF", "Congolese Franc", 2, "FC"),
+    fund("CHE", "WIR Euro", 2),
+    with_aliases(
+        fiat("CHF", "Swiss Franc", 2, "CHF"),
+        &["franc", "franc swiss"],
+    ),
+    fund("CHW", "WIR Franc", 2),
+    fund("CLF", "Unidad de Fomento", 4),
+    fiat("CLP", "Chilean Peso", 0, "$"),
+    with_aliases(
+        fiat("CNY", "Yuan Renminbi", 2, "¥"),
+        &["yuan", "renminbi", "rmb", "yuan china"],
+    ),
+    fiat("COP", "Colombian Peso", 2, "$"),
+    fund("COU", "Unidad de Valor Real", 2),
+    fiat("CRC", "Costa Rican Colon", 2, "₡"),
+    fiat("CUP", "Cuban Peso", 2, "$"),
+    fiat("CVE", "Cabo Verde Escudo", 2, "Esc"),
+    fiat("CZK", "Czech Koruna", 2, "Kč"),
+    fiat("DJF", "Djibouti Franc", 0, "Fdj"),
+    fiat("DKK", "Danish Krone", 2, "kr"),
+    fiat("DOP", "Dominican Peso", 2, "RD$"),
+    fiat("DZD", "Algerian Dinar", 2, "DA"),
+    fiat("EGP", "Egyptian Pound", 2, "E£"),
+    fiat("ERN", "Nakfa", 2, "Nfk"),
+    fiat("ETB", "Ethiopian Birr", 2, "Br"),
+    with_aliases(fiat("EUR", "Euro", 2, "€"), &["euro"]),
+    fiat("FJD", "Fiji Dollar", 2, "FJ$"),
+    fiat("FKP", "Falkland Islands Pound", 2, "£"),
+    with_aliases(
+        fiat("GBP", "Pound Sterling", 2, "£"),
+        &["pound", "sterling", "poundsterling", "quid", "pon", "£"],
+    ),
+    fiat("GEL", "Lari", 2, "₾"),
+    fiat("GHS", "Ghana Cedi", 2, "GH₵"),
+    fiat("GIP", "Gibraltar Pound", 2, "£"),
+    fiat("GMD", "Dalasi", 2, "D"),
+    fiat("GNF", "Guinean Franc", 0, "FG"),
+    fiat("GTQ", "Quetzal", 2, "Q"),
+    fiat("GYD", "Guyana Dollar", 2, "G$"),
+    with_aliases(
+        fiat("HKD", "Hong Kong Dollar", 2, "HK$"),
+        &["dolar hong kong"],
+    ),
+    fiat("HNL", "Lempira", 2, "L"),
+    fiat("HTG", "Gourde", 2, "G"),
+    fiat("HUF", "Forint", 2, "Ft"),
+    with_aliases(
+        fiat("IDR", "Rupiah", 2, "Rp"),
+        &["rupiah", "rupiah indonesia"],
+    ),
+    fiat("ILS", "New Israeli Sheqel", 2, "₪"),
+    with_aliases(
+        fiat("INR", "Indian Rupee", 2, "₹"),
+        &["rupee", "rupee india"],
+    ),
+    fiat("IQD", "Iraqi Dinar", 3, "ع.د"),
+    fiat("IRR", "Iranian Rial", 2, "﷼"),
+    fiat("ISK", "Iceland Krona", 0, "kr"),
+    fiat("JMD", "Jamaican Dollar", 2, "J$"),
+    fiat("JOD", "Jordanian Dinar", 3, "JD"),
+    with_aliases(fiat("JPY", "Yen", 0, "¥"), &["yen", "yen jepang", "¥"]),
+    fiat("KES", "Kenyan Shilling", 2, "KSh"),
+    fiat("KGS", "Som", 2, "с"),
+    fiat("KHR", "Riel", 2, "៛"),
+    fiat("KMF", "Comorian Franc", 0, "CF"),
+    fiat("KPW", "North Korean Won", 2, "₩"),
+    with_aliases(fiat("KRW", "Won", 0, "₩"), &["won", "won korea", "₩"]),
+    fiat("KWD", "Kuwaiti Dinar", 3, "KD"),
+    fiat("KYD", "Cayman Islands Dollar", 2, "CI$"),
+    fiat("KZT", "Tenge", 2, "₸"),
+    fiat("LAK", "Lao Kip", 2, "₭"),
+    fiat("LBP", "Lebanese Pound", 2, "L£"),
+    fiat("LKR", "Sri Lanka Rupee", 2, "Rs"),
+    fiat("LRD", "Liberian Dollar", 2, "L$"),
+    fiat("LSL", "Loti", 2, "L"),
+    fiat("LYD", "Libyan Dinar", 3, "LD"),
+    fiat("MAD", "Moroccan Dirham", 2, "DH"),
+    fiat("MDL", "Moldovan Leu", 2, "L"),
+    fiat("MGA", "Malagasy Ariary", 2, "Ar"),
+    fiat("MKD", "Denar", 2, "ден"),
+    fiat("MMK", "Kyat", 2, "K"),
+    fiat("MNT", "Tugrik", 2, "₮"),
+    fiat("MOP", "Pataca", 2, "MOP$"),
+    fiat("MRU", "Ouguiya", 2, "UM"),
+    fiat("MUR", "Mauritius Rupee", 2, "Rs"),
+    fiat("MVR", "Rufiyaa", 2, "Rf"),
+    fiat("MWK", "Malawi Kwacha", 2, "MK"),
+    fiat("MXN", "Mexican Peso", 2, "$"),
+    fund("MXV", "Mexican Unidad de Inversion", 2),
+    with_aliases(
+        fiat("MYR", "Malaysian Ringgit", 2, "RM"),
+        &["ringgit", "ringgit malaysia"],
+    ),
+    fiat("MZN", "Mozambique Metical", 2, "MT"),
+    fiat("NAD", "Namibia Dollar", 2, "N$"),
+    fiat("NGN", "Naira", 2, "₦"),
+    fiat("NIO", "Cordoba Oro", 2, "C$"),
+    fiat("NOK", "Norwegian Krone", 2, "kr"),
+    fiat("NPR", "Nepalese Rupee", 2, "Rs"),
+    with_aliases(
+        fiat("NZD", "New Zealand Dollar", 2, "NZ$"),
+        &["kiwi dollar", "dolar selandia baru"],
+    ),
+    fiat("OMR", "Rial Omani", 3, "RO"),
+    fiat("PAB", "Balboa", 2, "B/."),
+    fiat("PEN", "Sol", 2, "S/"),
+    fiat("PGK", "Kina", 2, "K"),
+    with_aliases(
+        fiat("PHP", "Philippine Peso", 2, "₱"),
+        &["peso", "peso filipina"],
+    ),
+    fiat("PKR", "Pakistan Rupee", 2, "Rs"),
+    fiat("PLN", "Zloty", 2, "zł"),
+    fiat("PYG", "Guarani", 0, "₲"),
+    fiat("QAR", "Qatari Rial", 2, "QR"),
+    fiat("RON", "Romanian Leu", 2, "lei"),
+    fiat("RSD", "Serbian Dinar", 2, "din"),
+    fiat("RUB", "Russian Ruble", 2, "₽"),
+    fiat("RWF", "Rwanda Franc", 0, "FRw"),
+    with_aliases(
+        fiat("SAR", "Saudi Riyal", 2, "SR"),
+        &["riyal", "riyal saudi"],
+    ),
+    fiat("SBD", "Solomon Islands Dollar", 2, "SI$"),
+    fiat("SCR", "Seychelles Rupee", 2, "SRe"),
+    fiat("SDG", "Sudanese Pound", 2, "LS"),
+    fiat("SEK", "Swedish Krona", 2, "kr"),
+    with_aliases(
+        fiat("SGD", "Singapore Dollar", 2, "S$"),
+        &["dolar singapura", "sing dollar"],
+    ),
+    fiat("SHP", "Saint Helena Pound", 2, "£"),
+    fiat("SLE", "Leone", 2, "Le"),
+    fiat("SOS", "Somali Shilling", 2, "Sh"),
+    fiat("SRD", "Surinam Dollar", 2, "$"),
+    fiat("SSP", "South Sudanese Pound", 2, "SSP"),
+    fiat("STN", "Dobra", 2, "Db"),
+    fiat("SVC", "El Salvador Colon", 2, "₡"),
+    fiat("SYP", "Syrian Pound", 2, "LS"),
+    fiat("SZL", "Lilangeni", 2, "E"),
+    with_aliases(fiat("THB", "Baht", 2, "฿"), &["baht", "baht thailand"]),
+    fiat("TJS", "Somoni", 2, "SM"),
+    fiat("TMT", "Turkmenistan New Manat", 2, "m"),
+    fiat("TND", "Tunisian Dinar", 3, "DT"),
+    fiat("TOP", "Pa'anga", 2, "T$"),
+    fiat("TRY", "Turkish Lira", 2, "₺"),
+    fiat("TTD", "Trinidad and Tobago Dollar", 2, "TT$"),
+    with_aliases(
+        fiat("TWD", "New Taiwan Dollar", 2, "NT$"),
+        &["dolar taiwan"],
+    ),
+    fiat("TZS", "Tanzanian Shilling", 2, "TSh"),
+    fiat("UAH", "Hryvnia", 2, "₴"),
+    fiat("UGX", "Uganda Shilling", 0, "USh"),
+    with_aliases(
+        fiat("USD", "US Dollar", 2, "US$"),
+        &["greenback", "us dollar", "dolar as", "dolar amerika"],
+    ),
+    fund("USN", "US Dollar (Next day)", 2),
+    fund("UYI", "Uruguay Peso en Unidades Indexadas", 0),
+    fiat("UYU", "Peso Uruguayo", 2, "$U"),
+    fund("UYW", "Unidad Previsional", 4),
+    fiat("UZS", "Uzbekistan Sum", 2, "soʻm"),
+    fiat("VED", "Bolívar Soberano", 2, "Bs.D"),
+    fiat("VES", "Bolívar Soberano", 2, "Bs.S"),
+    with_aliases(fiat("VND", "Dong", 0, "₫"), &["dong vietnam"]),
+    fiat("VUV", "Vatu", 0, "VT"),
+    fiat("WST", "Tala", 2, "WS$"),
+    fiat("XAF", "CFA Franc BEAC", 0, "FCFA"),
+    fund("XBA", "Bond Markets Unit European Composite Unit", 2),
+    fund("XBB", "Bond Markets Unit European Monetary Unit", 2),
+    fund("XBC", "Bond Markets Unit European Unit of Account 9", 2),
+    fund("XBD", "Bond Markets Unit European Unit of Account 17", 2),
+    fiat("XCD", "East Caribbean Dollar", 2, "EC$"),
+    fiat("XCG", "Caribbean Guilder", 2, "Cg"),
+    fund("XDR", "SDR (Special Drawing Right)", 2),
+    fiat("XOF", "CFA Franc BCEAO", 0, "CFA"),
+    fiat("XPF", "CFP Franc", 0, "₣"),
+    fund("XSU", "Sucre", 2),
+    fund("XUA", "ADB Unit of Account", 2),
+    fiat("YER", "Yemeni Rial", 2, "﷼"),
+    fiat("ZAR", "Rand", 2, "R"),
+    fiat("ZMW", "Zambian Kwacha", 2, "ZK"),
+    fiat("ZWG", "Zimbabwe Gold", 2, "ZiG"),
+    // precious metals, per troy ounce
+    Currency {
+        code: "XAU",
+        name: "Gold",
+        kind: CurrencyKind::Metal,
+        minor_units: 2,
+        symbol: "XAU",
+        aliases: &["gold", "emas"],
+    },
+    Currency {
+        code: "XAG",
+        name: "Silver",
+        kind: CurrencyKind::Metal,
+        minor_units: 2,
+        symbol: "XAG",
+        aliases: &["silver", "perak"],
+    },
+    Currency {
+        code: "XPT",
+        name: "Platinum",
+        kind: CurrencyKind::Metal,
+        minor_units: 2,
+        symbol: "XPT",
+        aliases: &["platinum", "platina"],
+    },
+    Currency {
+        code: "XPD",
+        name: "Palladium",
+        kind: CurrencyKind::Metal,
+        minor_units: 2,
+        symbol: "XPD",
+        aliases: &["palladium", "paladium"],
+    },
+    // crypto, precision capped at 8 decimals for display
+    with_aliases(crypto("BTC", "Bitcoin", 8, "₿"), &["bitcoin"]),
+    with_aliases(crypto("ETH", "Ether", 8, "Ξ"), &["ether", "ethereum"]),
+    with_aliases(crypto("USDT", "Tether", 6, "USDT"), &["tether"]),
+    crypto("USDC", "USD Coin", 6, "USDC"),
+    crypto("BNB", "BNB", 8, "BNB"),
+    with_aliases(crypto("SOL", "Solana", 8, "SOL"), &["solana"]),
+    crypto("XRP", "XRP", 6, "XRP"),
+    with_aliases(crypto("DOGE", "Dogecoin", 8, "Ð"), &["dogecoin"]),
+    with_aliases(crypto("ADA", "Cardano", 6, "ADA"), &["cardano"]),
+    with_aliases(crypto("TRX", "TRON", 6, "TRX"), &["tron"]),
+    with_aliases(crypto("TON", "Toncoin", 8, "TON"), &["toncoin"]),
+    with_aliases(crypto("DOT", "Polkadot", 8, "DOT"), &["polkadot"]),
+    with_aliases(crypto("MATIC", "Polygon", 8, "MATIC"), &["polygon"]),
+    with_aliases(crypto("LTC", "Litecoin", 8, "Ł"), &["litecoin"]),
+    crypto("SHIB", "Shiba Inu", 8, "SHIB"),
+    with_aliases(crypto("AVAX", "Avalanche", 8, "AVAX"), &["avalanche"]),
+    with_aliases(crypto("LINK", "Chainlink", 8, "LINK"), &["chainlink"]),
+    crypto("DAI", "Dai", 8, "DAI"),
+    crypto("PEPE", "Pepe", 8, "PEPE"),
+];
+
+static BY_CODE: LazyLock<HashMap<&'static str, &'static Currency>> = LazyLock::new(|| {
+    CURRENCIES
+        .iter()
+        .map(|currency| (currency.code, currency))
+        .collect()
+});
+
+// names, symbols and name endings like "dollar" of every currency going by them
+static BY_NAME: LazyLock<HashMap<String, Vec<&'static Currency>>> = LazyLock::new(|| {
+    let mut ret: HashMap<String, Vec<&'static Currency>> = HashMap::new();
+    for currency in CURRENCIES.iter().filter(|c| c.kind != CurrencyKind::Fund) {
+        let name = normalize(currency.name);
+        let mut keys = vec![name.clone(), normalize(currency.symbol)];
+        if let Some((_, last)) = name.rsplit_once(' ') {
+            keys.push(last.to_string());
+        }
+
+        for key in keys {
+            let currencies = ret.entry(key).or_default();
+            if !currencies.contains(&currency) {
+                currencies.push(currency);
+            }
+        }
+    }
+
+    ret
+});
+
+static BY_ALIAS: LazyLock<HashMap<&'static str, &'static Currency>> = LazyLock::new(|| {
+    CURRENCIES
+        .iter()
+        .flat_map(|currency| currency.aliases.iter().map(move |alias| (*alias, currency)))
+        .collect()
+});
+
+// words for whichever dollar the deployment means by default
+static DOLLAR_WORDS: &[&str] = &["dollar", "dolar", "buck", "$"];
+
+// major currencies listed first among alternatives
+static MAJOR: &[&str] = &[
+    "USD", "EUR", "JPY", "GBP", "CNY", "AUD", "CAD", "CHF", "HKD", "SGD", "NZD",
+];
+
+// most alternatives listed of an ambiguous name
+const MAX_ALTERNATIVES: usize = 5;
+
+/// Shape of a code users may type, from fiat `IDR` to tickers like `MATIC`. Known codes are checked after.
+pub(crate) const CODE_PATTERN: &str = "[a-z][a-z0-9]{1,9}";
+
+static CODE_FORMAT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"(?i)^{}$", CODE_PATTERN)).expect("failed initializing currency regex")
+});
+
+// most suggestions shown for an unknown code
+const MAX_SUGGESTIONS: usize = 3;
+
+/// Currency of a code, case insensitive.
+pub(crate) fn currency(code: &str) -> Option<&'static Currency> {
+    BY_CODE.get(code.to_ascii_uppercase().as_str()).copied()
+}
+
+/// Currency of a code, or an error naming close known codes.
+pub(crate) fn known_currency(code: &str) -> Result<&'static Currency, Localized> {
+    if let Some(currency) = currency(code) {
+        return Ok(currency);
+    }
+
+    let code = code.to_ascii_uppercase();
+    let suggestions = suggest(&code);
+    if suggestions.is_empty() {
+        return Err(Localized::new("currency.unknown").arg("code", code));
+    }
+
+    let suggestions: Vec<String> = suggestions
+        .iter()
+        .map(|currency| format!("{} ({})", currency.code, currency.name))
+        .collect();
+    Err(Localized::new("currency.did_you_mean")
+        .arg("code", code)
+        .arg("suggestions", suggestions.join(", ")))
+}
+
+pub(crate) fn is_crypto(code: &str) -> bool {
+    currency(code).is_some_and(|currency| currency.kind == CurrencyKind::Crypto)
+}
+
+/// A currency read from a code, name, alias or symbol.
+#[derive(Debug, Clone)]
+pub(crate) struct Resolved {
+    pub currency: &'static Currency,
+
+    // what was typed, normalized
+    pub input: String,
+
+    // other currencies going by the same name
+    pub alternatives: Vec<&'static Currency>,
+}
+
+impl Resolved {
+    /// Note telling which currency an ambiguous name was read as.
+    pub(crate) fn note(&self) -> Option<Localized> {
+        if self.alternatives.is_empty() {
+            return None;
+        }
+
+        Some(
+            Localized::new("currency.read_as")
+                .arg("input", &self.input)
+                .arg("code", self.currency.code)
+                .arg("alternatives", list_codes(&self.alternatives)),
+        )
+    }
+}
+
+/// Currency of a code, or of an English or Indonesian name, slang or symbol, case insensitive.
+pub(crate) fn resolve_currency(input: &str) -> Result<Resolved, Localized> {
+    let key = normalize(input);
+    if let Some(currency) = currency(&key) {
+        return Ok(Resolved {
+            currency,
+            input: key,
+            alternatives: vec![],
+        });
+    }
+
+    // plurals, e.g. dollars or bucks
+    let singular = key.strip_suffix('s').filter(|key| key.len() > 1);
+    for candidate in std::iter::once(key.as_str()).chain(singular) {
+        if let Some(ret) = resolve_name(candidate, &key) {
+            return ret;
+        }
+    }
+
+    known_currency(input.trim()).map(|currency| Resolved {
+        currency,
+        input: key,
+        alternatives: vec![],
+    })
+}
+
+// currency going by `key`, none when nothing does
+fn resolve_name(key: &str, input: &str) -> Option<Result<Resolved, Localized>> {
+    let (key, preferred) = if DOLLAR_WORDS.contains(&key) {
+        ("dollar", Some(default_dollar()))
+    } else {
+        (key, BY_ALIAS.get(key).copied())
+    };
+
+    let mut candidates = BY_NAME.get(key).cloned().unwrap_or_default();
+    candidates.sort_by_key(|currency| {
+        let major = MAJOR.iter().position(|code| *code == currency.code);
+        (major.unwrap_or(MAJOR.len()), currency.code)
+    });
+
+    let currency = match (preferred, candidates.as_slice()) {
+        (Some(currency), _) => currency,
+        (None, [currency]) => *currency,
+        (None, []) => return None,
+        (None, _) => {
+            return Some(Err(Localized::new("currency.ambiguous")
+                .arg("input", input)
+                .arg("alternatives", list_codes(&candidates))));
+        }
+    };
+
+    Some(Ok(Resolved {
+        currency,
+        input: input.to_string(),
+        alternatives: candidates
+            .into_iter()
+            .filter(|candidate| *candidate != currency)
+            .collect(),
+    }))
+}
+
+fn default_dollar() -> &'static Currency {
+    currency(&config().default_dollar)
+        .or_else(|| currency("USD"))
+        .expect("USD is in the registry")
+}
+
+fn normalize(input: &str) -> String {
+    input
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+fn list_codes(currencies: &[&'static Currency]) -> String {
+    let mut codes: Vec<&str> = currencies
+        .iter()
+        .take(MAX_ALTERNATIVES)
+        .map(|currency| currency.code)
+        .collect();
+    if currencies.len() > MAX_ALTERNATIVES {
+        codes.push("…");
+    }
+
+    codes.join(", ")
+}
+
+/// Code of a currency argument, keeping in `notes` how an ambiguous name was read.
+///
+/// Input matching nothing that isn't even shaped like a code gets `format_err` instead.
+pub(crate) fn read_currency(
+    input: &str,
+    notes: &mut Vec<Localized>,
+    format_err: impl FnOnce() -> Localized,
+) -> Result<String, Localized> {
+    match resolve_currency(input) {
+        Ok(resolved) => {
+            notes.extend(resolved.note());
+            Ok(resolved.currency.code.to_string())
+        }
+        Err(err) if looks_like_code(input) => Err(err),
+        Err(_) => Err(format_err()),
+    }
+}
+
+/// Whether `input` is shaped like a code, known or not.
+pub(crate) fn looks_like_code(input: &str) -> bool {
+    CODE_FORMAT.is_match(input.trim())
+}
+
+/// Notes of ambiguous currency names in a request, one per line.
+pub(crate) fn render_notes(notes: &[Localized], lang: Lang) -> String {
+    notes
+        .iter()
+        .map(|note| format!("{}\n", note.render(lang)))
+        .collect()
+}
+
+/// Known codes within a typo of `code`, closest first.
+pub(crate) fn suggest(code: &str) -> Vec<&'static Currency> {
+    let code = code.to_ascii_uppercase();
+    // one typo in short codes already reaches a lot of them
+    let max_distance = if code.chars().count() <= 4 { 1 } else { 2 };
+
+    let mut candidates: Vec<(usize, &'static Currency)> = CURRENCIES
+        .iter()
+        .map(|currency| (edit_distance(&code, currency.code), currency))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort_by_key(|(distance, currency)| (*distance, currency.code));
+
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, currency)| currency)
+        .collect()
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and swaps of adjacent characters.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let mut dist = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in dist.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    dist[0] = (0..=b.len()).collect();
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            dist[i][j] = (dist[i - 1][j] + 1)
+                .min(dist[i][j - 1] + 1)
+                .min(dist[i - 1][j - 1] + cost);
+
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                dist[i][j] = dist[i][j].min(dist[i - 2][j - 2] + 1);
+            }
+        }
+    }
+
+    dist[a.len()][b.len()]
+}
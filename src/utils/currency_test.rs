@@ -0,0 +1,105 @@
+use crate::i18n::Lang;
+use crate::utils::currency::{
+    CURRENCIES, CurrencyKind, currency, edit_distance, known_currency, resolve_currency, suggest,
+};
+
+#[test]
+fn registry_lookup() {
+    let idr = currency("idr").unwrap();
+    assert_eq!("IDR", idr.code);
+    assert_eq!("Rupiah", idr.name);
+    assert_eq!("Rp", idr.symbol);
+    assert_eq!(2, idr.minor_units);
+    assert_eq!(CurrencyKind::Fiat, idr.kind);
+
+    assert_eq!(0, currency("JPY").unwrap().minor_units);
+    assert_eq!(3, currency("KWD").unwrap().minor_units);
+    assert_eq!(CurrencyKind::Metal, currency("XAU").unwrap().kind);
+    assert_eq!(8, currency("btc").unwrap().minor_units);
+
+    // testing and no-currency codes are not money
+    assert!(currency("XTS").is_none());
+    assert!(currency("XXX").is_none());
+    assert!(currency("ABC").is_none());
+}
+
+#[test]
+fn distance_counts_swaps_once() {
+    assert_eq!(0, edit_distance("IDR", "IDR"));
+    assert_eq!(1, edit_distance("IRD", "IDR"));
+    assert_eq!(1, edit_distance("USDD", "USD"));
+    assert_eq!(2, edit_distance("ABC", "AED"));
+    assert_eq!(3, edit_distance("", "EUR"));
+}
+
+#[test]
+fn suggestions_are_closest_first() {
+    let codes: Vec<&str> = suggest("ird").iter().map(|c| c.code).collect();
+    assert_eq!("IDR", codes[0]);
+    assert!(codes.len() <= 3);
+
+    assert!(suggest("QQQ").is_empty());
+}
+
+#[test]
+fn unknown_codes_name_suggestions() {
+    assert_eq!(
+        "Unknown currency IRD, did you mean IDR (Rupiah), IQD (Iraqi Dinar), IRR (Iranian Rial)?",
+        known_currency("ird").unwrap_err().render(Lang::En)
+    );
+    assert_eq!(
+        "Mata uang QQQ tidak dikenal.",
+        known_currency("qqq").unwrap_err().render(Lang::Id)
+    );
+}
+
+#[test]
+fn names_aliases_and_symbols_resolve() {
+    let code = |input: &str| resolve_currency(input).unwrap().currency.code;
+
+    assert_eq!("JPY", code("yen"));
+    assert_eq!("IDR", code("Rupiah"));
+    assert_eq!("MYR", code("ringgit"));
+    assert_eq!("XAU", code("emas"));
+    assert_eq!("XAG", code("perak"));
+    assert_eq!("EUR", code("€"));
+    assert_eq!("GBP", code("pounds"));
+    assert_eq!("USD", code("  us   dollar "));
+    assert_eq!("BTC", code("bitcoin"));
+}
+
+#[test]
+fn dollar_reads_as_default_with_alternatives() {
+    let resolved = resolve_currency("bucks").unwrap();
+
+    assert_eq!("USD", resolved.currency.code);
+    assert!(resolved.alternatives.iter().any(|c| c.code == "AUD"));
+    assert_eq!(
+        "<i>Read \"bucks\" as USD, it may also mean AUD, CAD, HKD, SGD, NZD, ….</i>",
+        resolved.note().unwrap().render(Lang::En)
+    );
+
+    // names of a single currency need no note
+    assert!(resolve_currency("yen").unwrap().note().is_none());
+}
+
+#[test]
+fn ambiguous_names_are_rejected() {
+    assert_eq!(
+        "\"kr\" may mean DKK, ISK, NOK, SEK, please use one of the codes.",
+        resolve_currency("kr").unwrap_err().render(Lang::En)
+    );
+    assert_eq!(
+        "Unknown currency QQQ.",
+        resolve_currency("qqq").unwrap_err().render(Lang::En)
+    );
+}
+
+#[test]
+fn aliases_are_unique() {
+    let mut seen = std::collections::HashSet::new();
+    for alias in CURRENCIES.iter().flat_map(|c| c.aliases) {
+        assert!(seen.insert(*alias), "{} is an alias twice", alias);
+        assert!(currency(alias).is_none(), "{} is also a code", alias);
+    }
+}
@@ -0,0 +1,203 @@
+//! Dates members type for historical rates: ISO and local formats, month names in English or Indonesian, and words
+//! relative to today like `kemarin`, `-7d`, `last friday` or `2 weeks ago`.
+use std::sync::LazyLock;
+
+use chrono::{DateTime, Datelike, Days, Months, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use regex::Regex;
+
+use crate::i18n::Localized;
+
+// most words a date takes, `2 minggu yang lalu`
+pub(crate) const MAX_DATE_WORDS: usize = 4;
+
+static RELATIVE_SHORT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^-(\d{1,4})\s*([dwmy])$").expect("failed initializing relative date regex")
+});
+
+static RELATIVE_WORDS: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{1,4}) ([a-z]+) (?:ago|lalu|yang lalu)$")
+        .expect("failed initializing relative date regex")
+});
+
+static LAST_WEEKDAY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:last ([a-z']+)|([a-z']+) (?:lalu|kemarin))$")
+        .expect("failed initializing weekday regex")
+});
+
+static DAY_FIRST: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{1,2})[/.](\d{1,2})[/.](\d{4})$").expect("failed initializing date regex")
+});
+
+static DAY_MONTH_NAME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{1,2}) ([a-z]+)\.? (\d{4})$").expect("failed initializing date regex")
+});
+
+static MONTH_NAME_DAY: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([a-z]+)\.? (\d{1,2}),? (\d{4})$").expect("failed initializing date regex")
+});
+
+static MONTHS: &[&[&str]] = &[
+    &["jan", "januari", "january"],
+    &["feb", "februari", "pebruari", "february"],
+    &["mar", "maret", "march"],
+    &["apr", "april"],
+    &["mei", "may"],
+    &["jun", "juni", "june"],
+    &["jul", "juli", "july"],
+    &["agu", "agt", "agus", "agustus", "aug", "august"],
+    &["sep", "sept", "september"],
+    &["okt", "oktober", "oct", "october"],
+    &["nov", "nopember", "november"],
+    &["des", "desember", "dec", "december"],
+];
+
+static WEEKDAYS: &[(Weekday, &[&str])] = &[
+    (Weekday::Mon, &["mon", "monday", "senin"]),
+    (Weekday::Tue, &["tue", "tuesday", "selasa"]),
+    (Weekday::Wed, &["wed", "wednesday", "rabu"]),
+    (Weekday::Thu, &["thu", "thursday", "kamis"]),
+    (Weekday::Fri, &["fri", "friday", "jumat", "jum'at"]),
+    (Weekday::Sat, &["sat", "saturday", "sabtu"]),
+    (Weekday::Sun, &["sun", "sunday", "minggu", "ahad"]),
+];
+
+/// Today in `tz`, the day relative dates count from.
+pub(crate) fn today(tz: Tz) -> NaiveDate {
+    Utc::now().with_timezone(&tz).date_naive()
+}
+
+/// Date `text` names, counting relative words from `today`. Future dates are read too, callers decide on them.
+pub(crate) fn parse_date(text: &str, today: NaiveDate) -> Option<NaiveDate> {
+    let text = text
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase();
+
+    match text.as_str() {
+        "today" | "now" | "hari ini" | "sekarang" => return Some(today),
+        "yesterday" | "kemarin" => return today.checked_sub_days(Days::new(1)),
+        "kemarin lusa" => return today.checked_sub_days(Days::new(2)),
+        _ => {}
+    }
+
+    if let Ok(date) = NaiveDate::parse_from_str(&text, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    if let Some(caps) = RELATIVE_SHORT.captures(&text) {
+        return ago(today, caps[1].parse().ok()?, &caps[2]);
+    }
+
+    if let Some(caps) = RELATIVE_WORDS.captures(&text) {
+        let unit = match &caps[2] {
+            "day" | "days" | "hari" => "d",
+            "week" | "weeks" | "minggu" | "pekan" => "w",
+            "month" | "months" | "bulan" => "m",
+            "year" | "years" | "tahun" => "y",
+            _ => return None,
+        };
+        return ago(today, caps[1].parse().ok()?, unit);
+    }
+
+    if let Some(caps) = LAST_WEEKDAY.captures(&text) {
+        let name = caps.get(1).or(caps.get(2))?.as_str();
+        let weekday = WEEKDAYS
+            .iter()
+            .find(|(_, names)| names.contains(&name))
+            .map(|(weekday, _)| *weekday)?;
+
+        // the one before today, a week back when today is that day
+        let back =
+            (today.weekday().num_days_from_monday() + 7 - weekday.num_days_from_monday()) % 7;
+        let back = if back == 0 { 7 } else { back };
+        return today.checked_sub_days(Days::new(back as u64));
+    }
+
+    if let Some(caps) = DAY_FIRST.captures(&text) {
+        return ymd(&caps[3], &caps[2], &caps[1]);
+    }
+
+    if let Some(caps) = DAY_MONTH_NAME.captures(&text) {
+        let month = month(&caps[2])?.to_string();
+        return ymd(&caps[3], &month, &caps[1]);
+    }
+
+    if let Some(caps) = MONTH_NAME_DAY.captures(&text) {
+        let month = month(&caps[1])?.to_string();
+        return ymd(&caps[3], &month, &caps[2]);
+    }
+
+    None
+}
+
+/// Whether a word not reading as a date was still meant as one, e.g. `2022-13-01`.
+pub(crate) fn looks_like_date(word: &str) -> bool {
+    word.starts_with(|ch: char| ch.is_ascii_digit() || ch == '-')
+}
+
+/// Day of historical rates typed as `text`, none for today's, which are the latest rates.
+///
+/// Weekends have no rates of their own, they move to the Friday before with a note in `notes`.
+pub(crate) fn historical_date(
+    text: &str,
+    today: NaiveDate,
+    notes: &mut Vec<Localized>,
+) -> Result<Option<DateTime<Utc>>, Localized> {
+    let text = text.trim();
+    let date =
+        parse_date(text, today).ok_or_else(|| Localized::new("date.invalid").arg("date", text))?;
+
+    if date > today {
+        return Err(Localized::new("date.future")
+            .arg("date", date.format("%Y-%m-%d"))
+            .arg("today", today.format("%Y-%m-%d")));
+    }
+    if date == today {
+        return Ok(None);
+    }
+
+    let trading = last_trading_day(date);
+    if trading != date {
+        notes.push(
+            Localized::new("date.weekend")
+                .arg("date", date.format("%Y-%m-%d"))
+                .arg("trading", trading.format("%Y-%m-%d")),
+        );
+    }
+
+    Ok(trading.and_hms_opt(0, 0, 0).map(|date| date.and_utc()))
+}
+
+/// Weekday on or before `date`, when markets are open.
+pub(crate) fn last_trading_day(date: NaiveDate) -> NaiveDate {
+    let back = match date.weekday() {
+        Weekday::Sat => 1,
+        Weekday::Sun => 2,
+        _ => 0,
+    };
+
+    date - Days::new(back)
+}
+
+fn ago(today: NaiveDate, count: u32, unit: &str) -> Option<NaiveDate> {
+    match unit {
+        "d" => today.checked_sub_days(Days::new(count as u64)),
+        "w" => today.checked_sub_days(Days::new(count as u64 * 7)),
+        "m" => today.checked_sub_months(Months::new(count)),
+        "y" => today.checked_sub_months(Months::new(count.checked_mul(12)?)),
+        _ => None,
+    }
+}
+
+fn month(name: &str) -> Option<usize> {
+    MONTHS
+        .iter()
+        .position(|names| names.contains(&name))
+        .map(|index| index + 1)
+}
+
+fn ymd(year: &str, month: &str, day: &str) -> Option<NaiveDate> {
+    NaiveDate::from_ymd_opt(year.parse().ok()?, month.parse().ok()?, day.parse().ok()?)
+}
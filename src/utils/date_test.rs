@@ -0,0 +1,124 @@
+use chrono::NaiveDate;
+
+use crate::i18n::Lang;
+use crate::utils::date::{historical_date, last_trading_day, looks_like_date, parse_date};
+
+fn date(text: &str) -> NaiveDate {
+    NaiveDate::parse_from_str(text, "%Y-%m-%d").unwrap()
+}
+
+// a sunday
+fn today() -> NaiveDate {
+    date("2026-10-18")
+}
+
+#[test]
+fn absolute_dates() {
+    for text in [
+        "2026-10-17",
+        "17/10/2026",
+        "17.10.2026",
+        "17 Okt 2026",
+        "17 oct. 2026",
+        "17 Oktober 2026",
+        "October 17, 2026",
+        "  17   OCT   2026 ",
+    ] {
+        assert_eq!(
+            Some(date("2026-10-17")),
+            parse_date(text, today()),
+            "{}",
+            text
+        );
+    }
+
+    assert_eq!(Some(date("2024-05-01")), parse_date("1 mei 2024", today()));
+    assert_eq!(
+        Some(date("2024-08-17")),
+        parse_date("17 agustus 2024", today())
+    );
+
+    for text in [
+        "2022-13-01",
+        "31/02/2024",
+        "17 okb 2026",
+        "02-02-2022",
+        "2022/02/02",
+        "wrongdate",
+    ] {
+        assert_eq!(None, parse_date(text, today()), "{}", text);
+    }
+}
+
+#[test]
+fn relative_dates() {
+    for (text, expected) in [
+        ("today", "2026-10-18"),
+        ("hari ini", "2026-10-18"),
+        ("yesterday", "2026-10-17"),
+        ("Kemarin", "2026-10-17"),
+        ("kemarin lusa", "2026-10-16"),
+        ("-7d", "2026-10-11"),
+        ("-2w", "2026-10-04"),
+        ("-1m", "2026-09-18"),
+        ("-1y", "2025-10-18"),
+        ("2 weeks ago", "2026-10-04"),
+        ("3 days ago", "2026-10-15"),
+        ("1 bulan lalu", "2026-09-18"),
+        ("2 minggu yang lalu", "2026-10-04"),
+        ("last friday", "2026-10-16"),
+        ("jumat lalu", "2026-10-16"),
+        ("jum'at kemarin", "2026-10-16"),
+        // a week back when today is that day
+        ("last sunday", "2026-10-11"),
+        ("minggu lalu", "2026-10-11"),
+    ] {
+        assert_eq!(Some(date(expected)), parse_date(text, today()), "{}", text);
+    }
+
+    assert_eq!(None, parse_date("2 fortnights ago", today()));
+    assert_eq!(None, parse_date("last payday", today()));
+}
+
+#[test]
+fn weekends_move_to_friday() {
+    assert_eq!(date("2026-10-16"), last_trading_day(date("2026-10-17")));
+    assert_eq!(date("2026-10-16"), last_trading_day(date("2026-10-18")));
+    assert_eq!(date("2026-10-19"), last_trading_day(date("2026-10-19")));
+
+    let mut notes = vec![];
+    let ret = historical_date("kemarin", today(), &mut notes).unwrap();
+    assert_eq!(Some(date("2026-10-16")), ret.map(|at| at.date_naive()));
+    assert_eq!(
+        "<i>2026-10-17 is a weekend, showing rates of the last trading day, 2026-10-16.</i>",
+        notes[0].render(Lang::En)
+    );
+
+    let mut notes = vec![];
+    let ret = historical_date("2 weeks ago", date("2026-10-21"), &mut notes).unwrap();
+    assert_eq!(Some(date("2026-10-07")), ret.map(|at| at.date_naive()));
+    assert!(notes.is_empty());
+}
+
+#[test]
+fn today_is_latest_and_future_is_rejected() {
+    let mut notes = vec![];
+    assert_eq!(None, historical_date("today", today(), &mut notes).unwrap());
+
+    let err = historical_date("2026-10-19", today(), &mut notes).unwrap_err();
+    assert_eq!(
+        "2026-10-19 is in the future, historical rates go up to today, 2026-10-18.",
+        err.render(Lang::En)
+    );
+
+    let err = historical_date("soon", today(), &mut notes).unwrap_err();
+    assert!(err.render(Lang::En).starts_with("Invalid date soon, e.g."));
+    assert!(notes.is_empty());
+}
+
+#[test]
+fn words_looking_like_dates() {
+    assert!(looks_like_date("2022-13-01"));
+    assert!(looks_like_date("-7x"));
+    assert!(!looks_like_date("yen"));
+}
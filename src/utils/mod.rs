@@ -1 +1,15 @@
+pub(crate) mod currency;
+#[cfg(test)]
+mod currency_test;
+pub(crate) mod date;
+#[cfg(test)]
+mod date_test;
 pub(crate) mod money;
+#[cfg(test)]
+mod money_test;
+pub(crate) mod syntax;
+#[cfg(test)]
+mod syntax_test;
+pub(crate) mod time;
+#[cfg(test)]
+mod time_test;
@@ -1,16 +1,178 @@
 use std::str::FromStr;
 
-use accounting::Accounting;
 use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
 
-pub fn format_money_str(code: &str, money_str: &str) -> String {
-    let mut ac = Accounting::new_from_seperator(code, 2, ",", ".");
+use crate::i18n::{Lang, Localized, format_number};
+use crate::utils::currency::{currency, is_crypto};
 
-    ac.set_format("{s} {v}");
+// decimals of codes missing from the registry
+const DEFAULT_MINOR_UNITS: u32 = 2;
 
-    let amount = Decimal::from_str(money_str).unwrap_or(dec!(0));
-    let money_display = ac.format_money(amount);
+// decimals of compact amounts, e.g. 1.5M
+const COMPACT_DECIMALS: u32 = 1;
 
-    money_display
+// significant digits kept of amounts below one, e.g. 0.00000123 of PEPE in USD
+const SMALL_SIGNIFICANT_DIGITS: u32 = 3;
+
+// decimals always shown of crypto amounts
+const CRYPTO_MIN_DECIMALS: u32 = 2;
+
+// rust_decimal keeps at most 28 decimals
+const MAX_DECIMALS: u32 = 28;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum MoneyStyle {
+    /// `Rp1,500,000.00`, or `XDR 1,500,000.00` when there's no symbol
+    Symbol,
+
+    /// `Rp1.5M`
+    Compact,
+}
+
+/// An amount of a currency, formatted with the currency's minor units and the reader's separators.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Money {
+    pub code: String,
+    pub amount: Decimal,
+
+    // symbol overriding the registry one, e.g. from an upstream answer
+    pub symbol: Option<String>,
+}
+
+impl Money {
+    pub(crate) fn new(code: &str, amount: Decimal) -> Self {
+        Money {
+            code: code.to_ascii_uppercase(),
+            amount,
+            symbol: None,
+        }
+    }
+
+    /// Money from an amount written like upstream does, with `,` thousands and `.` decimal separators.
+    pub(crate) fn parse(code: &str, amount: &str) -> Result<Self, Localized> {
+        let value = Decimal::from_str(&amount.trim().replace(',', ""))
+            .map_err(|_| Localized::new("money.invalid_amount").arg("amount", amount))?;
+
+        Ok(Money::new(code, value))
+    }
+
+    /// Use the symbol of an upstream formatted amount, e.g. `Rp` of `Rp1,573,000.00`.
+    pub(crate) fn with_symbol_of(mut self, formatted: &str) -> Self {
+        let symbol = formatted
+            .split(|ch: char| ch.is_ascii_digit() || ch == '-')
+            .next()
+            .unwrap_or_default()
+            .trim();
+
+        // answers without symbols repeat the code
+        if !symbol.is_empty() && !symbol.eq_ignore_ascii_case(&self.code) {
+            self.symbol = Some(symbol.to_string());
+        }
+
+        self
+    }
+
+    pub(crate) fn minor_units(&self) -> u32 {
+        currency(&self.code).map_or(DEFAULT_MINOR_UNITS, |currency| currency.minor_units)
+    }
+
+    /// The amount without code or symbol, e.g. `1,500.00`.
+    pub(crate) fn format_value(&self, lang: Lang) -> String {
+        // crypto amounts are rarely whole, trailing zeros of their long precision say nothing
+        let min_decimals = if is_crypto(&self.code) {
+            CRYPTO_MIN_DECIMALS.min(self.minor_units())
+        } else {
+            self.minor_units()
+        };
+
+        format_amount(lang, self.amount, min_decimals, self.minor_units())
+    }
+
+    fn symbol(&self) -> Option<&str> {
+        match &self.symbol {
+            Some(symbol) => Some(symbol),
+            None => currency(&self.code)
+                .map(|currency| currency.symbol)
+                .filter(|symbol| *symbol != self.code),
+        }
+    }
+
+    pub(crate) fn format(&self, lang: Lang, style: MoneyStyle) -> String {
+        let value = match style {
+            MoneyStyle::Compact => compact_number(lang, self.amount.abs(), self.minor_units()),
+            MoneyStyle::Symbol => Money::new(&self.code, self.amount.abs()).format_value(lang),
+        };
+        // sign goes before the symbol, -Rp5.00
+        let sign = if self.amount.is_sign_negative()
+            && value.chars().any(|ch| ('1'..='9').contains(&ch))
+        {
+            "-"
+        } else {
+            ""
+        };
+
+        match self.symbol() {
+            Some(symbol) => format!("{}{}{}", sign, symbol, value),
+            None => format!("{} {}{}", self.code, sign, value),
+        }
+    }
+}
+
+// thousands and above shortened with the reader's suffixes, smaller amounts keep the minor units
+fn compact_number(lang: Lang, value: Decimal, minor_units: u32) -> String {
+    let scales = [
+        (
+            Decimal::from(1_000_000_000_000u64),
+            "money.compact.trillions",
+        ),
+        (Decimal::from(1_000_000_000u64), "money.compact.billions"),
+        (Decimal::from(1_000_000u64), "money.compact.millions"),
+        (Decimal::from(1_000u64), "money.compact.thousands"),
+    ];
+
+    for (scale, key) in scales {
+        if value >= scale {
+            let scaled = format_number(lang, value / scale, COMPACT_DECIMALS);
+            let (_, decimal_sep) = lang.separators();
+            let scaled = scaled
+                .strip_suffix(&format!("{}0", decimal_sep))
+                .unwrap_or(&scaled);
+
+            return Localized::new(key).arg("value", scaled).render(lang);
+        }
+    }
+
+    format_number(lang, value, minor_units)
+}
+
+/// Format `value` with `min_decimals` to `max_decimals` decimals, more for amounts below one so they don't round to zero.
+fn format_amount(lang: Lang, value: Decimal, min_decimals: u32, max_decimals: u32) -> String {
+    let mut decimals = max_decimals;
+    if !value.is_zero() && value.abs() < Decimal::ONE {
+        // decimals up to the first significant digit, e.g. 6 of 0.0000012
+        let mut leading = 0;
+        let mut scaled = value.abs();
+        while scaled < Decimal::ONE && leading < MAX_DECIMALS {
+            scaled *= Decimal::TEN;
+            leading += 1;
+        }
+        decimals = (leading + SMALL_SIGNIFICANT_DIGITS - 1)
+            .max(max_decimals)
+            .min(MAX_DECIMALS);
+    }
+
+    // digits past the minimum only while they say something
+    let formatted = format_number(lang, value, decimals);
+    let (_, decimal_sep) = lang.separators();
+    let Some((int_part, frac_part)) = formatted.split_once(decimal_sep) else {
+        return formatted;
+    };
+    let trimmed = frac_part.trim_end_matches('0');
+    let frac_part = &frac_part[..trimmed.len().max(min_decimals as usize)];
+
+    if frac_part.is_empty() {
+        int_part.to_string()
+    } else {
+        format!("{}{}{}", int_part, decimal_sep, frac_part)
+    }
 }
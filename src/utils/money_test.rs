@@ -0,0 +1,74 @@
+use rust_decimal_macros::dec;
+
+use crate::i18n::Lang;
+use crate::utils::money::{Money, MoneyStyle};
+
+#[test]
+fn decimals_follow_minor_units() {
+    let money = |code, amount| Money::new(code, amount).format(Lang::En, MoneyStyle::Symbol);
+
+    assert_eq!("US$1,500.00", money("usd", dec!(1500)));
+    assert_eq!("¥1,501", money("JPY", dec!(1500.5)));
+    assert_eq!("KD1.235", money("KWD", dec!(1.2345)));
+    assert_eq!("₿0.00012345", money("BTC", dec!(0.00012345)));
+    assert_eq!("-€5.00", money("EUR", dec!(-5)));
+    assert_eq!("-€0.001", money("EUR", dec!(-0.001)));
+
+    // no symbol, or not in the registry
+    assert_eq!("XDR 10.00", money("XDR", dec!(10)));
+    assert_eq!("ABC 10.00", money("ABC", dec!(10)));
+}
+
+#[test]
+fn small_amounts_keep_significant_digits() {
+    let money = |code, amount| Money::new(code, amount).format(Lang::En, MoneyStyle::Symbol);
+
+    assert_eq!("US$0.50", money("USD", dec!(0.5)));
+    assert_eq!("US$0.0000012", money("USD", dec!(0.0000012)));
+    assert_eq!(
+        "₿0.0000000000176",
+        money("BTC", dec!(0.0000000000176470588))
+    );
+
+    // crypto drops trailing zeros of its precision
+    assert_eq!("₿1.00", money("BTC", dec!(1)));
+    assert_eq!("PEPE 1,000,000.50", money("PEPE", dec!(1000000.5)));
+    assert_eq!("USDT 0.123457", money("USDT", dec!(0.1234567)));
+}
+
+#[test]
+fn separators_follow_locale() {
+    let money = Money::new("IDR", dec!(1500000));
+
+    assert_eq!("Rp1,500,000.00", money.format(Lang::En, MoneyStyle::Symbol));
+    assert_eq!("Rp1.500.000,00", money.format(Lang::Id, MoneyStyle::Symbol));
+}
+
+#[test]
+fn compact_amounts() {
+    let compact = |amount, lang| Money::new("IDR", amount).format(lang, MoneyStyle::Compact);
+
+    assert_eq!("Rp1,5 jt", compact(dec!(1500000), Lang::Id));
+    assert_eq!("Rp1.5M", compact(dec!(1500000), Lang::En));
+    assert_eq!("Rp2 rb", compact(dec!(2000), Lang::Id));
+    assert_eq!("Rp3.2B", compact(dec!(3210000000), Lang::En));
+    assert_eq!("Rp999.00", compact(dec!(999), Lang::En));
+}
+
+#[test]
+fn upstream_amounts_and_symbols() {
+    let money = Money::parse("IDR", "1,573,000.00")
+        .unwrap()
+        .with_symbol_of("Rp1,573,000.00");
+    assert_eq!(dec!(1573000), money.amount);
+    assert_eq!(Some("Rp".to_string()), money.symbol);
+
+    // symbol-less answers repeat the code
+    let money = Money::parse("IDR", "1").unwrap().with_symbol_of("IDR 1.00");
+    assert_eq!(None, money.symbol);
+
+    assert_eq!(
+        "Invalid amount \"12abc\".",
+        Money::parse("IDR", "12abc").unwrap_err().render(Lang::En)
+    );
+}
@@ -0,0 +1,641 @@
+//! Declared syntax of command arguments: typed values, separators, fixed words, optional parts and parts in any order,
+//! in one or more forms. The first form matching wins, otherwise the error reached farthest into the arguments is
+//! shown, pointing at where it happened, with the usage of its form. Usage lines also head `/help <command>`.
+use chrono::{DateTime, NaiveDate, Utc};
+use teloxide::utils::html;
+
+use crate::i18n::{Lang, Localized, t};
+use crate::utils::currency::{looks_like_code, read_currency};
+use crate::utils::date::{MAX_DATE_WORDS, historical_date, looks_like_date};
+
+// most words of a currency name, `new taiwan dollar`
+const MAX_CURRENCY_WORDS: usize = 4;
+
+/// Currency code, name or symbol, read as its code.
+pub(crate) static CURRENCY: Kind = Kind {
+    max_words: MAX_CURRENCY_WORDS,
+    spans_separators: false,
+    parse: |text, _, notes| {
+        read_currency(text, notes, || {
+            Localized::new("syntax.currency").arg("value", text)
+        })
+        .map(Value::Text)
+    },
+    claims: looks_like_code,
+};
+
+/// Day of historical rates, none for today's.
+pub(crate) static DATE: Kind = Kind {
+    max_words: MAX_DATE_WORDS,
+    // 17/10/2026
+    spans_separators: true,
+    parse: |text, ctx, notes| historical_date(text, ctx.today, notes).map(Value::Date),
+    claims: looks_like_date,
+};
+
+/// What arguments are read against.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Context {
+    /// Day the command was sent, relative dates count from it.
+    pub today: NaiveDate,
+}
+
+/// Value of an argument.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum Value {
+    Text(String),
+    List(Vec<String>),
+
+    // none for today
+    Date(Option<DateTime<Utc>>),
+
+    Range(NaiveDate, NaiveDate),
+
+    // first word of the choice taken
+    Word(&'static str),
+}
+
+/// Kind of value an argument takes.
+#[derive(Debug)]
+pub(crate) struct Kind {
+    /// Most words a value spans, e.g. 4 of `2 minggu yang lalu`.
+    pub max_words: usize,
+
+    /// Whether a value may go over separators of the syntax, like the slashes of `17/10/2026`.
+    pub spans_separators: bool,
+
+    /// Value of `text`, keeping in notes how it was read when that isn't obvious.
+    pub parse: fn(&str, &Context, &mut Vec<Localized>) -> Result<Value, Localized>,
+
+    /// Whether `text` was meant as this kind even though it failed, so its error beats others at the same place.
+    pub claims: fn(&str) -> bool,
+}
+
+/// Part of a form.
+#[derive(Debug, Clone)]
+pub(crate) enum Part {
+    /// Value of `kind` under a name, shown as `<name>` from `syntax.arg.<name>`.
+    Arg(&'static str, &'static Kind),
+
+    /// One of the choices under a name, each a word and its aliases, e.g. `az|strength`.
+    Word(&'static str, &'static [&'static [&'static str]]),
+
+    /// Separator character, like `;` or the `/` of a pair.
+    Sep(char),
+
+    Optional(Vec<Part>),
+
+    /// Parts in any order, each at most once. Optional ones may be left out.
+    AnyOrder(Vec<Part>),
+}
+
+/// Forms of arguments of a command.
+#[derive(Debug)]
+pub(crate) struct Syntax {
+    command: &'static str,
+    forms: Vec<(&'static str, Vec<Part>)>,
+}
+
+impl Syntax {
+    pub(crate) fn new(command: &'static str) -> Self {
+        Syntax {
+            command,
+            forms: vec![],
+        }
+    }
+
+    /// Adds a form under `name`, forms are tried in the order they're added. Several forms may share a name.
+    pub(crate) fn form(mut self, name: &'static str, parts: Vec<Part>) -> Self {
+        self.forms.push((name, parts));
+        self
+    }
+
+    /// Usage line of each form, e.g. `/forex <from>/<to> [date]`.
+    pub(crate) fn usage(&self, lang: Lang) -> Vec<String> {
+        self.forms
+            .iter()
+            .map(|(_, parts)| usage_line(self.command, parts, lang))
+            .collect()
+    }
+
+    /// Values of `input` in the first form it matches.
+    pub(crate) fn parse(&self, input: &str, ctx: &Context) -> Result<Matched, SyntaxError> {
+        self.parse_forms(input, ctx, |_| true)
+    }
+
+    /// Values of `input` in forms named `name` only.
+    pub(crate) fn parse_form(
+        &self,
+        name: &str,
+        input: &str,
+        ctx: &Context,
+    ) -> Result<Matched, SyntaxError> {
+        self.parse_forms(input, ctx, |form| form == name)
+    }
+
+    fn parse_forms(
+        &self,
+        input: &str,
+        ctx: &Context,
+        wanted: impl Fn(&str) -> bool,
+    ) -> Result<Matched, SyntaxError> {
+        let input = input.trim();
+        let mut matcher = Matcher {
+            input,
+            separators: self.separators(),
+            ctx,
+            form: 0,
+            failure: None,
+        };
+
+        for (idx, (name, parts)) in self.forms.iter().enumerate() {
+            if !wanted(name) {
+                continue;
+            }
+            matcher.form = idx;
+            let matched = Matched {
+                form: name,
+                values: vec![],
+                notes: vec![],
+            };
+            let pending = parts.iter().rev().map(Pending::Part).collect();
+            if let Some(matched) = matcher.run(pending, 0, matched) {
+                return Ok(matched);
+            }
+        }
+
+        let failure = matcher.failure.unwrap_or(Failure {
+            at: 0,
+            claimed: false,
+            problem: Problem::Unexpected(input.into()),
+            form: 0,
+        });
+        Err(SyntaxError {
+            problem: Box::new(failure.problem),
+            input: input.into(),
+            at: failure.at,
+            command: self.command,
+            form: self
+                .forms
+                .get(failure.form)
+                .map(|(_, parts)| parts.clone())
+                .unwrap_or_default(),
+        })
+    }
+
+    fn separators(&self) -> Vec<char> {
+        fn collect(parts: &[Part], ret: &mut Vec<char>) {
+            for part in parts {
+                match part {
+                    Part::Sep(sep) if !ret.contains(sep) => ret.push(*sep),
+                    Part::Optional(parts) | Part::AnyOrder(parts) => collect(parts, ret),
+                    _ => {}
+                }
+            }
+        }
+
+        let mut ret = vec![];
+        for (_, parts) in &self.forms {
+            collect(parts, &mut ret);
+        }
+        ret
+    }
+}
+
+/// Arguments matching a form.
+#[derive(Debug, Clone)]
+pub(crate) struct Matched {
+    form: &'static str,
+    values: Vec<(&'static str, Value)>,
+
+    /// How values were read, e.g. an ambiguous currency name.
+    pub notes: Vec<Localized>,
+}
+
+impl Matched {
+    /// Name of the form matched.
+    pub(crate) fn form(&self) -> &'static str {
+        self.form
+    }
+
+    fn value(&self, name: &str) -> Option<&Value> {
+        self.values
+            .iter()
+            .find(|(value_name, _)| *value_name == name)
+            .map(|(_, value)| value)
+    }
+
+    /// Text value under `name`, empty when the form has none.
+    pub(crate) fn text(&self, name: &str) -> String {
+        match self.value(name) {
+            Some(Value::Text(text)) => text.clone(),
+            _ => String::new(),
+        }
+    }
+
+    /// List under `name`, empty when left out.
+    pub(crate) fn list(&self, name: &str) -> Vec<String> {
+        match self.value(name) {
+            Some(Value::List(list)) => list.clone(),
+            _ => vec![],
+        }
+    }
+
+    /// Date under `name`, none when left out or today.
+    pub(crate) fn date(&self, name: &str) -> Option<DateTime<Utc>> {
+        match self.value(name) {
+            Some(Value::Date(date)) => *date,
+            _ => None,
+        }
+    }
+
+    pub(crate) fn range(&self, name: &str) -> Option<(NaiveDate, NaiveDate)> {
+        match self.value(name) {
+            Some(Value::Range(start, end)) => Some((*start, *end)),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn word(&self, name: &str) -> Option<&'static str> {
+        match self.value(name) {
+            Some(Value::Word(word)) => Some(word),
+            _ => None,
+        }
+    }
+}
+
+// what went wrong at a place
+#[derive(Debug, Clone)]
+enum Problem {
+    // a value that didn't parse
+    Invalid(Localized),
+
+    // something else should be here, the word found or empty at the end
+    Expected(Part, String),
+
+    // a word after a complete form
+    Unexpected(String),
+}
+
+/// Arguments matching no form, pointing at the error reached farthest.
+#[derive(Debug, Clone)]
+pub(crate) struct SyntaxError {
+    problem: Box<Problem>,
+
+    // arguments as sent, the error is at byte `at`
+    input: String,
+    at: usize,
+
+    command: &'static str,
+
+    // parts of the form the error came from
+    form: Vec<Part>,
+}
+
+impl SyntaxError {
+    /// The error alone, without where it is.
+    pub(crate) fn message(&self, lang: Lang) -> String {
+        match self.problem.as_ref() {
+            Problem::Invalid(err) => err.render(lang),
+            Problem::Expected(part, got) if got.is_empty() => Localized::new("syntax.missing")
+                .arg("expected", render_part(part, lang, false))
+                .render(lang),
+            Problem::Expected(part, got) => Localized::new("syntax.expected")
+                .arg("expected", render_part(part, lang, false))
+                .arg("got", got)
+                .render(lang),
+            Problem::Unexpected(got) => Localized::new("syntax.unexpected")
+                .arg("got", got)
+                .render(lang),
+        }
+    }
+
+    /// Arguments with a caret under the error, as two lines. Empty without arguments.
+    pub(crate) fn pointer(&self) -> String {
+        if self.input.is_empty() {
+            return String::new();
+        }
+
+        let column = self.input[..self.at].chars().count();
+        format!("{}\n{}^", self.input, " ".repeat(column))
+    }
+
+    /// Usage of the form the error came from.
+    pub(crate) fn usage(&self, lang: Lang) -> String {
+        Localized::new("syntax.usage")
+            .arg("usage", usage_line(self.command, &self.form, lang))
+            .render(lang)
+    }
+
+    pub(crate) fn render(&self, lang: Lang) -> String {
+        let pointer = self.pointer();
+        if pointer.is_empty() {
+            return format!("{}\n{}", self.message(lang), self.usage(lang));
+        }
+
+        format!("{}\n{}\n{}", self.message(lang), pointer, self.usage(lang))
+    }
+
+    /// Same as `render`, with the caret lined up in a monospace block.
+    pub(crate) fn render_html(&self, lang: Lang) -> String {
+        let pointer = self.pointer();
+        let pointer = if pointer.is_empty() {
+            String::new()
+        } else {
+            format!("<pre>{}</pre>", html::escape(&pointer))
+        };
+
+        format!(
+            "{}\n{}{}",
+            html::escape(&self.message(lang)),
+            pointer,
+            html::escape(&self.usage(lang))
+        )
+    }
+}
+
+impl std::fmt::Display for SyntaxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.render(Lang::En))
+    }
+}
+
+impl std::error::Error for SyntaxError {}
+
+fn usage_line(command: &str, parts: &[Part], lang: Lang) -> String {
+    let parts = render_parts(parts, lang, false);
+    if parts.is_empty() {
+        return format!("/{}", command);
+    }
+
+    format!("/{} {}", command, parts)
+}
+
+fn render_parts(parts: &[Part], lang: Lang, bare: bool) -> String {
+    let mut ret = String::new();
+    for part in parts {
+        // separators stick to what's before them, and what follows a pair's slash to it
+        let glued = ret.is_empty() || matches!(part, Part::Sep(_)) || ret.ends_with('/');
+        if !glued {
+            ret.push(' ');
+        }
+        ret.push_str(&render_part(part, lang, bare));
+    }
+
+    ret
+}
+
+// `bare` leaves out angle brackets, inside square ones: [date]
+fn render_part(part: &Part, lang: Lang, bare: bool) -> String {
+    match part {
+        Part::Arg(name, _) => {
+            let key = format!("syntax.arg.{}", name);
+            let name = t(lang, &key);
+            if bare {
+                name.to_string()
+            } else {
+                format!("<{}>", name)
+            }
+        }
+        Part::Word(_, choices) => choices
+            .iter()
+            .filter_map(|aliases| aliases.first())
+            .copied()
+            .collect::<Vec<_>>()
+            .join("|"),
+        Part::Sep(sep) => sep.to_string(),
+        Part::Optional(parts) => format!("[{}]", render_parts(parts, lang, true)),
+        Part::AnyOrder(parts) => render_parts(parts, lang, bare),
+    }
+}
+
+// error reached so far
+struct Failure {
+    at: usize,
+    claimed: bool,
+    problem: Problem,
+    form: usize,
+}
+
+// parts still to match, with the ones of an any order group already matched
+#[derive(Clone)]
+enum Pending<'p> {
+    Part(&'p Part),
+    AnyOrder(&'p [Part], u64),
+}
+
+struct Matcher<'a> {
+    input: &'a str,
+    separators: Vec<char>,
+    ctx: &'a Context,
+    form: usize,
+    failure: Option<Failure>,
+}
+
+impl<'a> Matcher<'a> {
+    // matches `pending`, next part last, from byte `at`
+    fn run<'p>(
+        &mut self,
+        mut pending: Vec<Pending<'p>>,
+        at: usize,
+        mut matched: Matched,
+    ) -> Option<Matched> {
+        let at = self.skip_spaces(at);
+        let Some(next) = pending.pop() else {
+            if at == self.input.len() {
+                return Some(matched);
+            }
+            self.fail(at, Problem::Unexpected(self.word_at(at).into()), false);
+            return None;
+        };
+
+        let part = match next {
+            Pending::Part(part) => part,
+            Pending::AnyOrder(parts, used) => {
+                return self.any_order(pending, parts, used, at, matched);
+            }
+        };
+
+        match part {
+            Part::Sep(sep) => {
+                if self.input[at..].starts_with(*sep) {
+                    return self.run(pending, at + sep.len_utf8(), matched);
+                }
+                self.expected(part, at);
+                None
+            }
+            Part::Word(name, choices) => {
+                let word = self.word_at(at);
+                let choice = choices.iter().find(|aliases| {
+                    aliases
+                        .iter()
+                        .any(|alias| !word.is_empty() && alias.eq_ignore_ascii_case(word))
+                });
+                match choice.and_then(|aliases| aliases.first()) {
+                    Some(first) => {
+                        matched.values.push((name, Value::Word(first)));
+                        self.run(pending, at + word.len(), matched)
+                    }
+                    None => {
+                        self.expected(part, at);
+                        None
+                    }
+                }
+            }
+            Part::Arg(name, kind) => {
+                let ends = self.ends(at, kind);
+                if ends.is_empty() {
+                    self.expected(part, at);
+                    return None;
+                }
+
+                for end in ends {
+                    let text = &self.input[at..end];
+                    let mut notes = vec![];
+                    match (kind.parse)(text, self.ctx, &mut notes) {
+                        Ok(value) => {
+                            let mut next = matched.clone();
+                            next.values.push((name, value));
+                            next.notes.extend(notes);
+                            if let Some(done) = self.run(pending.clone(), end, next) {
+                                return Some(done);
+                            }
+                        }
+                        Err(err) => self.fail(at, Problem::Invalid(err), (kind.claims)(text)),
+                    }
+                }
+                None
+            }
+            Part::Optional(parts) => {
+                let mut with = pending.clone();
+                with.extend(parts.iter().rev().map(Pending::Part));
+                if let Some(done) = self.run(with, at, matched.clone()) {
+                    return Some(done);
+                }
+                self.run(pending, at, matched)
+            }
+            Part::AnyOrder(parts) => self.any_order(pending, parts, 0, at, matched),
+        }
+    }
+
+    fn any_order<'p>(
+        &mut self,
+        pending: Vec<Pending<'p>>,
+        parts: &'p [Part],
+        used: u64,
+        at: usize,
+        matched: Matched,
+    ) -> Option<Matched> {
+        for (idx, part) in parts.iter().enumerate() {
+            if used & (1 << idx) != 0 {
+                continue;
+            }
+
+            let mut next = pending.clone();
+            next.push(Pending::AnyOrder(parts, used | (1 << idx)));
+            // an optional part is matched here or later, leaving it out is up to the end of the group
+            let inner = match part {
+                Part::Optional(inner) => inner.as_slice(),
+                part => std::slice::from_ref(part),
+            };
+            next.extend(inner.iter().rev().map(Pending::Part));
+            if let Some(done) = self.run(next, at, matched.clone()) {
+                return Some(done);
+            }
+        }
+
+        let left_out_optional = parts
+            .iter()
+            .enumerate()
+            .all(|(idx, part)| used & (1 << idx) != 0 || matches!(part, Part::Optional(_)));
+        if left_out_optional {
+            return self.run(pending, at, matched);
+        }
+        None
+    }
+
+    fn skip_spaces(&self, at: usize) -> usize {
+        let rest = &self.input[at..];
+        at + rest.len() - rest.trim_start().len()
+    }
+
+    // word at `at`, up to a space or separator, or the separator itself
+    fn word_at(&self, at: usize) -> &'a str {
+        let rest = &self.input[at..];
+        match rest.chars().next() {
+            Some(ch) if self.separators.contains(&ch) => &rest[..ch.len_utf8()],
+            _ => {
+                let end = rest
+                    .find(|ch: char| ch.is_whitespace() || self.separators.contains(&ch))
+                    .unwrap_or(rest.len());
+                &rest[..end]
+            }
+        }
+    }
+
+    // where a value of `kind` starting at `start` may end, longest first: before a space, before a separator or at
+    // the end, within the words it takes
+    fn ends(&self, start: usize, kind: &Kind) -> Vec<usize> {
+        let mut ends = vec![];
+        let mut words = 1;
+        let mut after_space = false;
+        let mut complete = true;
+
+        for (idx, ch) in self.input[start..].char_indices() {
+            let idx = start + idx;
+            if ch.is_whitespace() {
+                if !after_space {
+                    ends.push(idx);
+                }
+                after_space = true;
+                continue;
+            }
+            if after_space {
+                words += 1;
+                after_space = false;
+                if words > kind.max_words {
+                    complete = false;
+                    break;
+                }
+            }
+            if self.separators.contains(&ch) {
+                if idx > start && ends.last() != Some(&idx) {
+                    ends.push(idx);
+                }
+                if !kind.spans_separators {
+                    complete = false;
+                    break;
+                }
+            }
+        }
+        if complete && !after_space && start < self.input.len() {
+            ends.push(self.input.len());
+        }
+
+        ends.retain(|end| *end > start);
+        ends.dedup();
+        ends.reverse();
+        ends
+    }
+
+    fn expected(&mut self, part: &Part, at: usize) {
+        let got = self.word_at(at).to_string();
+        self.fail(at, Problem::Expected(part.clone(), got), false);
+    }
+
+    // keeps the error reached farthest, then one from a value meant as its kind, then the first
+    fn fail(&mut self, at: usize, problem: Problem, claimed: bool) {
+        let better = match &self.failure {
+            None => true,
+            Some(failure) => at > failure.at || (at == failure.at && claimed && !failure.claimed),
+        };
+        if better {
+            self.failure = Some(Failure {
+                at,
+                claimed,
+                problem,
+                form: self.form,
+            });
+        }
+    }
+}
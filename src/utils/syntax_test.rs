@@ -0,0 +1,117 @@
+use chrono::NaiveDate;
+
+use crate::handlers::{convert, forex};
+use crate::i18n::Lang;
+use crate::utils::syntax::Context;
+
+// a sunday
+fn ctx() -> Context {
+    Context {
+        today: NaiveDate::from_ymd_opt(2026, 10, 18).unwrap(),
+    }
+}
+
+#[test]
+fn usage_lines_follow_forms() {
+    assert_eq!(
+        vec![
+            "/forex",
+            "/forex <from>/<to> [date]",
+            "/forex <base> [quotes] [date] [az|strength]",
+            "/forex <from>/<to> stats [range]",
+        ],
+        forex::SYNTAX.usage(Lang::En)
+    );
+    assert_eq!(
+        vec![
+            "/convert",
+            "/convert <dari> <jumlah>; <ke> [; tanggal]",
+            "/convert <jumlah> <dari>; <ke> [; tanggal]",
+        ],
+        convert::SYNTAX.usage(Lang::Id)
+    );
+}
+
+#[test]
+fn forms_are_told_apart() {
+    let matched = forex::SYNTAX.parse("", &ctx()).unwrap();
+    assert_eq!("watchlist", matched.form());
+
+    let matched = forex::SYNTAX
+        .parse("yen/rupiah 2 weeks ago", &ctx())
+        .unwrap();
+    assert_eq!("pair", matched.form());
+    assert_eq!("JPY", matched.text("from"));
+    assert_eq!("IDR", matched.text("to"));
+    assert!(matched.date("date").is_some());
+
+    let matched = forex::SYNTAX.parse("USD/IDR stats 30d", &ctx()).unwrap();
+    assert_eq!("stats", matched.form());
+    assert!(matched.range("range").is_some());
+
+    let matched = convert::SYNTAX.parse("100 us dollar; idr", &ctx()).unwrap();
+    assert_eq!("convert", matched.form());
+    assert_eq!("USD", matched.text("from"));
+    assert_eq!("100", matched.text("amount"));
+    assert_eq!("IDR", matched.text("to"));
+}
+
+#[test]
+fn optional_parts_in_any_order() {
+    let matched = forex::SYNTAX
+        .parse("USD strength 2 weeks ago EUR,JPY", &ctx())
+        .unwrap();
+    assert_eq!("rates", matched.form());
+    assert_eq!(vec!["EUR", "JPY"], matched.list("quotes"));
+    assert_eq!(Some("strength"), matched.word("sort"));
+    assert!(matched.date("date").is_some());
+
+    let matched = forex::SYNTAX.parse("USD az", &ctx()).unwrap();
+    assert!(matched.list("quotes").is_empty());
+    assert!(matched.date("date").is_none());
+}
+
+#[test]
+fn dates_may_hold_separators() {
+    let matched = forex::SYNTAX.parse("usd/idr 17/10/2026", &ctx()).unwrap();
+    assert_eq!("IDR", matched.text("to"));
+    assert_eq!(
+        "2026-10-16",
+        matched.date("date").unwrap().format("%Y-%m-%d").to_string()
+    );
+}
+
+#[test]
+fn errors_point_at_the_farthest_mistake() {
+    let err = forex::SYNTAX.parse("USD 2022-13-01", &ctx()).unwrap_err();
+    assert!(err.message(Lang::En).starts_with("Invalid date 2022-13-01"));
+    assert_eq!("USD 2022-13-01\n    ^", err.pointer());
+    assert_eq!(
+        "Usage: /forex <base> [quotes] [date] [az|strength]",
+        err.usage(Lang::En)
+    );
+
+    let err = forex::SYNTAX.parse("USD//IDR", &ctx()).unwrap_err();
+    assert_eq!("Expected <to> here, not \"/\".", err.message(Lang::En));
+    assert_eq!("USD//IDR\n    ^", err.pointer());
+
+    let err = convert::SYNTAX.parse("USD 100", &ctx()).unwrap_err();
+    assert_eq!(
+        "Missing ; at the end.\nUSD 100\n       ^\nUsage: /convert <from> <amount>; <to> [; date]",
+        err.render(Lang::En)
+    );
+
+    let err = convert::SYNTAX
+        .parse("USD 100; IDR; 17/10/2026 x", &ctx())
+        .unwrap_err();
+    assert_eq!("Didn't expect \"x\" here.", err.message(Lang::En));
+}
+
+#[test]
+fn html_errors_keep_the_caret_monospaced() {
+    let err = forex::SYNTAX.parse("USD//IDR", &ctx()).unwrap_err();
+    assert_eq!(
+        "Expected &lt;to&gt; here, not \"/\".\n<pre>USD//IDR\n    ^</pre>Usage: /forex &lt;from&gt;/&lt;to&gt; [date]",
+        err.render_html(Lang::En)
+    );
+}
@@ -0,0 +1,86 @@
+//! Times shown to members, in the timezone of their chat: `Asia/Jakarta` unless set, labelled WIB, WITA or WIT
+//! across Indonesia, from the tz database built into the binary.
+use chrono::{DateTime, NaiveTime, Utc};
+use chrono_tz::{TZ_VARIANTS, Tz};
+
+use crate::config::config;
+use crate::deps::storage::storage;
+use crate::i18n::{Lang, Localized};
+
+// names members know Indonesian timezones by
+static LOCAL_NAMES: &[(&str, Tz)] = &[
+    ("wib", Tz::Asia__Jakarta),
+    ("wita", Tz::Asia__Makassar),
+    ("wit", Tz::Asia__Jayapura),
+];
+
+/// Timezone named `name`: an IANA name like `Asia/Makassar` in any case, or WIB, WITA, WIT.
+pub(crate) fn parse_timezone(name: &str) -> Option<Tz> {
+    let name = name.trim();
+
+    LOCAL_NAMES
+        .iter()
+        .find(|(local, _)| local.eq_ignore_ascii_case(name))
+        .map(|(_, tz)| *tz)
+        .or_else(|| {
+            TZ_VARIANTS
+                .iter()
+                .find(|tz| tz.name().eq_ignore_ascii_case(name))
+                .copied()
+        })
+}
+
+/// Configured timezone of chats without their own.
+pub(crate) fn default_timezone() -> Tz {
+    parse_timezone(&config().default_timezone).unwrap_or(Tz::Asia__Jakarta)
+}
+
+/// Timezone of chat `chat_id`: its setting, then the configured default.
+pub(crate) fn timezone_for(chat_id: i64) -> Tz {
+    match storage().chat_settings(chat_id) {
+        Ok(settings) => {
+            if let Some(tz) = settings.timezone.as_deref().and_then(parse_timezone) {
+                return tz;
+            }
+        }
+        Err(err) => eprintln!("[time] {:#}", err),
+    }
+
+    default_timezone()
+}
+
+/// `at` in `tz` with the zone's abbreviation, e.g. `2022-02-02 07:00:00 WIB`.
+pub(crate) fn format_time(at: DateTime<Utc>, tz: Tz) -> String {
+    at.with_timezone(&tz)
+        .format("%Y-%m-%d %H:%M:%S %Z")
+        .to_string()
+}
+
+/// Time rates are of, like `format_time`. Rates of a past day are stamped at midnight UTC, by upstream or by
+/// `utils::date`, and show as that day alone, as 07:00 WIB would be a time nobody asked for.
+pub(crate) fn format_rates_time(at: DateTime<Utc>, tz: Tz) -> String {
+    if at.time() == NaiveTime::MIN {
+        at.format("%Y-%m-%d").to_string()
+    } else {
+        format_time(at, tz)
+    }
+}
+
+/// How long before `now` `at` was, e.g. `5 minutes ago`.
+pub(crate) fn ago(at: DateTime<Utc>, now: DateTime<Utc>, lang: Lang) -> String {
+    let minutes = (now - at).num_minutes().max(0);
+
+    match minutes {
+        0 => Localized::new("time.ago.now").render(lang),
+        1..60 => Localized::plural("time.ago.minutes", minutes).render(lang),
+        60..2880 => Localized::plural("time.ago.hours", minutes / 60).render(lang),
+        _ => Localized::plural("time.ago.days", minutes / 1440).render(lang),
+    }
+}
+
+/// `updated 5 minutes ago`, of data fetched `at`.
+pub(crate) fn updated_ago(at: DateTime<Utc>, now: DateTime<Utc>, lang: Lang) -> String {
+    Localized::new("time.updated")
+        .arg("ago", ago(at, now, lang))
+        .render(lang)
+}
@@ -0,0 +1,83 @@
+use chrono::{Duration, TimeZone, Utc};
+use chrono_tz::Tz;
+
+use crate::i18n::Lang;
+use crate::utils::time::{
+    ago, format_rates_time, format_time, parse_timezone, timezone_for, updated_ago,
+};
+
+#[test]
+fn timezone_names() {
+    assert_eq!(Some(Tz::Asia__Jakarta), parse_timezone("WIB"));
+    assert_eq!(Some(Tz::Asia__Makassar), parse_timezone("wita"));
+    assert_eq!(Some(Tz::Asia__Jayapura), parse_timezone(" Wit "));
+    assert_eq!(Some(Tz::Asia__Makassar), parse_timezone("asia/makassar"));
+    assert_eq!(Some(Tz::Europe__London), parse_timezone("Europe/London"));
+    assert_eq!(None, parse_timezone("Asia/Bandung"));
+    assert_eq!(None, parse_timezone(""));
+}
+
+#[test]
+fn times_carry_local_labels() {
+    let at = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+
+    assert_eq!(
+        "2022-02-02 07:00:00 WIB",
+        format_time(at, Tz::Asia__Jakarta)
+    );
+    assert_eq!(
+        "2022-02-02 08:00:00 WITA",
+        format_time(at, Tz::Asia__Makassar)
+    );
+    assert_eq!(
+        "2022-02-02 09:00:00 WIT",
+        format_time(at, Tz::Asia__Jayapura)
+    );
+    assert_eq!(
+        "2022-02-02 00:00:00 GMT",
+        format_time(at, Tz::Europe__London)
+    );
+}
+
+#[test]
+fn rates_of_a_day_have_no_time() {
+    let day = Utc.with_ymd_and_hms(2022, 2, 2, 0, 0, 0).unwrap();
+    assert_eq!("2022-02-02", format_rates_time(day, Tz::Asia__Jakarta));
+    assert_eq!("2022-02-02", format_rates_time(day, Tz::America__New_York));
+
+    let at = Utc.with_ymd_and_hms(2022, 2, 2, 9, 30, 0).unwrap();
+    assert_eq!(
+        "2022-02-02 16:30:00 WIB",
+        format_rates_time(at, Tz::Asia__Jakarta)
+    );
+}
+
+#[test]
+fn relative_times() {
+    let now = Utc.with_ymd_and_hms(2026, 10, 18, 12, 0, 0).unwrap();
+    let ago = |minutes, lang| ago(now - Duration::minutes(minutes), now, lang);
+
+    assert_eq!("just now", ago(0, Lang::En));
+    // clocks a little ahead
+    assert_eq!("just now", ago(-3, Lang::En));
+    assert_eq!("1 minute ago", ago(1, Lang::En));
+    assert_eq!("5 minutes ago", ago(5, Lang::En));
+    assert_eq!("5 menit yang lalu", ago(5, Lang::Id));
+    assert_eq!("1 hour ago", ago(60, Lang::En));
+    assert_eq!("47 hours ago", ago(47 * 60, Lang::En));
+    assert_eq!("2 days ago", ago(48 * 60, Lang::En));
+
+    assert_eq!(
+        "updated 5 minutes ago",
+        updated_ago(now - Duration::minutes(5), now, Lang::En)
+    );
+    assert_eq!(
+        "diperbarui 5 menit yang lalu",
+        updated_ago(now - Duration::minutes(5), now, Lang::Id)
+    );
+}
+
+#[test]
+fn chats_without_a_timezone_use_the_default() {
+    assert_eq!(Tz::Asia__Jakarta, timezone_for(-100_400_001));
+}